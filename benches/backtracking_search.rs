@@ -0,0 +1,54 @@
+//! Runs the backtracking search through many nodes, where the per-node
+//! `fetch_add` on the node counter is on the hot path. `n` chunks each delete
+//! one `a` line from a file holding `n - 1` of them, so no placement works and
+//! the search has to try them all: 13,828 nodes for 8 chunks, and the
+//! `MAX_BACKTRACK_NODES` cap for 9. Each case runs once with the patcher's
+//! private counter and once with a shared `BacktrackingOptions::node_counter`.
+
+fn lines_and_chunks(
+    chunk_count: usize,
+) -> (std::vec::Vec<std::string::String>, std::vec::Vec<zenpatch::data::chunk::Chunk>) {
+    let lines = (1..chunk_count).map(|_| "a".to_string()).collect();
+    let mut patch = std::string::String::from("*** Begin Patch\n*** Update File: a.txt\n");
+    for i in 0..chunk_count {
+        patch.push_str(&std::format!("@@\n-a\n+a{i}\n"));
+    }
+    patch.push_str("*** End Patch");
+    let chunks = zenpatch::parser::text_to_patch::text_to_patch(&patch).unwrap().remove(0).chunks;
+    (lines, chunks)
+}
+
+fn search(
+    lines: &[std::string::String],
+    chunks: &[zenpatch::data::chunk::Chunk],
+    options: &zenpatch::applier::backtracking_options::BacktrackingOptions,
+) {
+    let result = zenpatch::applier::backtracking_patcher::apply_patch_backtracking_with_options(
+        criterion::black_box(lines),
+        chunks,
+        zenpatch::applier::whitespace_mode::WhitespaceMode::Strict,
+        options,
+        &mut std::vec::Vec::new(),
+    );
+    assert!(result.is_err());
+}
+
+fn bench_search(c: &mut criterion::Criterion) {
+    let private = zenpatch::applier::backtracking_options::BacktrackingOptions::default();
+    let counter = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let shared = zenpatch::applier::backtracking_options::BacktrackingOptions {
+        node_counter: std::option::Option::Some(counter.clone()),
+        ..std::default::Default::default()
+    };
+    for (chunk_count, label) in [(8, "exhaust 13,828 nodes"), (9, "stop at the node cap")] {
+        let (lines, chunks) = lines_and_chunks(chunk_count);
+        c.bench_function(&std::format!("{label}, private counter"), |b| {
+            b.iter(|| search(&lines, &chunks, &private))
+        });
+        c.bench_function(&std::format!("{label}, shared counter"), |b| b.iter(|| search(&lines, &chunks, &shared)));
+    }
+    assert!(counter.load(std::sync::atomic::Ordering::Relaxed) > 0);
+}
+
+criterion::criterion_group!(benches, bench_search);
+criterion::criterion_main!(benches);
@@ -0,0 +1,48 @@
+//! Applies a 50-hunk patch to a 10,000-line file, where scanning the file for
+//! every hunk's candidate positions dominates. Compare
+//! `cargo bench --bench candidate_positions` with the same command plus
+//! `--features rayon` to measure the parallel scan.
+//!
+//! The repeating-blocks case mirrors `test_large_file_with_many_repeating_lines`:
+//! one hunk whose lines each occur 1,000 times, so only the full context
+//! pins it. Positions are scanned once per (chunk, mode) before the search
+//! starts, so this measures the scan rather than repeated lookups.
+
+fn file_and_patch() -> (zenpatch::Vfs, std::string::String) {
+    let content: std::vec::Vec<std::string::String> = (0..10_000).map(|i| std::format!("line {i}")).collect();
+    let mut patch = std::string::String::from("*** Begin Patch\n*** Update File: big.txt\n");
+    for i in (0..50).map(|n| 100 + n * 195) {
+        patch.push_str(&std::format!("@@\n line {}\n-line {}\n+changed {}\n line {}\n", i - 1, i, i, i + 1));
+    }
+    patch.push_str("*** End Patch");
+
+    let mut vfs = zenpatch::Vfs::new();
+    vfs.insert("big.txt".to_string(), content.join("\n"));
+    (vfs, patch)
+}
+
+fn repeating_blocks() -> (zenpatch::Vfs, std::string::String) {
+    let content: std::vec::Vec<std::string::String> = (0..1000)
+        .map(|i| std::format!("--- Start Block {i} ---\nRepeat A\nRepeat B\nRepeat C\n--- End Block {i} ---"))
+        .collect();
+    let patch = "*** Begin Patch\n*** Update File: repeating.txt\n@@\n --- Start Block 500 ---\n Repeat A\n-Repeat B\n\
+                 +Repeat B - Modified\n Repeat C\n --- End Block 500 ---\n*** End Patch";
+
+    let mut vfs = zenpatch::Vfs::new();
+    vfs.insert("repeating.txt".to_string(), content.join("\n"));
+    (vfs, patch.to_string())
+}
+
+fn bench_apply(c: &mut criterion::Criterion) {
+    let (vfs, patch) = file_and_patch();
+    c.bench_function("apply 50 hunks to 10k lines", |b| {
+        b.iter(|| zenpatch::apply(criterion::black_box(&patch), &vfs).unwrap())
+    });
+    let (vfs, patch) = repeating_blocks();
+    c.bench_function("apply 1 hunk among 1000 repeating blocks", |b| {
+        b.iter(|| zenpatch::apply(criterion::black_box(&patch), &vfs).unwrap())
+    });
+}
+
+criterion::criterion_group!(benches, bench_apply);
+criterion::criterion_main!(benches);
@@ -0,0 +1,70 @@
+//! Heap memory held by a parsed patch that rewrites every line of a
+//! 5,000-line file, in 250 hunks of 20 lines. Before any timing, it prints
+//! the live bytes of the parsed actions as the parser returns them and again
+//! with `del_lines`/`ins_lines` emptied. The second figure is what deriving
+//! them from `lines` (as `del_lines_iter`/`ins_lines_iter` do) would cost.
+//! Run with `cargo bench --bench chunk_memory`.
+
+struct CountingAllocator;
+
+static LIVE_BYTES: std::sync::atomic::AtomicIsize = std::sync::atomic::AtomicIsize::new(0);
+
+unsafe impl std::alloc::GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: std::alloc::Layout) -> *mut u8 {
+        LIVE_BYTES.fetch_add(layout.size() as isize, std::sync::atomic::Ordering::Relaxed);
+        std::alloc::System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: std::alloc::Layout) {
+        LIVE_BYTES.fetch_sub(layout.size() as isize, std::sync::atomic::Ordering::Relaxed);
+        std::alloc::System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: std::alloc::Layout, new_size: usize) -> *mut u8 {
+        LIVE_BYTES.fetch_add(new_size as isize - layout.size() as isize, std::sync::atomic::Ordering::Relaxed);
+        std::alloc::System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn live_bytes() -> isize {
+    LIVE_BYTES.load(std::sync::atomic::Ordering::Relaxed)
+}
+
+/// A patch replacing each of the file's 5,000 lines, 20 lines per hunk.
+fn five_thousand_line_patch() -> std::string::String {
+    let mut patch = std::string::String::from("*** Begin Patch\n*** Update File: big.txt\n");
+    for hunk in 0..250 {
+        patch.push_str("@@\n");
+        for i in hunk * 20..(hunk + 1) * 20 {
+            patch.push_str(&std::format!("-original line number {i} of the file\n"));
+            patch.push_str(&std::format!("+rewritten line number {i} of the file\n"));
+        }
+    }
+    patch.push_str("*** End Patch");
+    patch
+}
+
+fn bench_memory(c: &mut criterion::Criterion) {
+    let patch = five_thousand_line_patch();
+
+    let before = live_bytes();
+    let mut parsed: zenpatch::Patch = patch.parse().unwrap();
+    let with_vectors = live_bytes() - before;
+    for chunk in parsed.0.iter_mut().flat_map(|action| action.chunks.iter_mut()) {
+        chunk.del_lines = std::vec::Vec::new();
+        chunk.ins_lines = std::vec::Vec::new();
+    }
+    let lines_only = live_bytes() - before;
+    std::println!("parsed 5,000-line patch: {with_vectors} bytes as parsed, {lines_only} bytes without del/ins_lines");
+    drop(parsed);
+
+    c.bench_function("parse 5,000-line rewrite", |b| {
+        b.iter(|| criterion::black_box(&patch).parse::<zenpatch::Patch>().unwrap())
+    });
+}
+
+criterion::criterion_group!(benches, bench_memory);
+criterion::criterion_main!(benches);
@@ -0,0 +1,39 @@
+//! Applies a patch of 20 hunks to each of 50 independent 2,000-line files,
+//! sequentially with `apply` and concurrently with `parallel_apply`. Needs the
+//! `rayon` feature: `cargo bench --bench parallel_apply --features rayon`.
+//! The speedup depends on the number of cores.
+
+fn files_and_patch() -> (zenpatch::Vfs, std::string::String) {
+    let mut vfs = zenpatch::Vfs::new();
+    let mut patch = std::string::String::from("*** Begin Patch\n");
+    for file in 0..50 {
+        let path = std::format!("src/module_{file}.rs");
+        let content: std::vec::Vec<std::string::String> =
+            (0..2_000).map(|i| std::format!("let value_{i} = {file};")).collect();
+        vfs.insert(path.clone(), content.join("\n"));
+
+        patch.push_str(&std::format!("*** Update File: {path}\n"));
+        for i in (0..20).map(|n| 50 + n * 97) {
+            let (before, after) = (i - 1, i + 1);
+            patch.push_str(&std::format!("@@\n let value_{before} = {file};\n-let value_{i} = {file};\n"));
+            patch.push_str(&std::format!("+let value_{i} = {file} + 1;\n let value_{after} = {file};\n"));
+        }
+    }
+    patch.push_str("*** End Patch");
+    (vfs, patch)
+}
+
+fn bench_parallel_apply(c: &mut criterion::Criterion) {
+    let (vfs, patch) = files_and_patch();
+    std::assert_eq!(zenpatch::parallel_apply(&patch, &vfs).unwrap(), zenpatch::apply(&patch, &vfs).unwrap());
+
+    let mut group = c.benchmark_group("50 files, 20 hunks each");
+    group.bench_function("apply", |b| b.iter(|| zenpatch::apply(criterion::black_box(&patch), &vfs).unwrap()));
+    group.bench_function("parallel_apply", |b| {
+        b.iter(|| zenpatch::parallel_apply(criterion::black_box(&patch), &vfs).unwrap())
+    });
+    group.finish();
+}
+
+criterion::criterion_group!(benches, bench_parallel_apply);
+criterion::criterion_main!(benches);
@@ -0,0 +1,19 @@
+//! Applies an arbitrary hunk to an arbitrary one-file VFS: `apply` must
+//! return `Ok` or `Err`, never panic.
+//!
+//! The input is split at its first NUL byte into the file content and the
+//! hunk body; the hunk is wrapped in an `Update File` envelope so most inputs
+//! reach the backtracking search instead of failing to parse.
+
+#![no_main]
+
+libfuzzer_sys::fuzz_target!(|data: &[u8]| {
+    let text = std::string::String::from_utf8_lossy(data);
+    let (content, hunk) = text.split_once('\0').unwrap_or((text.as_ref(), ""));
+
+    let mut vfs = zenpatch::Vfs::new();
+    vfs.insert("a.txt".to_string(), content.to_string());
+    let patch = std::format!("*** Begin Patch\n*** Update File: a.txt\n@@\n{}\n*** End Patch", hunk);
+
+    let _ = zenpatch::apply(&patch, &vfs);
+});
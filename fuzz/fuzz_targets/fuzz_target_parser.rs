@@ -0,0 +1,9 @@
+//! Feeds arbitrary text to `text_to_patch`: it must return `Ok` or `Err`,
+//! never panic.
+
+#![no_main]
+
+libfuzzer_sys::fuzz_target!(|data: &[u8]| {
+    let text = std::string::String::from_utf8_lossy(data);
+    let _ = zenpatch::parser::text_to_patch::text_to_patch(&text);
+});
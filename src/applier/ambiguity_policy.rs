@@ -0,0 +1,17 @@
+//! Defines the `AmbiguityPolicy` enum: what the patcher does when its search
+//! cannot prove that a patch has a single result.
+//!
+//! Conforms to rust coding guidelines (one item per file).
+
+/// How the backtracking patcher treats a search that could not be resolved
+/// to exactly one resulting file.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AmbiguityPolicy {
+    /// Refuse to apply with `ZenpatchError::AmbiguousPatch` (the default).
+    #[default]
+    Reject,
+    /// When the search gave up at its node limit before finding a second,
+    /// DIFFERENT result, apply the first solution found and emit a warning.
+    /// Patches with two genuinely different results are still rejected.
+    AcceptFirst,
+}
@@ -0,0 +1,60 @@
+//! Defines `BacktrackingApplier`, the [`crate::applier::chunk_applier::ChunkApplier`]
+//! that runs the full backtracking search.
+//!
+//! Conforms to rust coding guidelines (one item per file).
+
+/// Places chunks with [`crate::applier::backtracking_patcher::apply_patch_backtracking_mode`]
+/// in a single whitespace mode, exploring every placement and rejecting
+/// ambiguous ones. Unlike the default pipeline it never retries in a looser mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BacktrackingApplier {
+    /// The whitespace mode lines are matched in.
+    pub mode: crate::applier::whitespace_mode::WhitespaceMode,
+}
+
+/// Strict matching.
+impl std::default::Default for BacktrackingApplier {
+    fn default() -> Self {
+        Self { mode: crate::applier::whitespace_mode::WhitespaceMode::Strict }
+    }
+}
+
+impl crate::applier::chunk_applier::ChunkApplier for BacktrackingApplier {
+    fn apply(
+        &self,
+        original_lines: &[std::string::String],
+        chunks: &[crate::data::chunk::Chunk],
+    ) -> std::result::Result<std::vec::Vec<std::string::String>, crate::error::ZenpatchError> {
+        crate::applier::backtracking_patcher::apply_patch_backtracking_mode(original_lines, chunks, self.mode)
+    }
+
+    fn mode(&self) -> crate::applier::whitespace_mode::WhitespaceMode {
+        self.mode
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::applier::chunk_applier::ChunkApplier;
+    use crate::tests::test_macros::{chunks, lines};
+
+    #[test]
+    fn test_applies_unique_chunk_and_rejects_ambiguous_one() {
+        let applier = super::BacktrackingApplier::default();
+        let original = lines("a\nx\nb\nx");
+        std::assert_eq!(applier.apply(&original, &chunks("@@\n a\n-x\n+y")).unwrap(), lines("a\ny\nb\nx"));
+        std::assert!(std::matches!(
+            applier.apply(&original, &chunks("@@\n-x\n+y")),
+            std::result::Result::Err(crate::error::ZenpatchError::AmbiguousPatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_matches_only_in_its_mode() {
+        let original = lines("    a\nb");
+        let chunks = chunks("@@\n a\n-b\n+c");
+        std::assert!(super::BacktrackingApplier::default().apply(&original, &chunks).is_err());
+        let lenient = super::BacktrackingApplier { mode: crate::applier::whitespace_mode::WhitespaceMode::Lenient };
+        std::assert_eq!(lenient.apply(&original, &chunks).unwrap(), lines("    a\nc"));
+    }
+}
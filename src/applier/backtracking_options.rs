@@ -0,0 +1,67 @@
+//! Defines the `BacktrackingOptions` struct passed to the backtracking patcher.
+//!
+//! These are the applier-level knobs that [`crate::apply_options::ApplyOptions`]
+//! is translated into; the defaults reproduce the plain strict/lenient search.
+//! Conforms to rust coding guidelines (one item per file).
+
+/// Tuning options for the backtracking patcher.
+#[derive(Clone)]
+pub struct BacktrackingOptions {
+    /// Do not insert a line that is byte-identical to the line currently at
+    /// the insertion position; the existing line is kept in its place instead.
+    pub skip_redundant_insertions: bool,
+    /// Resolve a contextless pure-deletion chunk that matches in several
+    /// places to its earliest match instead of treating it as ambiguous.
+    pub prefer_first_occurrence: bool,
+    /// What to do when the search cannot settle on a single result.
+    pub ambiguity_policy: crate::applier::ambiguity_policy::AmbiguityPolicy,
+    /// Search nodes to visit before giving up (see `ambiguity_policy`).
+    pub max_nodes: usize,
+    /// Optional shared counter incremented once per visited search node, so
+    /// progress can be observed (or reset) from outside, including across
+    /// threads. When `None` the patcher counts with a private counter.
+    pub node_counter: std::option::Option<std::sync::Arc<std::sync::atomic::AtomicUsize>>,
+    /// The whitespace modes tried, in order, until the chunks apply.
+    pub retry_strategy: crate::applier::retry_strategy::RetryStrategy,
+    /// Match only in this mode, skipping the strict → lenient fallback.
+    pub whitespace_mode: std::option::Option<crate::applier::whitespace_mode::WhitespaceMode>,
+    /// Only accept placements whose positions follow the chunks' `orig_index`
+    /// (document) order. The search always tries that order first; this
+    /// drops the fallback to out-of-order placements, so a patch whose hunks
+    /// are out of file order fails with a `PatchConflict` instead.
+    pub require_ordered_application: bool,
+    /// Replaces the whole mode-escalating search with this applier.
+    pub chunk_applier: std::option::Option<std::sync::Arc<dyn crate::applier::chunk_applier::ChunkApplier>>,
+}
+
+impl std::fmt::Debug for BacktrackingOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BacktrackingOptions")
+            .field("skip_redundant_insertions", &self.skip_redundant_insertions)
+            .field("prefer_first_occurrence", &self.prefer_first_occurrence)
+            .field("ambiguity_policy", &self.ambiguity_policy)
+            .field("max_nodes", &self.max_nodes)
+            .field("node_counter", &self.node_counter)
+            .field("retry_strategy", &self.retry_strategy)
+            .field("whitespace_mode", &self.whitespace_mode)
+            .field("require_ordered_application", &self.require_ordered_application)
+            .field("chunk_applier", &self.chunk_applier.as_ref().map(|_| "<dyn ChunkApplier>"))
+            .finish()
+    }
+}
+
+impl std::default::Default for BacktrackingOptions {
+    fn default() -> Self {
+        Self {
+            skip_redundant_insertions: false,
+            prefer_first_occurrence: false,
+            ambiguity_policy: crate::applier::ambiguity_policy::AmbiguityPolicy::Reject,
+            max_nodes: crate::applier::backtracking_patcher::MAX_BACKTRACK_NODES,
+            node_counter: std::option::Option::None,
+            retry_strategy: crate::applier::retry_strategy::RetryStrategy::LenientFallback,
+            whitespace_mode: std::option::Option::None,
+            require_ordered_application: false,
+            chunk_applier: std::option::Option::None,
+        }
+    }
+}
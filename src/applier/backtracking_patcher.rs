@@ -4,49 +4,40 @@
 //! for all chunks, applying deletions and insertions in turn. Fails on ambiguity
 //! or conflict. Conforms to rust coding guidelines (one item per file).
 
+use crate::applier::ambiguity_policy::AmbiguityPolicy;
+use crate::applier::backtracking_options::BacktrackingOptions;
 use crate::applier::state::BacktrackingState;
 use crate::applier::whitespace_mode::WhitespaceMode;
 use crate::data::chunk::Chunk;
 use crate::data::line_type::LineType;
 use crate::error::ZenpatchError;
+use crate::text_utils::{levenshtein, normalize, super_normalise};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-/// Maximum allowed backtracking nodes before giving up as "ambiguous".
-const MAX_BACKTRACK_NODES: usize = 100_000;
-
-fn super_normalise(s: &str) -> String {
-    s.trim()
-        .chars()
-        .map(|c| match c {
-            // Various dash / hyphen code-points → ASCII '-'
-            '\u{2010}' | '\u{2011}' | '\u{2012}' | '\u{2013}' | '\u{2014}' | '\u{2015}'
-            | '\u{2212}' => '-',
-            // Fancy single quotes → '\''
-            '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' => '\'',
-            // Fancy double quotes → '"'
-            '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' => '"',
-            // Non-breaking space and other odd spaces → normal space
-            '\u{00A0}' | '\u{2002}' | '\u{2003}' | '\u{2004}' | '\u{2005}' | '\u{2006}'
-            | '\u{2007}' | '\u{2008}' | '\u{2009}' | '\u{200A}' | '\u{202F}' | '\u{205F}'
-            | '\u{3000}' => ' ',
-            other => other,
-        })
-        .collect::<String>()
-}
+/// (chunk index, original position) pairs placing every chunk.
+type Placement = Vec<(usize, usize)>;
 
-fn normalize(s: &str) -> String {
-    s.split_whitespace().collect::<Vec<_>>().join(" ")
-}
+/// Default number of backtracking nodes visited before giving up as "ambiguous".
+pub const MAX_BACKTRACK_NODES: usize = 100_000;
 
 /// Compares two lines according to whitespace mode: exact or trimmed.
-fn match_line(a: &str, b: &str, mode: WhitespaceMode) -> bool {
+pub(crate) fn match_line(a: &str, b: &str, mode: WhitespaceMode) -> bool {
     match mode {
         WhitespaceMode::Strict => a == b,
+        WhitespaceMode::IgnoreTrailingWhitespace => a.trim_end() == b.trim_end(),
         WhitespaceMode::Lenient => {
             normalize(a) == normalize(b)
         },
         WhitespaceMode::SuperLenient => {
             super_normalise(&normalize(a)) == super_normalise(&normalize(b))
         }
+        WhitespaceMode::Fuzzy { max_edit_distance } => {
+            let (a, b) = (super_normalise(&normalize(a)), super_normalise(&normalize(b)));
+            a == b || levenshtein(&a, &b) <= max_edit_distance
+        }
+        WhitespaceMode::IgnoreAllWhitespace => {
+            a.chars().filter(|c| !c.is_whitespace()).eq(b.chars().filter(|c| !c.is_whitespace()))
+        }
     }
 }
 
@@ -56,26 +47,88 @@ fn match_line(a: &str, b: &str, mode: WhitespaceMode) -> bool {
 /// the patch author invented or mistyped — so we name the FIRST such line verbatim. If every
 /// such line does exist individually but not as a consecutive block, the patch has an ordering /
 /// extra-line problem, which we say instead.
-fn diagnose_conflict(original_lines: &[String], chunks: &[Chunk], mode: WhitespaceMode) -> String {
-    for chunk in chunks {
+///
+/// Returns the index of the chunk at fault together with the message: the chunk holding the
+/// missing line, else the first chunk with no candidate position at all (else chunk 0).
+fn diagnose_conflict(
+    original_lines: &[String],
+    chunks: &[Chunk],
+    valid_positions: &[Vec<usize>],
+    mode: WhitespaceMode,
+) -> (usize, String) {
+    let forced_miss = chunks
+        .iter()
+        .zip(valid_positions)
+        .enumerate()
+        .find_map(|(i, (chunk, positions))| chunk.forced_position.filter(|_| positions.is_empty()).map(|pos| (i, pos)));
+    if let Some((chunk_index, pos)) = forced_miss {
+        return (
+            chunk_index,
+            format!(
+                "the chunk was forced to line {} (forced_position), but its deleted lines do not match there",
+                pos + 1
+            ),
+        );
+    }
+    for (chunk_index, chunk) in chunks.iter().enumerate() {
         for (line_type, content) in &chunk.lines {
             if matches!(line_type, LineType::Context | LineType::Deletion) {
                 let exists = original_lines.iter().any(|l| match_line(l, content, mode));
                 if !exists {
-                    return format!(
-                        "this context/deleted line does not exist in the file (it was likely \
-                         invented, mistyped, or has wrong whitespace — copy lines verbatim from \
-                         the file): \"{}\"",
-                        content.trim_end()
+                    return (
+                        chunk_index,
+                        format!(
+                            "this context/deleted line does not exist in the file (it was likely \
+                             invented, mistyped, or has wrong whitespace — copy lines verbatim from \
+                             the file): \"{}\"",
+                            content.trim_end()
+                        ),
                     );
                 }
             }
         }
     }
-    "the patch's context/deleted lines all exist in the file but not as one consecutive block — \
-     a line is out of order, duplicated, or there is an extra line inserted between context lines; \
-     re-copy a contiguous run of real lines around the change"
-        .to_string()
+    let chunk_index = valid_positions.iter().position(|p| p.is_empty()).unwrap_or(0);
+    (
+        chunk_index,
+        "the patch's context/deleted lines all exist in the file but not as one consecutive block — \
+         a line is out of order, duplicated, or there is an extra line inserted between context lines; \
+         re-copy a contiguous run of real lines around the change"
+            .to_string(),
+    )
+}
+
+/// Cheap check that every chunk has at least one candidate position in
+/// `original_lines`. A chunk with none makes the patch impossible, which is
+/// reported as a `PatchConflict` without running the search.
+pub fn pre_validate_chunks(
+    original_lines: &[String],
+    chunks: &[Chunk],
+    mode: WhitespaceMode,
+) -> Result<(), ZenpatchError> {
+    let valid_positions: Vec<Vec<usize>> = chunks
+        .iter()
+        .map(|chunk| valid_positions_for_chunk(original_lines, chunk, mode))
+        .collect();
+    reject_unplaceable_chunks(original_lines, chunks, &valid_positions, mode)
+}
+
+/// `PatchConflict` for the first chunk without any valid position.
+fn reject_unplaceable_chunks(
+    original_lines: &[String],
+    chunks: &[Chunk],
+    valid_positions: &[Vec<usize>],
+    mode: WhitespaceMode,
+) -> Result<(), ZenpatchError> {
+    if valid_positions.iter().all(|p| !p.is_empty()) {
+        return Ok(());
+    }
+    let (chunk_index, message) = diagnose_conflict(original_lines, chunks, valid_positions, mode);
+    Err(ZenpatchError::PatchConflict {
+        path: String::new(),
+        chunk_index,
+        message,
+    })
 }
 
 /// Applies patch chunks using strict or lenient whitespace matching.
@@ -93,21 +146,229 @@ pub fn apply_patch_backtracking_mode(
     chunks: &[Chunk],
     mode: WhitespaceMode,
 ) -> Result<Vec<String>, ZenpatchError> {
-    if original_lines.is_empty() && chunks.iter().all(|c| c.del_lines.is_empty()) {
-        let result: Vec<String> = chunks.iter()
-            .flat_map(|c| c.ins_lines.iter().cloned())
-            .collect();
+    apply_patch_backtracking_with_options(
+        original_lines,
+        chunks,
+        mode,
+        &BacktrackingOptions::default(),
+        &mut Vec::new(),
+    )
+}
+
+/// Backtracking patcher with configurable whitespace mode and search options.
+/// Non-fatal issues (e.g. an ambiguity accepted under `AmbiguityPolicy::AcceptFirst`)
+/// are appended to `warnings`.
+pub fn apply_patch_backtracking_with_options(
+    original_lines: &[String],
+    chunks: &[Chunk],
+    mode: WhitespaceMode,
+    options: &BacktrackingOptions,
+    warnings: &mut Vec<String>,
+) -> Result<Vec<String>, ZenpatchError> {
+    if let Some(result) = insert_into_empty_file(original_lines, chunks) {
         return Ok(result);
     }
+    let valid_positions = candidate_positions(original_lines, chunks, mode, options);
+    // A chunk that fits nowhere cannot be placed by any search order.
+    reject_unplaceable_chunks(original_lines, chunks, &valid_positions, mode)?;
+    search_placements(original_lines, chunks, &valid_positions, mode, options, warnings).map(|(result, _)| result)
+}
 
-    // The original file never changes during the search, so each chunk's
-    // candidate positions (context match + deletion content check) are
-    // computed exactly once here instead of at every search node.
-    let valid_positions: Vec<Vec<usize>> = chunks
-        .iter()
-        .map(|chunk| valid_positions_for_chunk(original_lines, chunk, mode))
-        .collect();
+/// Applies chunks in `orig_index` order, each at its only valid position.
+/// Falls back to [`apply_patch_backtracking_mode`] when some chunk could
+/// land in more than one place or the placements overlap or run out of order.
+pub fn apply_patch_greedy(
+    original_lines: &[String],
+    chunks: &[Chunk],
+    mode: WhitespaceMode,
+) -> Result<Vec<String>, ZenpatchError> {
+    apply_patch_greedy_with_options(
+        original_lines,
+        chunks,
+        mode,
+        &BacktrackingOptions::default(),
+        &mut Vec::new(),
+    )
+}
 
+/// [`apply_patch_greedy`] with search options for the backtracking fallback.
+pub fn apply_patch_greedy_with_options(
+    original_lines: &[String],
+    chunks: &[Chunk],
+    mode: WhitespaceMode,
+    options: &BacktrackingOptions,
+    warnings: &mut Vec<String>,
+) -> Result<Vec<String>, ZenpatchError> {
+    if let Some(result) = insert_into_empty_file(original_lines, chunks) {
+        return Ok(result);
+    }
+    let valid_positions = candidate_positions(original_lines, chunks, mode, options);
+    reject_unplaceable_chunks(original_lines, chunks, &valid_positions, mode)?;
+    match greedy_mapping(chunks, &valid_positions, mode) {
+        Some(mapping) => Ok(materialize_solution(original_lines, chunks, &mapping, mode, options)),
+        None => {
+            search_placements(original_lines, chunks, &valid_positions, mode, options, warnings).map(|(result, _)| result)
+        }
+    }
+}
+
+/// The (chunk index, original position) placement that
+/// [`apply_patch_greedy_with_options`] would apply. Does not handle the
+/// empty-file shortcut, which places nothing.
+pub(crate) fn find_placements(
+    original_lines: &[String],
+    chunks: &[Chunk],
+    mode: WhitespaceMode,
+    options: &BacktrackingOptions,
+) -> Result<Placement, ZenpatchError> {
+    let valid_positions = candidate_positions(original_lines, chunks, mode, options);
+    reject_unplaceable_chunks(original_lines, chunks, &valid_positions, mode)?;
+    match greedy_mapping(chunks, &valid_positions, mode) {
+        Some(mapping) => Ok(mapping),
+        None => search_placements(original_lines, chunks, &valid_positions, mode, options, &mut Vec::new())
+            .map(|(_, mapping)| mapping),
+    }
+}
+
+/// Replays the placement of `chunks` in `original_lines` and checks that
+/// each chunk's trailing context lines directly follow its changes in the
+/// result, since the search itself only anchors chunks by their leading
+/// context and deletions. Returns the first chunk whose trailing context is
+/// not where it should be, with a description.
+///
+/// Chunks without trailing context, made only of context, or whose last
+/// leading context line doubles as their first deletion are not checked.
+pub(crate) fn misplaced_trailing_context(
+    original_lines: &[String],
+    chunks: &[Chunk],
+    mode: WhitespaceMode,
+    options: &BacktrackingOptions,
+) -> Result<Option<(usize, String)>, ZenpatchError> {
+    if insert_into_empty_file(original_lines, chunks).is_some() {
+        return Ok(None);
+    }
+    let mut ordered = find_placements(original_lines, chunks, mode, options)?;
+    ordered.sort_by_key(|&(_, pos)| pos);
+    let mut result = original_lines.to_vec();
+    let mut delta: isize = 0;
+    for (chunk_idx, orig_pos) in ordered {
+        let chunk = &chunks[chunk_idx];
+        let pos = (orig_pos as isize + delta).max(0) as usize;
+        let before = result.len() as isize;
+        (result, _) = apply_chunk(&result, &vec![(); result.len()], chunk, pos, mode, options, &|| ());
+        delta += result.len() as isize - before;
+
+        let trailing = chunk.trailing_context_count();
+        if trailing == 0
+            || trailing == chunk.lines.len()
+            || adjusted_pre_len(chunk, mode) != chunk.leading_context_count()
+        {
+            continue;
+        }
+        let (changes, tail) = chunk.lines.split_at(chunk.lines.len() - trailing);
+        let start = pos
+            + changes
+                .iter()
+                .filter(|(lt, _)| matches!(lt, LineType::Context | LineType::Insertion))
+                .count();
+        for (j, (_, expected)) in tail.iter().enumerate() {
+            let found = result.get(start + j);
+            if !found.is_some_and(|l| match_line(l, expected, mode)) {
+                return Ok(Some((
+                    chunk_idx,
+                    format!(
+                        "trailing context line \"{}\" should be at line {} but found {}",
+                        expected,
+                        start + j + 1,
+                        found.map_or("the end of the file".to_string(), |l| format!("\"{}\"", l))
+                    ),
+                )));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Inserted lines of all chunks when the file is empty and nothing is deleted.
+fn insert_into_empty_file(original_lines: &[String], chunks: &[Chunk]) -> Option<Vec<String>> {
+    if original_lines.is_empty() && chunks.iter().all(|c| c.del_lines.is_empty()) {
+        Some(chunks.iter().flat_map(|c| c.ins_lines.iter().cloned()).collect())
+    } else {
+        None
+    }
+}
+
+/// The original file never changes during the search, so each chunk's
+/// candidate positions (context match + deletion content check) are
+/// computed exactly once here instead of at every search node.
+///
+/// Each chunk scans the file independently, so with the `rayon` feature the
+/// chunks are scanned in parallel. This is the expensive step:
+/// [`find_fixed_mappings`] only walks the results, and stays sequential
+/// because which of two overlapping chunks it fixes depends on chunk order.
+fn candidate_positions(
+    original_lines: &[String],
+    chunks: &[Chunk],
+    mode: WhitespaceMode,
+    options: &BacktrackingOptions,
+) -> Vec<Vec<usize>> {
+    let positions_for = |chunk: &Chunk| {
+        let mut positions = valid_positions_for_chunk(original_lines, chunk, mode);
+        // Opt-in: a contextless pure deletion carries no hint of which
+        // occurrence it targets, so take the earliest one.
+        if options.prefer_first_occurrence && is_contextless_pure_deletion(chunk) {
+            positions.truncate(1);
+        }
+        positions
+    };
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+        chunks.par_iter().map(positions_for).collect()
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        chunks.iter().map(positions_for).collect()
+    }
+}
+
+/// The placement of every chunk when each has exactly one valid position
+/// and, taken in `orig_index` order, those positions strictly increase with
+/// no overlapping deletions. Any such mapping is the only solution the full
+/// search could find, so it can be applied directly.
+fn greedy_mapping(
+    chunks: &[Chunk],
+    valid_positions: &[Vec<usize>],
+    mode: WhitespaceMode,
+) -> Option<Vec<(usize, usize)>> {
+    let mut order: Vec<usize> = (0..chunks.len()).collect();
+    order.sort_by_key(|&i| chunks[i].orig_index);
+    let mut mapping = Vec::with_capacity(chunks.len());
+    let mut consumed_until = 0;
+    for chunk_idx in order {
+        let [pos] = valid_positions[chunk_idx][..] else {
+            return None;
+        };
+        let affected = affected_range(&chunks[chunk_idx], pos, mode);
+        if mapping.last().is_some_and(|&(_, last)| pos <= last) || affected.start < consumed_until {
+            return None;
+        }
+        consumed_until = affected.end;
+        mapping.push((chunk_idx, pos));
+    }
+    Some(mapping)
+}
+
+/// Backtracking search over precomputed candidate positions. Returns the
+/// patched lines and the placement that produced them.
+fn search_placements(
+    original_lines: &[String],
+    chunks: &[Chunk],
+    valid_positions: &[Vec<usize>],
+    mode: WhitespaceMode,
+    options: &BacktrackingOptions,
+    warnings: &mut Vec<String>,
+) -> Result<(Vec<String>, Placement), ZenpatchError> {
     // Content class per chunk: identical chunks share a class, so solution
     // keys are invariant under permutations of interchangeable chunks.
     let chunk_classes: Vec<usize> = chunks
@@ -128,56 +389,87 @@ pub fn apply_patch_backtracking_mode(
     // legitimately resolves repeated-pattern patches that are ambiguous
     // without it. Ordered solutions are a subset of unordered ones, so an
     // ambiguity verdict here is final; only "no solution at all" falls
-    // back to the unordered search (out-of-order hunks).
-    let (mut current_path, mut state) = find_fixed_mappings(chunks, &valid_positions, mode);
+    // back to the unordered search (out-of-order hunks), unless the caller
+    // requires document order.
+    let local_counter = AtomicUsize::new(0);
+    let nodes: &AtomicUsize = options.node_counter.as_deref().unwrap_or(&local_counter);
+    let (mut current_path, mut state) = find_fixed_mappings(chunks, valid_positions, mode);
     let ordered_ctx = SearchCtx {
         lines: original_lines,
         chunks,
-        valid_positions: &valid_positions,
+        valid_positions,
         chunk_classes: &chunk_classes,
         mode,
+        options,
         ordered: true,
+        nodes,
+        node_base: nodes.load(Ordering::Relaxed),
     };
-    backtrack_with_mode(&ordered_ctx, &mut state, &mut current_path);
+    // The search checks each new placement against the fixed ones, but never
+    // fixed ones against each other; only a required order makes that matter.
+    let fixed_out_of_order = current_path
+        .iter()
+        .any(|&(i, pi)| current_path.iter().any(|&(j, pj)| i < j && pi > pj));
+    if !(options.require_ordered_application && fixed_out_of_order) {
+        backtrack_with_mode(&ordered_ctx, &mut state, &mut current_path);
+    }
 
-    if state.solution_count == 0 {
-        let (path, st) = find_fixed_mappings(chunks, &valid_positions, mode);
+    if state.solution_count == 0 && !options.require_ordered_application {
+        let (path, st) = find_fixed_mappings(chunks, valid_positions, mode);
         current_path = path;
         state = st;
-        let unordered_ctx = SearchCtx { ordered: false, ..ordered_ctx };
+        // Each pass gets the full node budget.
+        let unordered_ctx = SearchCtx {
+            ordered: false,
+            node_base: nodes.load(Ordering::Relaxed),
+            ..ordered_ctx
+        };
         backtrack_with_mode(&unordered_ctx, &mut state, &mut current_path);
     }
 
     if state.solution_count == 0 {
-        return Err(ZenpatchError::PatchConflict(diagnose_conflict(
-            original_lines,
-            chunks,
-            mode,
-        )));
+        let (chunk_index, message) =
+            diagnose_conflict(original_lines, chunks, valid_positions, mode);
+        return Err(ZenpatchError::PatchConflict {
+            path: String::new(),
+            chunk_index,
+            message,
+        });
+    }
+    if state.solution_count > 1
+        && state.node_limit_reached
+        && options.ambiguity_policy == AmbiguityPolicy::AcceptFirst
+    {
+        if let (Some(first), Some(path)) = (state.first_solution_result.take(), state.solution_path.take()) {
+            warnings.push(format!(
+                "search stopped after {} nodes without proving the patch unambiguous; \
+                 applied the first solution found",
+                options.max_nodes
+            ));
+            return Ok((first, path));
+        }
     }
     if state.solution_count > 1 {
-        return Err(ZenpatchError::AmbiguousPatch(
-            "Patch application is ambiguous - please include more context before or after insertions or deletions".to_string()
-        ));
+        // Point at the first chunk that could land in more than one place.
+        let chunk_index = valid_positions.iter().position(|p| p.len() > 1).unwrap_or(0);
+        return Err(ZenpatchError::AmbiguousPatch {
+            path: String::new(),
+            chunk_index,
+            message: "Patch application is ambiguous - please include more context before or after insertions or deletions".to_string(),
+        });
     }
 
-    Ok(state
-        .first_solution_result
-        .expect("first_solution_result must be set"))
+    match (state.first_solution_result, state.solution_path) {
+        (Some(result), Some(path)) => Ok((result, path)),
+        _ => unreachable!("a counted solution always records its result and path"),
+    }
 }
 
 /// Length of the chunk's leading context run, adjusted for the
 /// duplicated-line case where the last context line equals the first
 /// deleted line (the two refer to the same file line).
 fn adjusted_pre_len(chunk: &Chunk, mode: WhitespaceMode) -> usize {
-    let mut pre_len = 0;
-    for (lt, _) in chunk.lines.iter() {
-        if *lt == LineType::Context {
-            pre_len += 1;
-        } else {
-            break;
-        }
-    }
+    let pre_len = chunk.leading_context_count();
     if pre_len > 0 && !chunk.del_lines.is_empty() {
         let last_ctx = &chunk.lines[pre_len - 1].1;
         if let Some((LineType::Deletion, del)) = chunk.lines.get(pre_len) {
@@ -190,26 +482,38 @@ fn adjusted_pre_len(chunk: &Chunk, mode: WhitespaceMode) -> usize {
 }
 
 /// Candidate positions for a chunk: context matches whose deletion block
-/// also matches the file content at that offset.
-fn valid_positions_for_chunk(
+/// also matches the file content at that offset. A chunk with a
+/// `forced_position` has just that one, if its deletions match there.
+pub(crate) fn valid_positions_for_chunk(
     lines: &[String],
     chunk: &Chunk,
     mode: WhitespaceMode,
 ) -> Vec<usize> {
     let adj_pre = adjusted_pre_len(chunk, mode);
-    find_match_positions(lines, chunk, mode)
-        .into_iter()
-        .filter(|&pos| {
-            chunk.del_lines.iter().enumerate().all(|(j, del_line)| {
+    let deletions_match = |&pos: &usize| {
+        pos <= lines.len()
+            && chunk.del_lines.iter().enumerate().all(|(j, del_line)| {
                 let idx = pos + adj_pre + j;
                 idx < lines.len() && match_line(&lines[idx], del_line, mode)
             })
-        })
+    };
+    if let Some(pos) = chunk.forced_position {
+        return Some(pos).filter(deletions_match).into_iter().collect();
+    }
+    find_match_positions(lines, chunk, mode)
+        .into_iter()
+        .filter(deletions_match)
         .collect()
 }
 
+/// True for a chunk made only of deleted lines (no context, no insertions).
+fn is_contextless_pure_deletion(chunk: &Chunk) -> bool {
+    !chunk.del_lines.is_empty()
+        && chunk.lines.iter().all(|(lt, _)| *lt == LineType::Deletion)
+}
+
 /// The original-file index range consumed (deleted) by a chunk matched at `pos`.
-fn affected_range(chunk: &Chunk, pos: usize, mode: WhitespaceMode) -> std::ops::Range<usize> {
+pub(crate) fn affected_range(chunk: &Chunk, pos: usize, mode: WhitespaceMode) -> std::ops::Range<usize> {
     let start = pos + adjusted_pre_len(chunk, mode);
     start..start + chunk.del_lines.len()
 }
@@ -221,10 +525,26 @@ fn materialize_solution(
     chunks: &[Chunk],
     mapping: &[(usize, usize)],
     mode: WhitespaceMode,
+    options: &BacktrackingOptions,
 ) -> Vec<String> {
+    materialize_tracked(lines, &vec![(); lines.len()], chunks, mapping, mode, options, &|| ()).0
+}
+
+/// [`materialize_solution`] that also carries a tag per line: lines kept
+/// from `lines` keep their entry of `tags`, inserted lines get `inserted()`.
+pub(crate) fn materialize_tracked<T: Clone>(
+    lines: &[String],
+    tags: &[T],
+    chunks: &[Chunk],
+    mapping: &[(usize, usize)],
+    mode: WhitespaceMode,
+    options: &BacktrackingOptions,
+    inserted: &dyn Fn() -> T,
+) -> (Vec<String>, Vec<T>) {
     let mut ordered: Vec<(usize, usize)> = mapping.to_vec();
     ordered.sort_by_key(|&(_, pos)| pos);
     let mut result = lines.to_vec();
+    let mut result_tags = tags.to_vec();
     let mut delta: isize = 0;
     for (chunk_idx, orig_pos) in ordered {
         let chunk = &chunks[chunk_idx];
@@ -233,10 +553,13 @@ fn materialize_solution(
         } else {
             orig_pos.saturating_sub((-delta) as usize)
         };
-        result = apply_chunk(&result, chunk, pos, mode);
-        delta += chunk.ins_lines.len() as isize - chunk.del_lines.len() as isize;
+        let before = result.len() as isize;
+        (result, result_tags) = apply_chunk(&result, &result_tags, chunk, pos, mode, options, inserted);
+        // Measured rather than derived from the chunk: skipped redundant
+        // insertions make the real growth smaller than `ins - del`.
+        delta += result.len() as isize - before;
     }
-    result
+    (result, result_tags)
 }
 
 /// Pre-commits every chunk that has exactly one valid, non-overlapping
@@ -267,15 +590,10 @@ fn find_fixed_mappings(
 
 
 fn get_pre_context_lines(chunk: &Chunk) -> Vec<String> {
-    let mut ctx: Vec<String> = Vec::new();
-    for (line_type, content) in chunk.lines.iter() {
-        if *line_type == LineType::Context {
-            ctx.push(content.clone());
-        } else {
-            break;
-        }
-    }
-    ctx
+    chunk.lines[..chunk.leading_context_count()]
+        .iter()
+        .map(|(_, content)| content.clone())
+        .collect()
 }
 
 fn apply_chunk_constraints(
@@ -299,7 +617,7 @@ fn apply_chunk_constraints(
 
     // Filter by is_end_of_file: the matched region must reach the end of the file
     if chunk.is_end_of_file {
-        let pre_len = get_pre_context_lines(chunk).len();
+        let pre_len = chunk.leading_context_count();
         let span = pre_len + chunk.del_lines.len();
         // For pure insertions with context, the context + insertion should land at the end
         let effective_span = if span == 0 { 0 } else { span };
@@ -309,12 +627,49 @@ fn apply_chunk_constraints(
     filtered
 }
 
+/// The chunk's `orig_index` (as set from an `@@ -n,m +n,m @@` header) when
+/// its leading context, deleted lines and trailing context all match the
+/// file right there, so the file need not be scanned. `orig_index == 0`
+/// counts as "no hint".
+fn hinted_position(lines: &[String], chunk: &Chunk, pre: &[String], mode: WhitespaceMode) -> Option<usize> {
+    let pos = chunk.orig_index;
+    if pos == 0 || (pre.is_empty() && chunk.del_lines.is_empty()) {
+        return None;
+    }
+    let matches_at = |start: usize, expected: &[String]| {
+        expected
+            .iter()
+            .enumerate()
+            .all(|(j, line)| lines.get(start + j).is_some_and(|l| match_line(l, line, mode)))
+    };
+    let adj_pre = adjusted_pre_len(chunk, mode);
+    // The trailing context ends the chunk's original-side span; `adj_pre`
+    // drops the line shared by the last context and the first deletion.
+    let post: Vec<String> = chunk.post_context_lines().into_iter().map(String::from).collect();
+    let orig_len = chunk.lines.iter().filter(|(lt, _)| *lt != LineType::Insertion).count();
+    let post_start = pos + orig_len - (pre.len() - adj_pre) - post.len();
+    if matches_at(pos, pre)
+        && matches_at(pos + adj_pre, &chunk.del_lines)
+        && matches_at(post_start, &post)
+        && !apply_chunk_constraints(vec![pos], lines, chunk, mode).is_empty()
+    {
+        Some(pos)
+    } else {
+        None
+    }
+}
+
 fn find_match_positions(
     lines: &[String],
     chunk: &Chunk,
     mode: WhitespaceMode,
 ) -> Vec<usize> {
     let pre = get_pre_context_lines(chunk);
+    // Fast path: a correct line-number hint pins the chunk without a scan;
+    // a wrong one falls through to the full search below.
+    if let Some(pos) = hinted_position(lines, chunk, &pre, mode) {
+        return vec![pos];
+    }
     let mut positions: Vec<usize> = Vec::new();
     if pre.is_empty() {
         // No leading context: pure insertion or deletion
@@ -353,27 +708,18 @@ fn find_match_positions(
             positions.push(i);
         }
     }
-    // collect trailing context (post-context) for potential disambiguation
-    let post_context: Vec<String> = {
-        let mut ctx: Vec<String> = Vec::new();
-        for (lt, content) in chunk.lines.iter().rev() {
-            if *lt == LineType::Context {
-                if !content.trim().is_empty() {
-                    ctx.push(content.clone());
-                }
-            } else {
-                break;
-            }
-        }
-        ctx.reverse();
-        ctx
-    };
+    // Non-blank trailing context (post-context) for potential disambiguation.
+    let post_context: Vec<&str> = chunk
+        .post_context_lines()
+        .into_iter()
+        .filter(|line| !line.trim().is_empty())
+        .collect();
 
     // For pure insertions (no deletions), attempt to disambiguate using post-context
     if chunk.del_lines.is_empty() && !chunk.ins_lines.is_empty() && !post_context.is_empty() {
         // use the first post-context line as an anchor
-        let anchor = &post_context[0];
-        let pre_full_len = get_pre_context_lines(chunk).len();
+        let anchor = post_context[0];
+        let pre_full_len = chunk.leading_context_count();
         let mut filtered: Vec<usize> = Vec::new();
         for &pos in &positions {
             // search within a small window after pre-context for the anchor line
@@ -399,15 +745,25 @@ fn find_match_positions(
     apply_chunk_constraints(positions, lines, chunk, mode)
 }
 
-fn apply_chunk(lines: &[String], chunk: &Chunk, pos: usize, mode: WhitespaceMode) -> Vec<String> {
+fn apply_chunk<T: Clone>(
+    lines: &[String],
+    tags: &[T],
+    chunk: &Chunk,
+    pos: usize,
+    mode: WhitespaceMode,
+    options: &BacktrackingOptions,
+    inserted: &dyn Fn() -> T,
+) -> (Vec<String>, Vec<T>) {
     let adj_pre = adjusted_pre_len(chunk, mode);
 
     let mut result: Vec<String> = Vec::with_capacity(lines.len() + chunk.ins_lines.len());
+    let mut result_tags: Vec<T> = Vec::with_capacity(result.capacity());
     // Prefix: everything before the chunk + its leading context (the leading context
     // is copied verbatim from the original; `adj_pre` also folds the duplicated
     // last-context-equals-first-deletion case so we don't consume that line twice).
     let start_copy = (pos + adj_pre).min(lines.len());
     result.extend_from_slice(&lines[..start_copy]);
+    result_tags.extend_from_slice(&tags[..start_copy]);
 
     // Walk the chunk's lines IN ORDER from just past the leading context, so each
     // insertion lands at its real position (a context block can sit between two
@@ -425,6 +781,7 @@ fn apply_chunk(lines: &[String], chunk: &Chunk, pos: usize, mode: WhitespaceMode
                 }
                 if cursor < lines.len() {
                     result.push(lines[cursor].clone());
+                    result_tags.push(tags[cursor].clone());
                 }
                 cursor += 1;
             }
@@ -432,15 +789,26 @@ fn apply_chunk(lines: &[String], chunk: &Chunk, pos: usize, mode: WhitespaceMode
                 cursor += 1; // drop the original line
             }
             LineType::Insertion => {
-                result.push(content.clone());
+                if options.skip_redundant_insertions && lines.get(cursor) == Some(content) {
+                    // Already present (e.g. the patch was applied before):
+                    // keep the existing line instead of inserting a copy.
+                    result.push(lines[cursor].clone());
+                    result_tags.push(tags[cursor].clone());
+                    cursor += 1;
+                } else {
+                    result.push(content.clone());
+                    result_tags.push(inserted());
+                }
             }
+            LineType::NoNewlineAtEOF => {}
         }
     }
 
     if cursor < lines.len() {
         result.extend_from_slice(&lines[cursor..]);
+        result_tags.extend_from_slice(&tags[cursor..]);
     }
-    result
+    (result, result_tags)
 }
 
 /// Immutable inputs of the backtracking search, fixed for its whole duration.
@@ -448,13 +816,20 @@ struct SearchCtx<'a> {
     lines: &'a [String],
     chunks: &'a [Chunk],
     /// Pre-computed candidate positions per chunk (same indexing as `chunks`).
+    /// This is the search's memo of `find_match_positions`: the lines and
+    /// chunks never change during one call, so nodes only read it.
     valid_positions: &'a [Vec<usize>],
     /// Content class per chunk: index of the first chunk with equal content.
     chunk_classes: &'a [usize],
     mode: WhitespaceMode,
+    options: &'a BacktrackingOptions,
     /// When set, chunk positions must be non-decreasing in chunk order
     /// (hunks appear in file order).
     ordered: bool,
+    /// Visited-node counter, owned by the caller so it can be observed.
+    nodes: &'a AtomicUsize,
+    /// Counter value when this pass started; the cap applies to the difference.
+    node_base: usize,
 }
 
 fn backtrack_with_mode(
@@ -462,9 +837,13 @@ fn backtrack_with_mode(
     state: &mut BacktrackingState,
     current_path: &mut Vec<(usize, usize)>,
 ) {
-    let SearchCtx { lines, chunks, valid_positions, chunk_classes, mode, ordered } = *ctx;
-    state.nodes_visited += 1;
-    if state.nodes_visited > MAX_BACKTRACK_NODES || state.solution_count > 1 {
+    let SearchCtx { lines, chunks, valid_positions, chunk_classes, mode, options, ordered, nodes, node_base } = *ctx;
+    let visited = (nodes.fetch_add(1, Ordering::Relaxed) + 1).wrapping_sub(node_base);
+    if state.solution_count > 1 {
+        return;
+    }
+    if visited > options.max_nodes {
+        state.node_limit_reached = true;
         state.solution_count = 2;
         return;
     }
@@ -482,7 +861,7 @@ fn backtrack_with_mode(
         if state.first_solution_key.as_ref() == Some(&key) {
             return;
         }
-        let candidate = materialize_solution(lines, chunks, current_path, mode);
+        let candidate = materialize_solution(lines, chunks, current_path, mode, options);
         match &state.first_solution_result {
             None => {
                 state.solution_count = 1;
@@ -578,6 +957,14 @@ mod tests {
         assert!(!match_line("  hello", "hello", WhitespaceMode::Strict));
     }
 
+    #[test]
+    fn test_match_line_ignore_trailing_whitespace() {
+        let mode = WhitespaceMode::IgnoreTrailingWhitespace;
+        assert!(match_line("hello  \t", "hello", mode));
+        assert!(!match_line("  hello", "hello", mode));
+        assert!(!match_line("hello  world", "hello world", mode));
+    }
+
     #[test]
     fn test_match_line_lenient_collapses_whitespace() {
         assert!(match_line("hello  world", "hello world", WhitespaceMode::Lenient));
@@ -625,27 +1012,67 @@ mod tests {
         ));
     }
 
-    // ── normalize / super_normalise tests ──
+    #[test]
+    fn test_match_line_ignore_all_whitespace() {
+        let mode = WhitespaceMode::IgnoreAllWhitespace;
+        assert!(match_line("  foo  bar  ", "foobar", mode));
+        assert!(match_line("\tif(x){return 1;}", "if (x) { return 1; }", mode));
+        assert!(!match_line("foo bar", "foo baz", mode));
+        assert!(!match_line("foo  bar", "foobar", WhitespaceMode::Lenient));
+    }
 
     #[test]
-    fn test_normalize_collapses_whitespace() {
-        assert_eq!(normalize("  hello   world  "), "hello world");
-        assert_eq!(normalize("a"), "a");
-        assert_eq!(normalize(""), "");
-        assert_eq!(normalize("  \t\n  "), "");
+    fn test_match_line_fuzzy_single_character_typos() {
+        let fuzzy = WhitespaceMode::Fuzzy { max_edit_distance: 1 };
+        assert!(match_line("let value = 1;", "let vaule = 1;", WhitespaceMode::FUZZY)); // transposition
+        assert!(match_line("let value = 1;", "let vale = 1;", fuzzy)); // dropped letter
+        assert!(match_line("let value = 1;", "let valve = 1;", fuzzy)); // substitution
+        assert!(match_line("\u{201C}value\u{201D}  =", "\"valu\" =", fuzzy)); // compared after normalizing
+        assert!(!match_line("let value = 1;", "let vaule = 1;", fuzzy));
+        assert!(!match_line("let value = 1;", "let other = 2;", WhitespaceMode::FUZZY));
     }
 
     #[test]
-    fn test_super_normalise_fancy_characters() {
-        assert_eq!(super_normalise("\u{201C}hi\u{201D}"), "\"hi\"");
-        assert_eq!(super_normalise("\u{2018}hi\u{2019}"), "'hi'");
-        assert_eq!(super_normalise("a\u{2014}b"), "a-b");
-        assert_eq!(super_normalise("\u{00A0}hi\u{00A0}"), "hi");
+    fn test_fuzzy_mode_applies_chunk_with_typo_in_context() {
+        let original: Vec<String> = vec!["fn main() {", "    println!(\"hello\");", "}"]
+            .into_iter().map(String::from).collect();
+        let chunk = make_chunk(&["fn main() {", "    printn!(\"hello\");"], &[], &["    println!(\"world\");"], &["}"], 0);
+        assert!(apply_patch_backtracking_mode(&original, std::slice::from_ref(&chunk), WhitespaceMode::SuperLenient).is_err());
+        let result = apply_patch_backtracking_mode(&original, &[chunk], WhitespaceMode::FUZZY).unwrap();
+        assert_eq!(result[2], "    println!(\"world\");");
     }
 
     #[test]
-    fn test_super_normalise_trims() {
-        assert_eq!(super_normalise("  hello  "), "hello");
+    fn test_line_number_hint_pins_repeated_context() {
+        let original: Vec<String> = vec!["x", "a", "x", "a"].into_iter().map(String::from).collect();
+        let mut chunk = make_chunk(&["x"], &["a"], &["B"], &[], 0);
+        assert!(apply_patch_backtracking(&original, std::slice::from_ref(&chunk)).is_err());
+
+        chunk.orig_index = 2;
+        let result = apply_patch_backtracking(&original, &[chunk]).unwrap();
+        assert_eq!(result, vec!["x", "a", "x", "B"]);
+    }
+
+    #[test]
+    fn test_wrong_line_number_hint_falls_back_to_scan() {
+        let original: Vec<String> = vec!["a", "b", "c", "d"].into_iter().map(String::from).collect();
+        let chunk = make_chunk(&["b"], &["c"], &["C"], &[], 3);
+        let result = apply_patch_backtracking(&original, &[chunk]).unwrap();
+        assert_eq!(result, vec!["a", "b", "C", "d"]);
+    }
+
+    #[test]
+    fn test_line_number_hint_checks_trailing_context() {
+        let original: Vec<String> =
+            vec!["top", "x", "a", "1", "x", "a", "2"].into_iter().map(String::from).collect();
+        // Only the second block is followed by "2": a hint at the first one must not pin it
+        // there, and the full search leaves the chunk ambiguous instead of editing the wrong block.
+        let mut chunk = make_chunk(&["x"], &["a"], &["B"], &["2"], 1);
+        assert!(apply_patch_backtracking(&original, std::slice::from_ref(&chunk)).is_err());
+
+        chunk.orig_index = 4;
+        let result = apply_patch_backtracking(&original, &[chunk]).unwrap();
+        assert_eq!(result, vec!["top", "x", "a", "1", "x", "B", "2"]);
     }
 
     // ── apply_patch_backtracking direct tests ──
@@ -677,6 +1104,8 @@ mod tests {
             ins_lines: insertions.iter().map(|s| s.to_string()).collect(),
             change_context: None,
             is_end_of_file: false,
+            forced_position: None,
+            context_hint: None,
         }
     }
 
@@ -721,7 +1150,113 @@ mod tests {
             .into_iter().map(String::from).collect();
         let chunk = make_chunk(&["zzz"], &["bbb"], &["BBB"], &[], 0);
         let result = apply_patch_backtracking(&original, &[chunk]);
-        assert!(matches!(result, Err(ZenpatchError::PatchConflict(_))));
+        assert!(matches!(result, Err(ZenpatchError::PatchConflict { .. })));
+    }
+
+    #[test]
+    fn test_forced_position_resolves_repeated_context() {
+        let original: Vec<String> = vec!["x", "1", "x", "1"]
+            .into_iter().map(String::from).collect();
+        let mut chunk = make_chunk(&["x"], &["1"], &["one"], &[], 0);
+        chunk.forced_position = Some(2);
+        let result = apply_patch_backtracking(&original, &[chunk]).unwrap();
+        assert_eq!(result, vec!["x", "1", "x", "one"]);
+    }
+
+    #[test]
+    fn test_forced_position_with_wrong_deletions_is_conflict() {
+        let original: Vec<String> = vec!["a", "b", "c"]
+            .into_iter().map(String::from).collect();
+        let mut chunk = make_chunk(&["a"], &["b"], &["B"], &[], 0);
+        chunk.forced_position = Some(1);
+        match apply_patch_backtracking(&original, &[chunk]) {
+            Err(ZenpatchError::PatchConflict { message, .. }) => assert!(message.contains("forced_position"), "{}", message),
+            other => panic!("expected a conflict, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_pre_validate_chunks() {
+        let original: Vec<String> = vec!["aaa", "bbb"]
+            .into_iter().map(String::from).collect();
+        let good = make_chunk(&["aaa"], &["bbb"], &["BBB"], &[], 0);
+        let bad = make_chunk(&["zzz"], &["bbb"], &["BBB"], &[], 0);
+        assert!(pre_validate_chunks(&original, std::slice::from_ref(&good), WhitespaceMode::Strict).is_ok());
+        let result = pre_validate_chunks(&original, &[good, bad], WhitespaceMode::Strict);
+        assert!(matches!(result, Err(ZenpatchError::PatchConflict { chunk_index: 1, .. })));
+    }
+
+    #[test]
+    fn test_unplaceable_chunk_skips_search() {
+        let original: Vec<String> = (0..50).map(|i| format!("line {}", i % 5)).collect();
+        let mut chunks: Vec<Chunk> = (0..6)
+            .map(|_| make_chunk(&["line 1"], &[], &["new"], &[], 0))
+            .collect();
+        chunks.push(make_chunk(&["missing"], &[], &["new"], &[], 0));
+        let counter = std::sync::Arc::new(AtomicUsize::new(0));
+        let options = BacktrackingOptions {
+            node_counter: Some(counter.clone()),
+            ..BacktrackingOptions::default()
+        };
+        let result = apply_patch_backtracking_with_options(
+            &original,
+            &chunks,
+            WhitespaceMode::Strict,
+            &options,
+            &mut Vec::new(),
+        );
+        assert!(matches!(result, Err(ZenpatchError::PatchConflict { chunk_index: 6, .. })));
+        assert_eq!(counter.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn test_greedy_places_unique_chunks_without_search() {
+        let original: Vec<String> = vec!["a", "b", "c", "d", "e", "f"]
+            .into_iter().map(String::from).collect();
+        // Listed out of file order; the line hints restore it.
+        let chunks = vec![
+            make_chunk(&["d"], &["e"], &["E"], &[], 3),
+            make_chunk(&["a"], &["b"], &["B"], &[], 0),
+        ];
+        let counter = std::sync::Arc::new(AtomicUsize::new(0));
+        let options = BacktrackingOptions {
+            node_counter: Some(counter.clone()),
+            ..BacktrackingOptions::default()
+        };
+        let result = apply_patch_greedy_with_options(
+            &original,
+            &chunks,
+            WhitespaceMode::Strict,
+            &options,
+            &mut Vec::new(),
+        )
+        .unwrap();
+        assert_eq!(result, vec!["a", "B", "c", "d", "E", "f"]);
+        assert_eq!(counter.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn test_greedy_falls_back_to_search_for_repeated_context() {
+        // Each chunk fits both "x" lines; only the ordered search resolves them.
+        let original: Vec<String> = vec!["x", "1", "x", "1"]
+            .into_iter().map(String::from).collect();
+        let chunks = vec![
+            make_chunk(&["x"], &["1"], &["first"], &[], 0),
+            make_chunk(&["x"], &["1"], &["second"], &[], 0),
+        ];
+        let greedy = apply_patch_greedy(&original, &chunks, WhitespaceMode::Strict);
+        let full = apply_patch_backtracking(&original, &chunks);
+        assert_eq!(greedy, full);
+        assert!(greedy.is_ok());
+    }
+
+    #[test]
+    fn test_greedy_reports_conflict() {
+        let original: Vec<String> = vec!["aaa", "bbb"]
+            .into_iter().map(String::from).collect();
+        let chunk = make_chunk(&["zzz"], &["bbb"], &["BBB"], &[], 0);
+        let result = apply_patch_greedy(&original, &[chunk], WhitespaceMode::Strict);
+        assert!(matches!(result, Err(ZenpatchError::PatchConflict { .. })));
     }
 
     #[test]
@@ -730,7 +1265,7 @@ mod tests {
             .into_iter().map(String::from).collect();
         let chunk = make_chunk(&["aaa"], &["bbb"], &["BBB"], &[], 0);
         let result = apply_patch_backtracking(&original, &[chunk]);
-        assert!(matches!(result, Err(ZenpatchError::AmbiguousPatch(_))));
+        assert!(matches!(result, Err(ZenpatchError::AmbiguousPatch { .. })));
     }
 
     #[test]
@@ -827,6 +1362,17 @@ mod tests {
         );
     }
 
+    /// Duplicating one of two ADJACENT identical lines matches at either of
+    /// them, but both placements yield the same file: applied, not ambiguous.
+    #[test]
+    fn test_adjacent_identical_lines_yield_one_solution() {
+        let original: Vec<String> = vec!["a", "x", "x", "b"]
+            .into_iter().map(String::from).collect();
+        let chunk = make_chunk(&[], &["x"], &["x", "x"], &[], 0);
+        let result = apply_patch_backtracking(&original, &[chunk]).unwrap();
+        assert_eq!(result, vec!["a", "x", "x", "x", "b"]);
+    }
+
     /// Regression: 7 interchangeable chunks used to explode the search into
     /// chunk-order × position permutations, hit the node cap, and misreport
     /// the unique solution as AmbiguousPatch. With order-free enumeration
@@ -874,7 +1420,7 @@ mod tests {
         let chunk1 = make_chunk(&[], &["target"], &["X"], &[], 0);
         let chunk2 = make_chunk(&[], &["target"], &["Y"], &[], 0);
         let result = apply_patch_backtracking(&original, &[chunk1, chunk2]);
-        assert!(matches!(result, Err(ZenpatchError::AmbiguousPatch(_))));
+        assert!(matches!(result, Err(ZenpatchError::AmbiguousPatch { .. })));
     }
 
     /// Out-of-order hunks with unique placements still apply via the
@@ -889,6 +1435,43 @@ mod tests {
         assert_eq!(result, vec!["AAA", "mid", "ZZZ"]);
     }
 
+    /// Free ordering would let the two hunks swap occurrences (two different
+    /// results, so ambiguous); ordered application pins each to its own.
+    #[test]
+    fn test_required_order_resolves_interchangeable_hunks() {
+        let original: Vec<String> = vec!["marker", "target", "marker", "target"]
+            .into_iter().map(String::from).collect();
+        let chunks = [
+            make_chunk(&["marker"], &["target"], &["X"], &[], 0),
+            make_chunk(&["marker"], &["target"], &["Y"], &[], 0),
+        ];
+        let options = BacktrackingOptions { require_ordered_application: true, ..Default::default() };
+        let valid_positions = candidate_positions(&original, &chunks, WhitespaceMode::Strict, &options);
+        assert_eq!(valid_positions, vec![vec![0, 2], vec![0, 2]]);
+
+        let result = apply_patch_backtracking_with_options(
+            &original, &chunks, WhitespaceMode::Strict, &options, &mut Vec::new(),
+        )
+        .unwrap();
+        assert_eq!(result, vec!["marker", "X", "marker", "Y"]);
+    }
+
+    /// Without the unordered fallback, out-of-order hunks are a conflict.
+    #[test]
+    fn test_required_order_rejects_out_of_order_hunks() {
+        let original: Vec<String> = vec!["aaa", "mid", "zzz"]
+            .into_iter().map(String::from).collect();
+        let chunks = [
+            make_chunk(&[], &["zzz"], &["ZZZ"], &[], 0),
+            make_chunk(&[], &["aaa"], &["AAA"], &[], 0),
+        ];
+        let options = BacktrackingOptions { require_ordered_application: true, ..Default::default() };
+        let result = apply_patch_backtracking_with_options(
+            &original, &chunks, WhitespaceMode::Strict, &options, &mut Vec::new(),
+        );
+        assert!(matches!(result, Err(ZenpatchError::PatchConflict { .. })));
+    }
+
     // ── change_context constraint tests ──
 
     #[test]
@@ -927,7 +1510,7 @@ mod tests {
         chunk.change_context = Some("nonexistent".to_string());
 
         let result = apply_patch_backtracking(&original, &[chunk]);
-        assert!(matches!(result, Err(ZenpatchError::PatchConflict(_))));
+        assert!(matches!(result, Err(ZenpatchError::PatchConflict { .. })));
     }
 
     // ── is_end_of_file constraint tests ──
@@ -968,4 +1551,86 @@ mod tests {
         let result = apply_patch_backtracking(&original, &[chunk]).unwrap();
         assert_eq!(result, vec!["first", "last", "appended"]);
     }
+
+    // ── ambiguity policy tests ──
+
+    fn capped_options(policy: AmbiguityPolicy) -> BacktrackingOptions {
+        // Enough nodes to find the first of the three equivalent mappings
+        // below, but not to finish enumerating them.
+        BacktrackingOptions { ambiguity_policy: policy, max_nodes: 3, ..Default::default() }
+    }
+
+    #[test]
+    fn test_node_cap_rejects_under_reject_policy() {
+        let original: Vec<String> = vec!["x", "x", "x"].into_iter().map(String::from).collect();
+        let chunks = vec![make_chunk(&[], &["x"], &[], &[], 0), make_chunk(&[], &["x"], &[], &[], 0)];
+        let mut warnings = Vec::new();
+        let result = apply_patch_backtracking_with_options(
+            &original,
+            &chunks,
+            WhitespaceMode::Strict,
+            &capped_options(AmbiguityPolicy::Reject),
+            &mut warnings,
+        );
+        assert!(matches!(result, Err(ZenpatchError::AmbiguousPatch { .. })));
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_accept_first_takes_first_solution_when_capped() {
+        let original: Vec<String> = vec!["x", "x", "x"].into_iter().map(String::from).collect();
+        let chunks = vec![make_chunk(&[], &["x"], &[], &[], 0), make_chunk(&[], &["x"], &[], &[], 0)];
+        let mut warnings = Vec::new();
+        let result = apply_patch_backtracking_with_options(
+            &original,
+            &chunks,
+            WhitespaceMode::Strict,
+            &capped_options(AmbiguityPolicy::AcceptFirst),
+            &mut warnings,
+        )
+        .unwrap();
+        assert_eq!(result, vec!["x"]);
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_accept_first_still_rejects_different_results() {
+        let original: Vec<String> = vec!["a", "x", "b", "x"].into_iter().map(String::from).collect();
+        let chunks = vec![make_chunk(&[], &["x"], &[], &[], 0)];
+        let mut warnings = Vec::new();
+        let options = BacktrackingOptions {
+            ambiguity_policy: AmbiguityPolicy::AcceptFirst,
+            ..Default::default()
+        };
+        let result = apply_patch_backtracking_with_options(
+            &original,
+            &chunks,
+            WhitespaceMode::Strict,
+            &options,
+            &mut warnings,
+        );
+        assert!(matches!(result, Err(ZenpatchError::AmbiguousPatch { .. })));
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_external_node_counter_observes_search() {
+        let counter = std::sync::Arc::new(AtomicUsize::new(0));
+        let options = BacktrackingOptions {
+            node_counter: Some(counter.clone()),
+            ..Default::default()
+        };
+        let original: Vec<String> = vec!["x", "x", "x"].into_iter().map(String::from).collect();
+        let chunks = vec![make_chunk(&[], &["x"], &[], &[], 0), make_chunk(&[], &["x"], &[], &[], 0)];
+        let result = apply_patch_backtracking_with_options(
+            &original,
+            &chunks,
+            WhitespaceMode::Strict,
+            &options,
+            &mut Vec::new(),
+        )
+        .unwrap();
+        assert_eq!(result, vec!["x"]);
+        assert!(counter.load(Ordering::Relaxed) > 0);
+    }
 }
@@ -0,0 +1,25 @@
+//! Defines the `ChunkApplier` trait, the extension point for swapping the
+//! algorithm that places an `Update`'s chunks in a file.
+//!
+//! Conforms to rust coding guidelines (one item per file).
+
+/// Applies all chunks of one file update to the file's lines.
+///
+/// Implementations: [`crate::applier::backtracking_applier::BacktrackingApplier`]
+/// and [`crate::applier::greedy_applier::GreedyApplier`]. Set one (or your
+/// own) as [`crate::apply_options::ApplyOptions::chunk_applier`] to replace the
+/// built-in pipeline; it then receives the chunks exactly as parsed, minus any
+/// `\ No newline at end of file` markers.
+pub trait ChunkApplier: Send + Sync {
+    /// The patched lines, or the error explaining why the chunks do not apply.
+    fn apply(
+        &self,
+        original_lines: &[std::string::String],
+        chunks: &[crate::data::chunk::Chunk],
+    ) -> std::result::Result<std::vec::Vec<std::string::String>, crate::error::ZenpatchError>;
+
+    /// The whitespace mode lines are matched in, reported as
+    /// [`crate::summary::PatchSummary::whitespace_mode_used`] for files
+    /// this applier patched.
+    fn mode(&self) -> crate::applier::whitespace_mode::WhitespaceMode;
+}
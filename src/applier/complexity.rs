@@ -0,0 +1,63 @@
+//! Estimates the cost of the backtracking search before running it.
+//!
+//! Conforms to rust coding guidelines (one item per file).
+
+/// Counts the candidate positions of each chunk in `original_lines` and
+/// combines them into a [`crate::applier::patch_complexity::PatchComplexity`].
+///
+/// Callers can use it to pick [`crate::applier::backtracking_patcher::apply_patch_greedy`]
+/// over the full search, or to warn before a search that is likely to hit its
+/// node limit.
+pub fn estimate_patch_complexity(
+    original_lines: &[std::string::String],
+    chunks: &[crate::data::chunk::Chunk],
+    mode: crate::applier::whitespace_mode::WhitespaceMode,
+) -> crate::applier::patch_complexity::PatchComplexity {
+    let counts: std::vec::Vec<usize> = chunks
+        .iter()
+        .map(|chunk| crate::applier::backtracking_patcher::valid_positions_for_chunk(original_lines, chunk, mode).len())
+        .collect();
+    crate::applier::patch_complexity::PatchComplexity {
+        estimated_nodes: counts.iter().fold(1, |acc: usize, &n| acc.saturating_mul(n)),
+        is_likely_unique: counts.iter().all(|&n| n == 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::estimate_patch_complexity;
+    use crate::tests::test_macros::{chunks, lines};
+
+    #[test]
+    fn test_unique_chunks() {
+        let estimate = estimate_patch_complexity(
+            &lines("a\nb\nc\nd"),
+            &chunks("@@\n a\n-b\n+B\n@@\n c\n-d\n+D"),
+            crate::applier::whitespace_mode::WhitespaceMode::Strict,
+        );
+        assert_eq!(estimate.estimated_nodes, 1);
+        assert!(estimate.is_likely_unique);
+    }
+
+    #[test]
+    fn test_repeated_context_multiplies() {
+        let estimate = estimate_patch_complexity(
+            &lines("x\n1\nx\n1\nx\n1"),
+            &chunks("@@\n x\n-1\n+one\n@@\n x\n-1\n+uno"),
+            crate::applier::whitespace_mode::WhitespaceMode::Strict,
+        );
+        assert_eq!(estimate.estimated_nodes, 9);
+        assert!(!estimate.is_likely_unique);
+    }
+
+    #[test]
+    fn test_unplaceable_chunk_gives_zero() {
+        let estimate = estimate_patch_complexity(
+            &lines("a\nb"),
+            &chunks("@@\n zzz\n-b\n+B"),
+            crate::applier::whitespace_mode::WhitespaceMode::Strict,
+        );
+        assert_eq!(estimate.estimated_nodes, 0);
+        assert!(!estimate.is_likely_unique);
+    }
+}
@@ -0,0 +1,52 @@
+//! Defines `GreedyApplier`, the [`crate::applier::chunk_applier::ChunkApplier`]
+//! that places chunks at their unique positions when it can.
+//!
+//! Conforms to rust coding guidelines (one item per file).
+
+/// Places chunks with [`crate::applier::backtracking_patcher::apply_patch_greedy`]
+/// in a single whitespace mode: each chunk at its only candidate position,
+/// falling back to the backtracking search when that is not possible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GreedyApplier {
+    /// The whitespace mode lines are matched in.
+    pub mode: crate::applier::whitespace_mode::WhitespaceMode,
+}
+
+/// Strict matching.
+impl std::default::Default for GreedyApplier {
+    fn default() -> Self {
+        Self { mode: crate::applier::whitespace_mode::WhitespaceMode::Strict }
+    }
+}
+
+impl crate::applier::chunk_applier::ChunkApplier for GreedyApplier {
+    fn apply(
+        &self,
+        original_lines: &[std::string::String],
+        chunks: &[crate::data::chunk::Chunk],
+    ) -> std::result::Result<std::vec::Vec<std::string::String>, crate::error::ZenpatchError> {
+        crate::applier::backtracking_patcher::apply_patch_greedy(original_lines, chunks, self.mode)
+    }
+
+    fn mode(&self) -> crate::applier::whitespace_mode::WhitespaceMode {
+        self.mode
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::applier::chunk_applier::ChunkApplier;
+    use crate::tests::test_macros::{chunks, lines};
+
+    #[test]
+    fn test_agrees_with_backtracking_applier() {
+        let original = lines("a\nb\nc\nd\ne");
+        let chunks = chunks("@@\n a\n-b\n+B\n@@\n d\n-e\n+E");
+        let greedy = super::GreedyApplier::default().apply(&original, &chunks).unwrap();
+        std::assert_eq!(greedy, lines("a\nB\nc\nd\nE"));
+        std::assert_eq!(
+            greedy,
+            crate::applier::backtracking_applier::BacktrackingApplier::default().apply(&original, &chunks).unwrap()
+        );
+    }
+}
@@ -0,0 +1,43 @@
+//! Defines `LineMapping`, the correspondence between line numbers of a file
+//! before and after a patch, as computed by
+//! [`crate::applier::simulate_apply::simulate_apply`].
+//!
+//! Conforms to rust coding guidelines (one item per file).
+
+/// Zero-based line correspondence between an original file and its patched
+/// form, in the spirit of a source map.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineMapping {
+    /// For each original line, its index in the patched file (`None` if deleted).
+    to_new: std::vec::Vec<std::option::Option<usize>>,
+    /// For each patched line, its index in the original file (`None` if inserted).
+    to_original: std::vec::Vec<std::option::Option<usize>>,
+}
+
+impl LineMapping {
+    /// Builds the mapping from the origin of every patched line and the
+    /// original file's length.
+    pub fn from_origins(to_original: std::vec::Vec<std::option::Option<usize>>, original_len: usize) -> Self {
+        let mut to_new = std::vec![std::option::Option::None; original_len];
+        for (new_line, origin) in to_original.iter().enumerate() {
+            if let std::option::Option::Some(slot) = origin.and_then(|orig| to_new.get_mut(orig)) {
+                *slot = std::option::Option::Some(new_line);
+            }
+        }
+        Self { to_new, to_original }
+    }
+
+    /// Where original line `orig_line` ended up; `None` if it was deleted or
+    /// is out of range.
+    #[must_use]
+    pub fn original_to_new(&self, orig_line: usize) -> std::option::Option<usize> {
+        self.to_new.get(orig_line).copied().flatten()
+    }
+
+    /// Where patched line `new_line` came from; `None` if it was inserted or
+    /// is out of range.
+    #[must_use]
+    pub fn new_to_original(&self, new_line: usize) -> std::option::Option<usize> {
+        self.to_original.get(new_line).copied().flatten()
+    }
+}
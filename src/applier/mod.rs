@@ -2,6 +2,16 @@
 //!
 //! This module includes the backtracking patcher implementation.
 
+pub mod ambiguity_policy;
+pub mod backtracking_applier;
+pub mod backtracking_options;
 pub mod backtracking_patcher;
+pub mod chunk_applier;
+pub mod complexity;
+pub mod greedy_applier;
+pub mod line_mapping;
+pub mod patch_complexity;
+pub mod retry_strategy;
+pub mod simulate_apply;
 pub mod state;
 pub mod whitespace_mode;
@@ -0,0 +1,15 @@
+//! Defines the `PatchComplexity` estimate returned by
+//! [`crate::applier::complexity::estimate_patch_complexity`].
+//!
+//! Conforms to rust coding guidelines (one item per file).
+
+/// How large the backtracking search for a set of chunks could get.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PatchComplexity {
+    /// Product of every chunk's candidate-position count (saturating). Zero
+    /// when some chunk fits nowhere.
+    pub estimated_nodes: usize,
+    /// True when every chunk has exactly one candidate position, so the
+    /// greedy placement settles the patch without a search.
+    pub is_likely_unique: bool,
+}
@@ -0,0 +1,56 @@
+//! Defines the `RetryStrategy` enum selecting which whitespace modes the
+//! applier escalates through when a patch does not match.
+//!
+//! Conforms to rust coding guidelines (one item per file).
+
+/// The sequence of [`crate::applier::whitespace_mode::WhitespaceMode`]s tried,
+/// in order, until the chunks of a file apply.
+///
+/// Escalation continues past a `PatchConflict`, and past an `AmbiguousPatch`
+/// until `Lenient` has been tried; looser modes only match more lines, so
+/// they cannot resolve an ambiguity that lenient matching could not. When
+/// every mode fails, the error of the last mode tried up to `Lenient` is
+/// reported as the most precise diagnosis.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RetryStrategy {
+    /// `Strict` only.
+    NoRetry,
+    /// `Strict`, then `Lenient`: the plain applier's behaviour.
+    #[default]
+    LenientFallback,
+    /// `Strict`, `IgnoreTrailingWhitespace`, `Lenient`, `SuperLenient`, then
+    /// `Fuzzy` with an edit distance of 2.
+    FullFallback,
+}
+
+impl RetryStrategy {
+    /// The modes this strategy tries, strictest first.
+    pub fn modes(self) -> &'static [crate::applier::whitespace_mode::WhitespaceMode] {
+        use crate::applier::whitespace_mode::WhitespaceMode;
+        match self {
+            RetryStrategy::NoRetry => &[WhitespaceMode::Strict],
+            RetryStrategy::LenientFallback => &[WhitespaceMode::Strict, WhitespaceMode::Lenient],
+            RetryStrategy::FullFallback => &[
+                WhitespaceMode::Strict,
+                WhitespaceMode::IgnoreTrailingWhitespace,
+                WhitespaceMode::Lenient,
+                WhitespaceMode::SuperLenient,
+                WhitespaceMode::FUZZY,
+            ],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RetryStrategy;
+
+    #[test]
+    fn test_modes_escalate_from_strict() {
+        for strategy in [RetryStrategy::NoRetry, RetryStrategy::LenientFallback, RetryStrategy::FullFallback] {
+            let modes = strategy.modes();
+            assert_eq!(modes[0], crate::applier::whitespace_mode::WhitespaceMode::Strict);
+            assert!(modes.windows(2).all(|w| w[0] < w[1]), "{:?}", strategy);
+        }
+    }
+}
@@ -0,0 +1,94 @@
+//! Computes how a patch moves line numbers without producing the new content.
+//!
+//! Conforms to rust coding guidelines (one item per file).
+
+/// Places `chunks` in `original_lines` exactly as the applier would (strict
+/// whitespace first, then lenient) and returns the resulting
+/// [`crate::applier::line_mapping::LineMapping`] instead of the patched text.
+///
+/// Fails with the same `PatchConflict`/`AmbiguousPatch` errors as applying.
+pub fn simulate_apply(
+    original_lines: &[std::string::String],
+    chunks: &[crate::data::chunk::Chunk],
+) -> std::result::Result<crate::applier::line_mapping::LineMapping, crate::error::ZenpatchError> {
+    // Mirrors the empty-file shortcut of the patcher: everything is inserted.
+    if original_lines.is_empty() && chunks.iter().all(|c| c.del_lines.is_empty()) {
+        let inserted = chunks.iter().map(|c| c.ins_lines.len()).sum();
+        return std::result::Result::Ok(crate::applier::line_mapping::LineMapping::from_origins(
+            std::vec![std::option::Option::None; inserted],
+            0,
+        ));
+    }
+    let options = crate::applier::backtracking_options::BacktrackingOptions::default();
+    let origins: std::vec::Vec<std::option::Option<usize>> =
+        (0..original_lines.len()).map(std::option::Option::Some).collect();
+    let strict = crate::applier::whitespace_mode::WhitespaceMode::Strict;
+    let lenient = crate::applier::whitespace_mode::WhitespaceMode::Lenient;
+
+    let (mode, placements) =
+        match crate::applier::backtracking_patcher::find_placements(original_lines, chunks, strict, &options) {
+            std::result::Result::Ok(placements) => (strict, placements),
+            std::result::Result::Err(crate::error::ZenpatchError::PatchConflict { .. })
+            | std::result::Result::Err(crate::error::ZenpatchError::AmbiguousPatch { .. }) => (
+                lenient,
+                crate::applier::backtracking_patcher::find_placements(original_lines, chunks, lenient, &options)?,
+            ),
+            std::result::Result::Err(e) => return std::result::Result::Err(e),
+        };
+    let (_, to_original) = crate::applier::backtracking_patcher::materialize_tracked(
+        original_lines,
+        &origins,
+        chunks,
+        &placements,
+        mode,
+        &options,
+        &|| std::option::Option::None,
+    );
+    std::result::Result::Ok(crate::applier::line_mapping::LineMapping::from_origins(
+        to_original,
+        original_lines.len(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::simulate_apply;
+    use crate::tests::test_macros::{chunks, lines};
+
+    #[test]
+    fn test_mapping_tracks_deletions_and_insertions() {
+        // a b c d e  ->  a B1 B2 c e
+        let mapping =
+            simulate_apply(&lines("a\nb\nc\nd\ne"), &chunks("@@\n a\n-b\n+B1\n+B2\n@@\n c\n-d\n e")).unwrap();
+
+        assert_eq!(mapping.original_to_new(0), Some(0));
+        assert_eq!(mapping.original_to_new(1), None);
+        assert_eq!(mapping.original_to_new(2), Some(3));
+        assert_eq!(mapping.original_to_new(3), None);
+        assert_eq!(mapping.original_to_new(4), Some(4));
+        assert_eq!(mapping.original_to_new(5), None);
+
+        assert_eq!(mapping.new_to_original(1), None);
+        assert_eq!(mapping.new_to_original(2), None);
+        assert_eq!(mapping.new_to_original(3), Some(2));
+        assert_eq!(mapping.new_to_original(4), Some(4));
+    }
+
+    #[test]
+    fn test_mapping_across_chunks_shifts_later_lines() {
+        let mapping = simulate_apply(
+            &lines("a\nb\nc\nd\ne\nf"),
+            &chunks("@@\n a\n+new\n b\n@@\n e\n-f"),
+        )
+        .unwrap();
+        assert_eq!(mapping.original_to_new(3), Some(4));
+        assert_eq!(mapping.original_to_new(5), None);
+        assert_eq!(mapping.new_to_original(1), None);
+    }
+
+    #[test]
+    fn test_conflict_is_reported() {
+        let result = simulate_apply(&lines("a\nb"), &chunks("@@\n zzz\n-b"));
+        assert!(matches!(result, Err(crate::error::ZenpatchError::PatchConflict { .. })));
+    }
+}
@@ -23,8 +23,9 @@ pub struct BacktrackingState {
     /// sorted by position. Mappings with equal keys yield identical files,
     /// so they are deduplicated without materializing the result.
     pub first_solution_key: std::option::Option<std::vec::Vec<(usize, usize)>>,
-    /// Number of search nodes visited; the search aborts as ambiguous past a cap.
-    pub nodes_visited: usize,
+    /// Set when the search was cut off by the node cap (as opposed to having
+    /// found two distinct results).
+    pub node_limit_reached: bool,
 }
 
 impl BacktrackingState {
@@ -37,7 +38,7 @@ impl BacktrackingState {
             first_solution_result: std::option::Option::None,
             solution_path: std::option::Option::None,
             first_solution_key: std::option::Option::None,
-            nodes_visited: 0,
+            node_limit_reached: false,
         }
     }
 }
@@ -4,12 +4,38 @@
 //! whitespace should be handled when comparing lines.
 
 /// Controls whitespace sensitivity when matching patch context and deletions.
-#[derive(Clone, Copy, Debug)]
+///
+/// Variants are ordered from strictest to most lenient, so `max` picks the
+/// more lenient of two modes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum WhitespaceMode {
     /// Exact matching, preserving all whitespace (no normalization).
     Strict,
+    /// Exact matching except for trailing whitespace, which editors and
+    /// models routinely add or strip.
+    IgnoreTrailingWhitespace,
     /// Lenient matching: trims leading/trailing whitespace and collapses internal whitespace runs to single spaces before comparing.
     Lenient,
     /// SuperLenient matching: Lenient plus normalizes special characters like quotes and dashes.
     SuperLenient,
+    /// Fuzzy matching: SuperLenient, but lines also match when their normalized
+    /// forms are within `max_edit_distance` Levenshtein edits of each other.
+    /// Tolerates single-character typos in context lines.
+    Fuzzy {
+        /// Largest number of single-character insertions, deletions or
+        /// substitutions still considered a match.
+        max_edit_distance: usize,
+    },
+    /// Removes every whitespace character before comparing, so
+    /// `"  foo  bar  "` matches `"foobar"`. Never tried automatically; it
+    /// must be requested through `ApplyOptions::whitespace_mode`.
+    IgnoreAllWhitespace,
+}
+
+impl WhitespaceMode {
+    /// Edit distance used by [`WhitespaceMode::FUZZY`].
+    pub const DEFAULT_MAX_EDIT_DISTANCE: usize = 2;
+
+    /// `Fuzzy` with the default edit distance of 2.
+    pub const FUZZY: WhitespaceMode = WhitespaceMode::Fuzzy { max_edit_distance: Self::DEFAULT_MAX_EDIT_DISTANCE };
 }
\ No newline at end of file
@@ -5,20 +5,6 @@
 //! type (Add, Update, Delete), handling different whitespace modes and retries.
 //! Conforms to rust coding guidelines (one item per file).
 
-/// Applies a text-based patch to a Virtual File System (VFS) and returns the new VFS.
-///
-/// This is the primary public API for the `zenpatch` crate. It handles patch
-/// parsing and application for multiple file operations within a single patch.
-///
-/// # Arguments
-///
-/// * `patch_text` - A string slice containing the patch in the expected format.
-/// * `vfs` - A reference to the initial Virtual File System.
-///
-/// # Returns
-///
-/// * `Ok(Vfs)` - The patched VFS on success.
-/// * `Err(ZenpatchError)` - An error if parsing or application fails.
 /// Resolve a patch's target path against the VFS. Returns the exact key when it
 /// exists; otherwise, if the model dropped a leading path prefix (e.g. wrote
 /// `crates/x.rs` for a VFS key `a/b/crates/x.rs`), returns the UNIQUE key whose
@@ -37,146 +23,763 @@ pub fn resolve_vfs_path(vfs: &crate::vfs::Vfs, path: &str) -> std::option::Optio
     std::option::Option::Some(first.clone())
 }
 
+/// Applies a text-based patch to a Virtual File System (VFS) and returns the new VFS.
+///
+/// This is the primary public API for the `zenpatch` crate. It handles patch
+/// parsing and application for multiple file operations within a single patch.
+///
+/// # Arguments
+///
+/// * `patch_text` - A string slice containing the patch in the expected format.
+/// * `vfs` - A reference to the initial Virtual File System.
+///
+/// # Returns
+///
+/// * `Ok(Vfs)` - The patched VFS on success.
+/// * `Err(ZenpatchError)` - An error if parsing or application fails.
 pub fn apply(
     patch_text: &str,
     vfs: &crate::vfs::Vfs,
 ) -> std::result::Result<crate::vfs::Vfs, crate::error::ZenpatchError> {
-    let mut new_vfs = vfs.clone();
+    apply_with_options(patch_text, vfs, &crate::apply_options::ApplyOptions::default())
+}
+
+/// Applies `patch_text` on top of `snapshot` and returns the new state,
+/// leaving `snapshot` untouched (see [`crate::vfs_ext::VfsExt::snapshot`]).
+///
+/// Behaves exactly like [`apply`], which never modifies its input either;
+/// the name states the speculative intent at the call site.
+pub fn apply_to_snapshot(
+    patch_text: &str,
+    snapshot: &crate::vfs::Vfs,
+) -> std::result::Result<crate::vfs::Vfs, crate::error::ZenpatchError> {
+    apply(patch_text, snapshot)
+}
+
+/// Variant of [`apply`] that honours the given [`crate::apply_options::ApplyOptions`].
+///
+/// With `ApplyOptions::default()` this behaves exactly like [`apply`].
+pub fn apply_with_options(
+    patch_text: &str,
+    vfs: &crate::vfs::Vfs,
+    options: &crate::apply_options::ApplyOptions,
+) -> std::result::Result<crate::vfs::Vfs, crate::error::ZenpatchError> {
+    let actions = crate::parser::text_to_patch::text_to_patch(patch_text)?;
+    apply_actions(actions, vfs, options).map(|(new_vfs, _)| new_vfs)
+}
+
+/// Variant of [`apply`] that also returns a [`crate::summary::PatchSummary`] of
+/// what changed: files added/deleted/updated, line counts, and the whitespace
+/// mode that was ultimately needed.
+pub fn apply_with_summary(
+    patch_text: &str,
+    vfs: &crate::vfs::Vfs,
+) -> std::result::Result<(crate::vfs::Vfs, crate::summary::PatchSummary), crate::error::ZenpatchError> {
+    apply_with_summary_and_options(patch_text, vfs, &crate::apply_options::ApplyOptions::default())
+}
+
+/// [`apply_with_summary`] honouring the given [`crate::apply_options::ApplyOptions`];
+/// warnings raised by lenient options land in `PatchSummary::warnings`.
+pub fn apply_with_summary_and_options(
+    patch_text: &str,
+    vfs: &crate::vfs::Vfs,
+    options: &crate::apply_options::ApplyOptions,
+) -> std::result::Result<(crate::vfs::Vfs, crate::summary::PatchSummary), crate::error::ZenpatchError> {
     let actions = crate::parser::text_to_patch::text_to_patch(patch_text)?;
+    apply_actions(actions, vfs, options)
+}
 
+/// Variant of [`apply`] that only places each file's hunks in patch order:
+/// every hunk must land after the previous one. Plain [`apply`] prefers that
+/// order too but falls back to any order that yields a unique result; here
+/// out-of-order hunks are a `PatchConflict`, for patches whose hunks must
+/// take effect in the sequence written.
+pub fn apply_ordered(
+    patch_text: &str,
+    vfs: &crate::vfs::Vfs,
+) -> std::result::Result<crate::vfs::Vfs, crate::error::ZenpatchError> {
+    let options = crate::apply_options::ApplyOptions { require_ordered_application: true, ..std::default::Default::default() };
+    apply_with_options(patch_text, vfs, &options)
+}
+
+/// Variant of [`apply`] that escalates through the whitespace modes of
+/// `strategy` and also returns the loosest mode any file needed, so callers
+/// can warn about how far the match was degraded.
+pub fn apply_with_retry(
+    patch_text: &str,
+    vfs: &crate::vfs::Vfs,
+    strategy: crate::applier::retry_strategy::RetryStrategy,
+) -> std::result::Result<
+    (crate::vfs::Vfs, crate::applier::whitespace_mode::WhitespaceMode),
+    crate::error::ZenpatchError,
+> {
+    let options = crate::apply_options::ApplyOptions { retry_strategy: strategy, ..std::default::Default::default() };
+    apply_with_summary_and_options(patch_text, vfs, &options)
+        .map(|(new_vfs, summary)| (new_vfs, summary.whitespace_mode_used))
+}
+
+/// Variant of [`apply`] that passes every action's `path` and `new_path`
+/// through `path_mapper` before looking it up in (or writing it to) the VFS,
+/// for patches generated relative to a subtree of the VFS (e.g. mapping
+/// `src/Button.tsx` to `packages/ui/src/Button.tsx`).
+///
+/// The same remapping is available alongside other options through
+/// [`crate::apply_options::ApplyOptions::path_mapper`].
+pub fn apply_with_path_prefix_map<F>(
+    patch_text: &str,
+    vfs: &crate::vfs::Vfs,
+    path_mapper: F,
+) -> std::result::Result<crate::vfs::Vfs, crate::error::ZenpatchError>
+where
+    F: Fn(&str) -> std::string::String,
+{
+    let mut actions = crate::parser::text_to_patch::text_to_patch(patch_text)?;
+    map_action_paths(&mut actions, &path_mapper);
+    apply_actions(actions, vfs, &crate::apply_options::ApplyOptions::default()).map(|(new_vfs, _)| new_vfs)
+}
+
+/// Applies already-parsed actions to `vfs`; the shared core of [`apply`] and of
+/// callers that rewrite action paths before applying (e.g. the filesystem API).
+pub(crate) fn apply_actions(
+    actions: std::vec::Vec<crate::data::patch_action::PatchAction>,
+    vfs: &crate::vfs::Vfs,
+    options: &crate::apply_options::ApplyOptions,
+) -> std::result::Result<(crate::vfs::Vfs, crate::summary::PatchSummary), crate::error::ZenpatchError> {
+    apply_actions_observed(actions, vfs, options, &|_| true, &mut crate::observer::nop_observer::NopObserver)
+}
+
+/// Variant of [`apply`] that reports progress to `observer` as each action
+/// starts, as its chunks are applied, and as it completes or fails.
+pub fn apply_with_observer(
+    patch_text: &str,
+    vfs: &crate::vfs::Vfs,
+    observer: &mut dyn crate::observer::patch_observer::PatchObserver,
+) -> std::result::Result<crate::vfs::Vfs, crate::error::ZenpatchError> {
+    apply_filtered_with_observer(patch_text, vfs, |_| true, observer)
+}
+
+/// Variant of [`apply`] that applies only the actions for which `predicate`
+/// returns `true`, in order; the others are skipped silently.
+///
+/// Parse errors are reported before any filtering, and a failing action that
+/// was not skipped still fails the whole patch.
+pub fn apply_filtered<F>(
+    patch_text: &str,
+    vfs: &crate::vfs::Vfs,
+    predicate: F,
+) -> std::result::Result<crate::vfs::Vfs, crate::error::ZenpatchError>
+where
+    F: Fn(&crate::data::patch_action::PatchAction) -> bool,
+{
+    apply_filtered_with_observer(patch_text, vfs, predicate, &mut crate::observer::nop_observer::NopObserver)
+}
+
+/// [`apply_filtered`] reporting progress to `observer`, including an
+/// `on_action_skipped` event for each action the predicate rejects.
+pub fn apply_filtered_with_observer<F>(
+    patch_text: &str,
+    vfs: &crate::vfs::Vfs,
+    predicate: F,
+    observer: &mut dyn crate::observer::patch_observer::PatchObserver,
+) -> std::result::Result<crate::vfs::Vfs, crate::error::ZenpatchError>
+where
+    F: Fn(&crate::data::patch_action::PatchAction) -> bool,
+{
+    let actions = crate::parser::text_to_patch::text_to_patch(patch_text)?;
+    apply_actions_observed(actions, vfs, &crate::apply_options::ApplyOptions::default(), &predicate, observer)
+        .map(|(new_vfs, _)| new_vfs)
+}
+
+/// Variant of [`apply`] that applies only the actions at `indices`, in the
+/// order `indices` lists them rather than the order of the patch.
+///
+/// The whole patch is parsed first; an index past its last action is an
+/// `IndexOutOfBounds` error and nothing is applied. The selected actions are
+/// applied atomically, as [`apply`] applies a whole patch.
+pub fn apply_subset(
+    patch_text: &str,
+    vfs: &crate::vfs::Vfs,
+    indices: &[usize],
+) -> std::result::Result<crate::vfs::Vfs, crate::error::ZenpatchError> {
+    let actions = crate::parser::text_to_patch::text_to_patch(patch_text)?;
+    let selected = indices
+        .iter()
+        .map(|&index| {
+            actions.get(index).cloned().ok_or_else(|| {
+                crate::error::ZenpatchError::IndexOutOfBounds(std::format!(
+                    "action index {} but the patch has {} actions",
+                    index,
+                    actions.len()
+                ))
+            })
+        })
+        .collect::<std::result::Result<std::vec::Vec<_>, _>>()?;
+    apply_actions(selected, vfs, &crate::apply_options::ApplyOptions::default()).map(|(new_vfs, _)| new_vfs)
+}
+
+/// [`apply_actions`] with progress reported to `observer`, skipping actions
+/// that fail `predicate`; stops at the first failing action after reporting it.
+fn apply_actions_observed(
+    actions: std::vec::Vec<crate::data::patch_action::PatchAction>,
+    vfs: &crate::vfs::Vfs,
+    options: &crate::apply_options::ApplyOptions,
+    predicate: &dyn Fn(&crate::data::patch_action::PatchAction) -> bool,
+    observer: &mut dyn crate::observer::patch_observer::PatchObserver,
+) -> std::result::Result<(crate::vfs::Vfs, crate::summary::PatchSummary), crate::error::ZenpatchError> {
+    let mut actions = strip_path_prefixes(actions, options.prefix_strip)?;
+    if let std::option::Option::Some(mapper) = &options.path_mapper {
+        map_action_paths(&mut actions, mapper.as_ref());
+    }
+    let backtracking_options = options.backtracking_options();
+    let mut new_vfs = vfs.clone();
+    let mut summary = crate::summary::PatchSummary::new();
+
+    // A context-only patch would "succeed" without changing anything; hand the
+    // VFS back untouched instead of searching for (and rewriting) its files.
+    // A missing target still fails below like in any other patch.
+    if crate::patch_ops::is_empty_patch(&actions)
+        && actions.iter().all(|action| resolve_vfs_path(&new_vfs, &action.path).is_some())
+    {
+        let message = "patch contains no insertions or deletions; nothing applied";
+        observer.on_warning(message);
+        summary.warnings.push(message.to_string());
+        return std::result::Result::Ok((new_vfs, summary));
+    }
+
+    for (index, action) in actions.iter().enumerate() {
+        let missing = options.ignore_missing_files
+            && match action.type_ {
+                crate::data::action_type::ActionType::Update => !options.create_missing_as_add,
+                crate::data::action_type::ActionType::Delete => true,
+                _ => false,
+            }
+            && resolve_vfs_path(&new_vfs, &action.path).is_none();
+        if missing || !predicate(action) {
+            observer.on_action_skipped(index, action);
+            continue;
+        }
+        observer.on_action_start(index, action);
+        let result = apply_action(action, &mut new_vfs, &mut summary, options, &backtracking_options);
+        if result.is_ok() && action.type_ == crate::data::action_type::ActionType::Update {
+            for chunk_index in 0..action.chunks.len() {
+                observer.on_chunk_applied(index, chunk_index);
+            }
+        }
+        observer.on_action_complete(index, &result);
+        result?;
+    }
+
+    std::result::Result::Ok((new_vfs, summary))
+}
+
+/// Applies `ApplyOptions::prefix_strip` to every action's paths.
+fn strip_path_prefixes(
+    mut actions: std::vec::Vec<crate::data::patch_action::PatchAction>,
+    components: usize,
+) -> std::result::Result<std::vec::Vec<crate::data::patch_action::PatchAction>, crate::error::ZenpatchError> {
+    if components == 0 {
+        return std::result::Result::Ok(actions);
+    }
+    let strip = |path: &str| {
+        path.splitn(components + 1, '/')
+            .nth(components)
+            .filter(|rest| !rest.is_empty())
+            .map(str::to_string)
+            .ok_or_else(|| {
+                crate::error::ZenpatchError::InvalidPatchFormat(format!(
+                    "stripping {} leading path component(s) from '{}' leaves an empty path",
+                    components, path
+                ))
+            })
+    };
+    for action in &mut actions {
+        action.path = strip(&action.path)?;
+        if let std::option::Option::Some(new_path) = &action.new_path {
+            action.new_path = std::option::Option::Some(strip(new_path)?);
+        }
+    }
+    std::result::Result::Ok(actions)
+}
+
+/// Rewrites every action's `path` and `new_path` through `mapper`.
+fn map_action_paths(
+    actions: &mut [crate::data::patch_action::PatchAction],
+    mapper: &dyn Fn(&str) -> std::string::String,
+) {
     for action in actions {
-        match action.type_ {
-            crate::data::action_type::ActionType::Update => {
-                let key = resolve_vfs_path(&new_vfs, &action.path)
-                    .ok_or_else(|| crate::error::ZenpatchError::FileNotFound(action.path.clone()))?;
-                let original_content = new_vfs
-                    .get(&key)
-                    .ok_or_else(|| crate::error::ZenpatchError::FileNotFound(action.path.clone()))?;
+        action.path = mapper(&action.path);
+        if let std::option::Option::Some(new_path) = &action.new_path {
+            action.new_path = std::option::Option::Some(mapper(new_path));
+        }
+    }
+}
 
-                let original_lines: std::vec::Vec<std::string::String> =
-                    original_content.lines().map(std::string::String::from).collect();
+/// Applies one action to `vfs` in place, recording it in `summary`.
+pub(crate) fn apply_action(
+    action: &crate::data::patch_action::PatchAction,
+    vfs: &mut crate::vfs::Vfs,
+    summary: &mut crate::summary::PatchSummary,
+    options: &crate::apply_options::ApplyOptions,
+    backtracking_options: &crate::applier::backtracking_options::BacktrackingOptions,
+) -> std::result::Result<(), crate::error::ZenpatchError> {
+    match action.type_ {
+        crate::data::action_type::ActionType::Update
+            if options.create_missing_as_add && resolve_vfs_path(vfs, &action.path).is_none() =>
+        {
+            let path = action.new_path.clone().unwrap_or_else(|| action.path.clone());
+            if vfs.contains_key(&path) {
+                return std::result::Result::Err(crate::error::ZenpatchError::FileExists(path));
+            }
+            let content: std::vec::Vec<&str> = action.chunks.iter().flat_map(|c| c.ins_lines_iter()).collect();
+            summary.warnings.push(format!(
+                "{}: file not found; created from the patch's inserted lines",
+                action.path
+            ));
+            summary.record(crate::file_summary::FileSummary {
+                path: path.clone(),
+                action: crate::data::action_type::ActionType::Add,
+                lines_inserted: content.len(),
+                lines_deleted: 0,
+                whitespace_mode_used: crate::applier::whitespace_mode::WhitespaceMode::Strict,
+            });
+            vfs.insert(path, content.join("\n"));
+        }
+        crate::data::action_type::ActionType::Update => {
+            let key = resolve_vfs_path(vfs, &action.path)
+                .ok_or_else(|| crate::error::ZenpatchError::FileNotFound(action.path.clone()))?;
+            let (bom, original_content) = split_bom(
+                vfs.get(&key)
+                    .ok_or_else(|| crate::error::ZenpatchError::FileNotFound(action.path.clone()))?,
+                options,
+            );
 
-                // First, try with strict whitespace matching.
-                let result = crate::applier::backtracking_patcher::apply_patch_backtracking_mode(
+            let original_lines: std::vec::Vec<std::string::String> =
+                crate::split_lines::split_lines(original_content);
+            let mut prepared_chunks = std::option::Option::None;
+            if options.normalize_line_endings {
+                prepared_chunks = std::option::Option::Some(strip_carriage_returns(&action.chunks));
+            }
+            if options.trim_leading_context || options.trim_trailing_context {
+                let source = prepared_chunks.as_deref().unwrap_or(&action.chunks);
+                prepared_chunks = std::option::Option::Some(trim_context(source, options));
+            }
+            let chunks: &[crate::data::chunk::Chunk] = prepared_chunks.as_deref().unwrap_or(&action.chunks);
+
+            // Strict first, lenient on conflict/ambiguity. Errors are tagged with the
+            // file path so multi-file patches report WHICH file failed.
+            let mut warnings = std::vec::Vec::new();
+            let merged = merge_verified_chunks(&original_lines, chunks);
+            let merged_attempt = merged.as_deref().and_then(|merged| {
+                let mut merged_warnings = std::vec::Vec::new();
+                apply_chunks_with_fallback(&original_lines, merged, backtracking_options, &mut merged_warnings)
+                    .ok()
+                    .map(|applied| (applied, merged_warnings, merged))
+            });
+            let ((applied_lines, mode_used), applied_chunks) = match merged_attempt {
+                std::option::Option::Some((applied, merged_warnings, merged)) => {
+                    warnings = merged_warnings;
+                    (applied, merged)
+                }
+                // Errors are reported against the chunks as written.
+                std::option::Option::None => (
+                    apply_chunks_with_fallback(&original_lines, chunks, backtracking_options, &mut warnings)
+                        .map_err(|e| {
+                            if options.detect_swapped_markers {
+                                swapped_markers_error(&original_lines, chunks, &action.path, e)
+                            } else {
+                                e.with_path(&action.path)
+                            }
+                        })?,
+                    chunks,
+                ),
+            };
+            if options.verify_post_apply {
+                let misplaced = crate::applier::backtracking_patcher::misplaced_trailing_context(
                     &original_lines,
-                    &action.chunks,
-                    crate::applier::whitespace_mode::WhitespaceMode::Strict,
-                );
-
-                // If it fails with a conflict or ambiguity, retry with lenient whitespace matching.
-                // Errors are tagged with the file path so multi-file patches report WHICH file failed.
-                let applied_lines = match result {
-                    Err(crate::error::ZenpatchError::PatchConflict(_))
-                    | Err(crate::error::ZenpatchError::AmbiguousPatch(_)) => {
-                        crate::applier::backtracking_patcher::apply_patch_backtracking_mode(
-                            &original_lines,
-                            &action.chunks,
-                            crate::applier::whitespace_mode::WhitespaceMode::Lenient,
-                        )
-                        .map_err(|e| e.with_path(&action.path))?
-                    }
-                    Ok(lines) => lines,
-                    Err(e) => return Err(e.with_path(&action.path)),
-                };
-                // Re-join with the file's own dominant line ending and restore its
-                // trailing newline: a one-line patch must not rewrite every line
-                // ending in a CRLF file or strip the final newline.
-                let crlf_count = original_content.matches("\r\n").count();
-                let lf_only_count = original_content.matches('\n').count() - crlf_count;
-                let eol = if crlf_count > lf_only_count { "\r\n" } else { "\n" };
-                let mut updated_content = applied_lines.join(eol);
-                if original_content.ends_with('\n') && !updated_content.is_empty() {
-                    updated_content.push_str(eol);
+                    &without_eof_markers(applied_chunks),
+                    mode_used,
+                    backtracking_options,
+                )
+                .map_err(|e| e.with_path(&action.path))?;
+                if let std::option::Option::Some((chunk_index, detail)) = misplaced {
+                    return std::result::Result::Err(crate::error::ZenpatchError::PatchApplicationFailed(format!(
+                        "post-apply verification failed in '{}' chunk {}: {}",
+                        action.path, chunk_index, detail
+                    )));
                 }
+            }
+            summary
+                .warnings
+                .extend(warnings.into_iter().map(|w| format!("{}: {}", action.path, w)));
+            let updated_content =
+                std::format!("{}{}", bom, rejoin_for_chunks(original_content, &applied_lines, &action.chunks));
 
-                if let Some(new_path) = &action.new_path {
+            let final_path = match &action.new_path {
+                std::option::Option::Some(new_path) => {
                     // Handle rename
-                    new_vfs.remove(&key);
-                    new_vfs.insert(new_path.clone(), updated_content);
-                } else {
-                    new_vfs.insert(key, updated_content);
-                }
-            }
-            crate::data::action_type::ActionType::Add => {
-                if new_vfs.contains_key(&action.path) {
-                    return std::result::Result::Err(crate::error::ZenpatchError::FileExists(
-                        action.path.clone(),
-                    ));
+                    vfs.remove(&key);
+                    new_path.clone()
                 }
-                let content: std::vec::Vec<std::string::String> = action
-                    .chunks
-                    .iter()
-                    .flat_map(|c| c.ins_lines.clone())
-                    .collect();
-                new_vfs.insert(action.path.clone(), content.join("\n"));
+                std::option::Option::None => key,
+            };
+            vfs.insert(final_path.clone(), updated_content);
+            summary.record(crate::file_summary::FileSummary {
+                path: final_path,
+                action: crate::data::action_type::ActionType::Update,
+                lines_inserted: action.total_insertions(),
+                lines_deleted: action.total_deletions(),
+                whitespace_mode_used: mode_used,
+            });
+        }
+        crate::data::action_type::ActionType::Add => {
+            if vfs.contains_key(&action.path) {
+                return std::result::Result::Err(crate::error::ZenpatchError::FileExists(
+                    action.path.clone(),
+                ));
             }
-            crate::data::action_type::ActionType::Delete => {
-                let key = resolve_vfs_path(&new_vfs, &action.path)
-                    .ok_or_else(|| crate::error::ZenpatchError::FileNotFound(action.path.clone()))?;
-                let original_content = new_vfs
-                    .get(&key)
-                    .ok_or_else(|| crate::error::ZenpatchError::FileNotFound(action.path.clone()))?;
-
-                let content_to_delete: std::vec::Vec<std::string::String> = action
-                    .chunks
-                    .iter()
-                    .flat_map(|c| c.del_lines.clone())
-                    .collect();
+            let content: std::vec::Vec<std::string::String> = action
+                .chunks
+                .iter()
+                .flat_map(|c| c.ins_lines.clone())
+                .collect();
+            summary.record(crate::file_summary::FileSummary {
+                path: action.path.clone(),
+                action: crate::data::action_type::ActionType::Add,
+                lines_inserted: content.len(),
+                lines_deleted: 0,
+                whitespace_mode_used: crate::applier::whitespace_mode::WhitespaceMode::Strict,
+            });
+            vfs.insert(action.path.clone(), content.join("\n"));
+        }
+        crate::data::action_type::ActionType::Delete => {
+            let key = resolve_vfs_path(vfs, &action.path)
+                .ok_or_else(|| crate::error::ZenpatchError::FileNotFound(action.path.clone()))?;
+            let (_, original_content) = split_bom(
+                vfs.get(&key)
+                    .ok_or_else(|| crate::error::ZenpatchError::FileNotFound(action.path.clone()))?,
+                options,
+            );
 
-                let original_lines: std::vec::Vec<std::string::String> =
-                    original_content.lines().map(std::string::String::from).collect();
+            let content_to_delete: std::vec::Vec<std::string::String> = action
+                .chunks
+                .iter()
+                .flat_map(|c| c.del_lines.clone())
+                .collect();
 
-                if content_to_delete == original_lines {
-                    new_vfs.remove(&key);
-                } else {
-                    return std::result::Result::Err(crate::error::ZenpatchError::PatchConflict(
-                        format!(
-                            "in {}: content to delete does not match the file's content",
-                            action.path
-                        ),
-                    ));
-                }
+            let original_lines: std::vec::Vec<std::string::String> =
+                crate::split_lines::split_lines(original_content);
+
+            if content_to_delete == original_lines {
+                vfs.remove(&key);
+                summary.record(crate::file_summary::FileSummary {
+                    path: key,
+                    action: crate::data::action_type::ActionType::Delete,
+                    lines_inserted: 0,
+                    lines_deleted: original_lines.len(),
+                    whitespace_mode_used: crate::applier::whitespace_mode::WhitespaceMode::Strict,
+                });
+            } else {
+                return std::result::Result::Err(crate::error::ZenpatchError::PatchConflict {
+                    path: action.path.clone(),
+                    chunk_index: 0,
+                    message: "content to delete does not match the file's content".to_string(),
+                });
+            }
+        }
+        crate::data::action_type::ActionType::Copy => {
+            let key = resolve_vfs_path(vfs, &action.path)
+                .ok_or_else(|| crate::error::ZenpatchError::FileNotFound(action.path.clone()))?;
+            let destination = action.new_path.clone().ok_or_else(|| {
+                crate::error::ZenpatchError::InvalidPatchFormat(format!(
+                    "copy of {} has no destination",
+                    action.path
+                ))
+            })?;
+            if vfs.contains_key(&destination) {
+                return std::result::Result::Err(crate::error::ZenpatchError::FileExists(destination));
+            }
+            let content = vfs[&key].clone();
+            summary.record(crate::file_summary::FileSummary {
+                path: destination.clone(),
+                action: crate::data::action_type::ActionType::Copy,
+                lines_inserted: 0,
+                lines_deleted: 0,
+                whitespace_mode_used: crate::applier::whitespace_mode::WhitespaceMode::Strict,
+            });
+            vfs.insert(destination, content);
+        }
+        crate::data::action_type::ActionType::Rename => {
+            let key = resolve_vfs_path(vfs, &action.path)
+                .ok_or_else(|| crate::error::ZenpatchError::FileNotFound(action.path.clone()))?;
+            let destination = action.new_path.clone().ok_or_else(|| {
+                crate::error::ZenpatchError::InvalidPatchFormat(format!(
+                    "rename of {} has no destination",
+                    action.path
+                ))
+            })?;
+            if vfs.contains_key(&destination) {
+                return std::result::Result::Err(crate::error::ZenpatchError::FileExists(destination));
             }
+            let content = vfs.remove(&key).unwrap_or_default();
+            summary.record(crate::file_summary::FileSummary {
+                path: destination.clone(),
+                action: crate::data::action_type::ActionType::Rename,
+                lines_inserted: 0,
+                lines_deleted: 0,
+                whitespace_mode_used: crate::applier::whitespace_mode::WhitespaceMode::Strict,
+            });
+            vfs.insert(destination, content);
         }
     }
+    std::result::Result::Ok(())
+}
+
+/// Splits a leading UTF-8 BOM off `content` when `ApplyOptions::strip_bom` is
+/// set, returning `(bom, rest)`; `bom` is empty otherwise.
+fn split_bom<'a>(content: &'a str, options: &crate::apply_options::ApplyOptions) -> (&'static str, &'a str) {
+    match content.strip_prefix('\u{FEFF}') {
+        std::option::Option::Some(rest) if options.strip_bom => ("\u{FEFF}", rest),
+        _ => ("", content),
+    }
+}
+
+/// Copies `chunks` with every `\r` removed from their lines, for
+/// `ApplyOptions::normalize_line_endings`.
+fn strip_carriage_returns(chunks: &[crate::data::chunk::Chunk]) -> std::vec::Vec<crate::data::chunk::Chunk> {
+    let strip = |s: &std::string::String| s.replace('\r', "");
+    chunks
+        .iter()
+        .map(|chunk| crate::data::chunk::Chunk {
+            lines: chunk.lines.iter().map(|(lt, content)| (*lt, strip(content))).collect(),
+            del_lines: chunk.del_lines.iter().map(strip).collect(),
+            ins_lines: chunk.ins_lines.iter().map(strip).collect(),
+            change_context: chunk.change_context.as_ref().map(strip),
+            ..chunk.clone()
+        })
+        .collect()
+}
+
+/// Replaces a `PatchConflict` with an `InvalidPatchFormat` naming swapped
+/// `+`/`-` markers when the reversed chunks apply strictly to `lines`;
+/// otherwise returns `error` tagged with `path`.
+fn swapped_markers_error(
+    lines: &[std::string::String],
+    chunks: &[crate::data::chunk::Chunk],
+    path: &str,
+    error: crate::error::ZenpatchError,
+) -> crate::error::ZenpatchError {
+    if !std::matches!(error, crate::error::ZenpatchError::PatchConflict { .. }) {
+        return error.with_path(path);
+    }
+    let reversed: std::vec::Vec<crate::data::chunk::Chunk> = chunks.iter().map(crate::patch_ops::reverse_chunk).collect();
+    let strict_only = crate::applier::backtracking_options::BacktrackingOptions {
+        retry_strategy: crate::applier::retry_strategy::RetryStrategy::NoRetry,
+        ..std::default::Default::default()
+    };
+    match apply_chunks_with_fallback(lines, &reversed, &strict_only, &mut std::vec::Vec::new()) {
+        std::result::Result::Ok(_) => crate::error::ZenpatchError::InvalidPatchFormat(format!(
+            "patch markers appear to be swapped (+/- reversed) in '{}': the patch applies with '+' and '-' exchanged \
+             (or it has already been applied)",
+            path
+        )),
+        std::result::Result::Err(_) => error.with_path(path),
+    }
+}
 
-    std::result::Result::Ok(new_vfs)
+/// Copies of `chunks` without the leading and/or trailing `Context` lines
+/// selected by `options.trim_leading_context` / `options.trim_trailing_context`.
+/// `orig_index` and `forced_position` move past the dropped leading lines.
+fn trim_context(
+    chunks: &[crate::data::chunk::Chunk],
+    options: &crate::apply_options::ApplyOptions,
+) -> std::vec::Vec<crate::data::chunk::Chunk> {
+    chunks
+        .iter()
+        .map(|chunk| {
+            let mut chunk = chunk.clone();
+            if chunk.is_context_only() {
+                return chunk;
+            }
+            if options.trim_trailing_context && !chunk.is_end_of_file {
+                let keep = chunk.lines.len() - chunk.trailing_context_count();
+                chunk.lines.truncate(keep);
+            }
+            if options.trim_leading_context && !chunk.is_pure_insertion() {
+                let leading = chunk.leading_context_count();
+                chunk.lines.drain(..leading);
+                chunk.orig_index += leading;
+                chunk.forced_position = chunk.forced_position.map(|pos| pos + leading);
+            }
+            chunk.recompute_del_ins_lines();
+            chunk
+        })
+        .collect()
 }
 
 /// Re-joins patched lines with the file's dominant EOL and restores its trailing
 /// newline (so a one-line patch doesn't rewrite every ending or drop the final \n).
-fn rejoin(original_content: &str, applied_lines: &[std::string::String]) -> std::string::String {
-    let crlf_count = original_content.matches("\r\n").count();
-    let lf_only_count = original_content.matches('\n').count() - crlf_count;
-    let eol = if crlf_count > lf_only_count { "\r\n" } else { "\n" };
+/// A lone `\r` (classic Mac OS) counts as a line ending of its own.
+pub(crate) fn rejoin(original_content: &str, applied_lines: &[std::string::String]) -> std::string::String {
+    let eol = line_ending_of(original_content);
     let mut updated = applied_lines.join(eol);
-    if original_content.ends_with('\n') && !updated.is_empty() {
+    if (original_content.ends_with('\n') || original_content.ends_with('\r')) && !updated.is_empty() {
         updated.push_str(eol);
     }
     updated
 }
 
+/// [`rejoin`], except that a `\ No newline at end of file` marker in the last
+/// chunk decides the trailing newline instead of the original content.
+fn rejoin_for_chunks(
+    original_content: &str,
+    applied_lines: &[std::string::String],
+    chunks: &[crate::data::chunk::Chunk],
+) -> std::string::String {
+    let mut updated = rejoin(original_content, applied_lines);
+    let eol = line_ending_of(original_content);
+    match chunks.last().and_then(crate::data::chunk::Chunk::trailing_newline) {
+        std::option::Option::Some(false) if updated.ends_with(eol) => updated.truncate(updated.len() - eol.len()),
+        std::option::Option::Some(true) if !updated.is_empty() && !updated.ends_with(eol) => updated.push_str(eol),
+        _ => {}
+    }
+    updated
+}
+
+/// The dominant line terminator of `content` (`\n` when there is none).
+fn line_ending_of(content: &str) -> &'static str {
+    let crlf_count = content.matches("\r\n").count();
+    let lf_only_count = content.matches('\n').count() - crlf_count;
+    let cr_only_count = content.matches('\r').count() - crlf_count;
+    if cr_only_count > lf_only_count && cr_only_count > crlf_count {
+        "\r"
+    } else if crlf_count > lf_only_count {
+        "\r\n"
+    } else {
+        "\n"
+    }
+}
+
+/// Applies Update chunks to `lines` in `options.whitespace_mode` when one is
+/// set. Otherwise matches strictly, retrying with lenient matching if the
+/// strict search hits a conflict or ambiguity.
+/// Returns the patched lines and the whitespace mode that succeeded.
+fn apply_chunks_with_fallback(
+    lines: &[std::string::String],
+    chunks: &[crate::data::chunk::Chunk],
+    options: &crate::applier::backtracking_options::BacktrackingOptions,
+    warnings: &mut std::vec::Vec<std::string::String>,
+) -> std::result::Result<
+    (std::vec::Vec<std::string::String>, crate::applier::whitespace_mode::WhitespaceMode),
+    crate::error::ZenpatchError,
+> {
+    // `\ No newline at end of file` markers only steer the final rejoin; the
+    // matcher never sees them.
+    let stripped;
+    let chunks = if chunks.iter().any(|c| c.trailing_newline().is_some()) {
+        stripped = without_eof_markers(chunks);
+        &stripped[..]
+    } else {
+        chunks
+    };
+    if let std::option::Option::Some(applier) = &options.chunk_applier {
+        return applier.apply(lines, chunks).map(|applied| (applied, applier.mode()));
+    }
+    if let std::option::Option::Some(mode) = options.whitespace_mode {
+        return crate::applier::backtracking_patcher::apply_patch_greedy_with_options(lines, chunks, mode, options, warnings)
+            .map(|applied| (applied, mode));
+    }
+    let lenient = crate::applier::whitespace_mode::WhitespaceMode::Lenient;
+    let mut reported: std::option::Option<crate::error::ZenpatchError> = std::option::Option::None;
+    for &mode in options.retry_strategy.modes() {
+        let err = match crate::applier::backtracking_patcher::apply_patch_greedy_with_options(
+            lines, chunks, mode, options, warnings,
+        ) {
+            std::result::Result::Ok(applied) => return std::result::Result::Ok((applied, mode)),
+            std::result::Result::Err(err) => err,
+        };
+        // Looser modes only help when lines did not match; an ambiguity would
+        // just get worse once lenient matching has been tried. Errors up to
+        // `Lenient` are the more precise diagnosis.
+        let retry = match err {
+            crate::error::ZenpatchError::PatchConflict { .. } => true,
+            crate::error::ZenpatchError::AmbiguousPatch { .. } => mode < lenient,
+            _ => false,
+        };
+        if mode <= lenient || reported.is_none() {
+            reported = std::option::Option::Some(err);
+        }
+        if !retry {
+            break;
+        }
+    }
+    std::result::Result::Err(reported.unwrap_or_else(|| {
+        crate::error::ZenpatchError::PatchApplicationFailed("no whitespace mode to try".to_string())
+    }))
+}
+
+/// `chunks` with adjacent pairs combined by
+/// [`crate::patch_ops::merge_two_chunks`], so the search places fewer chunks.
+/// A pair is only combined when every context and deleted line of the
+/// combined chunk appears in `lines`, in order, at exactly one place, which
+/// confirms the two chunks really are neighbours. `None` when nothing merged.
+fn merge_verified_chunks(
+    lines: &[std::string::String],
+    chunks: &[crate::data::chunk::Chunk],
+) -> std::option::Option<std::vec::Vec<crate::data::chunk::Chunk>> {
+    let occurs_once = |chunk: &crate::data::chunk::Chunk| {
+        let old_side: std::vec::Vec<&str> = chunk
+            .into_iter()
+            .filter(|(lt, _)| {
+                matches!(lt, crate::data::line_type::LineType::Context | crate::data::line_type::LineType::Deletion)
+            })
+            .map(|(_, content)| content)
+            .collect();
+        !old_side.is_empty()
+            && lines
+                .windows(old_side.len())
+                .filter(|window| window.iter().zip(&old_side).all(|(a, b)| a == b))
+                .take(2)
+                .count()
+                == 1
+    };
+    let mut merged: std::vec::Vec<crate::data::chunk::Chunk> = std::vec::Vec::with_capacity(chunks.len());
+    for chunk in chunks {
+        let combined = merged
+            .last()
+            .and_then(|last| crate::patch_ops::merge_two_chunks(last, chunk))
+            .filter(|combined| occurs_once(combined));
+        match combined {
+            std::option::Option::Some(combined) => *merged.last_mut().unwrap() = combined,
+            std::option::Option::None => merged.push(chunk.clone()),
+        }
+    }
+    (merged.len() < chunks.len()).then_some(merged)
+}
+
+/// Copies of `chunks` without their `NoNewlineAtEOF` marker lines.
+fn without_eof_markers(chunks: &[crate::data::chunk::Chunk]) -> std::vec::Vec<crate::data::chunk::Chunk> {
+    chunks
+        .iter()
+        .map(|chunk| {
+            let mut chunk = chunk.clone();
+            chunk.lines.retain(|(lt, _)| *lt != crate::data::line_type::LineType::NoNewlineAtEOF);
+            chunk
+        })
+        .collect()
+}
+
 /// Applies a single Update chunk to `lines`, trying strict then lenient whitespace.
-fn apply_one_chunk(
+pub(crate) fn apply_one_chunk(
     lines: &[std::string::String],
     chunk: &crate::data::chunk::Chunk,
 ) -> std::result::Result<std::vec::Vec<std::string::String>, crate::error::ZenpatchError> {
-    let single = std::slice::from_ref(chunk);
-    match crate::applier::backtracking_patcher::apply_patch_backtracking_mode(
+    apply_chunks_with_fallback(
         lines,
-        single,
-        crate::applier::whitespace_mode::WhitespaceMode::Strict,
-    ) {
-        std::result::Result::Err(crate::error::ZenpatchError::PatchConflict(_))
-        | std::result::Result::Err(crate::error::ZenpatchError::AmbiguousPatch(_)) => {
-            crate::applier::backtracking_patcher::apply_patch_backtracking_mode(
-                lines,
-                single,
-                crate::applier::whitespace_mode::WhitespaceMode::Lenient,
-            )
-        }
-        other => other,
-    }
+        std::slice::from_ref(chunk),
+        &crate::applier::backtracking_options::BacktrackingOptions::default(),
+        &mut std::vec::Vec::new(),
+    )
+    .map(|(applied, _)| applied)
 }
 
 /// The outcome of a best-effort (partial) patch application.
@@ -189,7 +792,36 @@ pub struct PartialReport {
     pub skipped: std::vec::Vec<std::string::String>,
 }
 
-/// Best-effort variant of [`apply`]: applies every hunk it can and SKIPS the ones
+/// Action-level best-effort variant of [`apply`]: attempts EVERY action and
+/// collects the failures instead of aborting on the first one.
+///
+/// Each action is applied atomically on top of the results of the actions
+/// before it; a failing action leaves the VFS as it was and is reported as
+/// `(action index, error)`. The outer `Err` is reserved for patches that do not
+/// parse. See [`apply_partial_hunks`] for skipping individual hunks instead.
+pub fn apply_partial(
+    patch_text: &str,
+    vfs: &crate::vfs::Vfs,
+) -> std::result::Result<
+    (crate::vfs::Vfs, std::vec::Vec<(usize, crate::error::ZenpatchError)>),
+    crate::error::ZenpatchError,
+> {
+    let actions = crate::parser::text_to_patch::text_to_patch(patch_text)?;
+    let options = crate::apply_options::ApplyOptions::default();
+    let mut new_vfs = vfs.clone();
+    let mut failures = std::vec::Vec::new();
+
+    for (index, action) in actions.into_iter().enumerate() {
+        match apply_actions(std::vec![action], &new_vfs, &options) {
+            std::result::Result::Ok((updated, _)) => new_vfs = updated,
+            std::result::Result::Err(e) => failures.push((index, e)),
+        }
+    }
+
+    std::result::Result::Ok((new_vfs, failures))
+}
+
+/// Hunk-level best-effort variant of [`apply`]: applies every hunk it can and SKIPS the ones
 /// that don't, instead of rejecting the whole patch when a single hunk is wrong.
 ///
 /// For each Update file, the full set of hunks is first attempted atomically (the
@@ -197,7 +829,7 @@ pub struct PartialReport {
 /// each hunk independently, dropping the ones that conflict. The returned
 /// [`PartialReport`] lists what was skipped so the caller can re-prompt for just
 /// those. Only an unparseable patch returns `Err`.
-pub fn apply_partial(
+pub fn apply_partial_hunks(
     patch_text: &str,
     vfs: &crate::vfs::Vfs,
 ) -> std::result::Result<(crate::vfs::Vfs, PartialReport), crate::error::ZenpatchError> {
@@ -217,24 +849,16 @@ pub fn apply_partial(
                 };
                 let original_content = new_vfs.get(&key).map(|c| c.to_string()).unwrap_or_default();
                 let original_lines: std::vec::Vec<std::string::String> =
-                    original_content.lines().map(std::string::String::from).collect();
+                    crate::split_lines::split_lines(&original_content);
 
                 // 1. Try all hunks atomically (best fidelity / disambiguation).
-                let atomic = match crate::applier::backtracking_patcher::apply_patch_backtracking_mode(
+                let atomic = apply_chunks_with_fallback(
                     &original_lines,
                     &action.chunks,
-                    crate::applier::whitespace_mode::WhitespaceMode::Strict,
-                ) {
-                    std::result::Result::Err(crate::error::ZenpatchError::PatchConflict(_))
-                    | std::result::Result::Err(crate::error::ZenpatchError::AmbiguousPatch(_)) => {
-                        crate::applier::backtracking_patcher::apply_patch_backtracking_mode(
-                            &original_lines,
-                            &action.chunks,
-                            crate::applier::whitespace_mode::WhitespaceMode::Lenient,
-                        )
-                    }
-                    other => other,
-                };
+                    &crate::applier::backtracking_options::BacktrackingOptions::default(),
+                    &mut std::vec::Vec::new(),
+                )
+                .map(|(applied, _)| applied);
 
                 let final_lines = match atomic {
                     std::result::Result::Ok(lines) => {
@@ -267,7 +891,7 @@ pub fn apply_partial(
                 if final_lines == original_lines {
                     continue; // nothing applied for this file
                 }
-                let updated_content = rejoin(&original_content, &final_lines);
+                let updated_content = rejoin_for_chunks(&original_content, &final_lines, &action.chunks);
                 if let Some(new_path) = &action.new_path {
                     new_vfs.remove(&key);
                     new_vfs.insert(new_path.clone(), updated_content);
@@ -297,7 +921,7 @@ pub fn apply_partial(
                 let content_to_delete: std::vec::Vec<std::string::String> =
                     action.chunks.iter().flat_map(|c| c.del_lines.clone()).collect();
                 let original_lines: std::vec::Vec<std::string::String> =
-                    original_content.lines().map(std::string::String::from).collect();
+                    crate::split_lines::split_lines(&original_content);
                 if content_to_delete == original_lines {
                     new_vfs.remove(&key);
                     report.applied_hunks += 1;
@@ -305,6 +929,19 @@ pub fn apply_partial(
                     report.skipped.push(format!("{}: delete skipped (content mismatch)", action.path));
                 }
             }
+            crate::data::action_type::ActionType::Copy | crate::data::action_type::ActionType::Rename => {
+                let options = crate::apply_options::ApplyOptions::default();
+                match apply_actions(std::vec![action.clone()], &new_vfs, &options) {
+                    std::result::Result::Ok((updated, _)) => {
+                        new_vfs = updated;
+                        report.applied_hunks += 1;
+                    }
+                    std::result::Result::Err(e) => {
+                        let verb = if action.type_ == crate::data::action_type::ActionType::Copy { "copy" } else { "rename" };
+                        report.skipped.push(format!("{}: {} skipped ({})", action.path, verb, e));
+                    }
+                }
+            }
         }
     }
 
@@ -312,6 +949,7 @@ pub fn apply_partial(
 }
 
 #[cfg(test)]
+#[allow(clippy::unnecessary_get_then_check)]
 mod tests {
     // Note: VFS-based tests.
     use crate::vfs::Vfs;
@@ -323,27 +961,145 @@ mod tests {
     }
 
     #[test]
-    fn test_apply_partial_keeps_good_hunk_drops_bad() {
+    fn test_apply_with_summary_counts_changes() {
+        let patch = "*** Begin Patch\n\
+*** Add File: new.txt\n+n1\n+n2\n\
+*** Update File: a.txt\n@@\n-a\n+b\n+c\n\
+*** Delete File: old.txt\n-o1\n-o2\n-o3\n\
+*** End Patch";
+        let mut vfs = vfs_from_str("a.txt", "a");
+        vfs.insert("old.txt".to_string(), "o1\no2\no3".to_string());
+        let (out, summary) = super::apply_with_summary(patch, &vfs).unwrap();
+        assert_eq!(out.get("a.txt").unwrap(), "b\nc");
+        assert_eq!(summary.files_added, 1);
+        assert_eq!(summary.files_updated, 1);
+        assert_eq!(summary.files_deleted, 1);
+        assert_eq!(summary.lines_inserted, 4);
+        assert_eq!(summary.lines_deleted, 4);
+        assert_eq!(
+            summary.whitespace_mode_used,
+            crate::applier::whitespace_mode::WhitespaceMode::Strict
+        );
+        let paths: std::vec::Vec<&str> = summary.per_file.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["new.txt", "a.txt", "old.txt"]);
+        assert_eq!(summary.per_file[1].lines_inserted, 2);
+        assert_eq!(summary.per_file[1].lines_deleted, 1);
+    }
+
+    #[test]
+    fn test_apply_with_summary_reports_lenient_fallback() {
+        let patch = "*** Begin Patch\n*** Update File: a.txt\n@@\n ctx  line\n-a\n+b\n*** End Patch";
+        let vfs = vfs_from_str("a.txt", "ctx line\na");
+        let (out, summary) = super::apply_with_summary(patch, &vfs).unwrap();
+        assert_eq!(out.get("a.txt").unwrap(), "ctx line\nb");
+        assert_eq!(
+            summary.whitespace_mode_used,
+            crate::applier::whitespace_mode::WhitespaceMode::Lenient
+        );
+    }
+
+    /// An ambiguity cut off by the node cap is applied under `AcceptFirst`
+    /// and surfaced as a warning naming the file.
+    #[test]
+    fn test_accept_first_warning_reaches_summary() {
+        let patch = "*** Begin Patch\n*** Update File: a.txt\n@@\n-x\n@@\n-x\n*** End Patch";
+        let vfs = vfs_from_str("a.txt", "x\nx\nx");
+        let options = crate::apply_options::ApplyOptions {
+            ambiguity_policy: crate::applier::ambiguity_policy::AmbiguityPolicy::AcceptFirst,
+            max_backtrack_nodes: Some(3),
+            ..Default::default()
+        };
+        let (out, summary) = super::apply_with_summary_and_options(patch, &vfs, &options).unwrap();
+        assert_eq!(out.get("a.txt").unwrap(), "x");
+        assert_eq!(summary.warnings.len(), 1);
+        assert!(summary.warnings[0].starts_with("a.txt: "));
+    }
+
+    /// A contextless deletion of a repeated line is ambiguous by default; with
+    /// `prefer_first_occurrence` it removes the first occurrence.
+    #[test]
+    fn test_prefer_first_occurrence_for_contextless_deletion() {
+        let patch = "*** Begin Patch\n*** Update File: a.txt\n@@\n-dup\n*** End Patch";
+        let vfs = vfs_from_str("a.txt", "x\ndup\ny\ndup\nz");
+        assert!(matches!(
+            super::apply(patch, &vfs),
+            Err(crate::error::ZenpatchError::AmbiguousPatch { .. })
+        ));
+        let options = crate::apply_options::ApplyOptions {
+            prefer_first_occurrence: true,
+            ..Default::default()
+        };
+        let out = super::apply_with_options(patch, &vfs, &options).unwrap();
+        assert_eq!(out.get("a.txt").unwrap(), "x\ny\ndup\nz");
+    }
+
+    /// Re-applying an insertion patch under `skip_redundant_insertions` must
+    /// not double the inserted lines; without the flag it does.
+    #[test]
+    fn test_skip_redundant_insertions_makes_reapply_idempotent() {
+        let patch = "*** Begin Patch\n*** Update File: a.txt\n@@\n a\n+b1\n+b2\n c\n*** End Patch";
+        let options = crate::apply_options::ApplyOptions {
+            skip_redundant_insertions: true,
+            ..Default::default()
+        };
+        let vfs = vfs_from_str("a.txt", "a\nc\n");
+        let once = super::apply_with_options(patch, &vfs, &options).unwrap();
+        assert_eq!(once.get("a.txt").unwrap(), "a\nb1\nb2\nc\n");
+        let twice = super::apply_with_options(patch, &once, &options).unwrap();
+        assert_eq!(twice.get("a.txt").unwrap(), "a\nb1\nb2\nc\n");
+
+        let doubled = super::apply(patch, &once).unwrap();
+        assert_eq!(doubled.get("a.txt").unwrap(), "a\nb1\nb2\nb1\nb2\nc\n");
+    }
+
+    /// One failing action out of three: the other two land, the failure is
+    /// reported with its action index.
+    #[test]
+    fn test_apply_partial_collects_per_action_errors() {
+        let patch = "*** Begin Patch\n\
+*** Update File: a.txt\n@@\n-a\n+A\n\
+*** Update File: b.txt\n@@\n-ghost\n+x\n\
+*** Add File: c.txt\n+c\n\
+*** End Patch";
+        let mut vfs = vfs_from_str("a.txt", "a");
+        vfs.insert("b.txt".to_string(), "b".to_string());
+        let (out, failures) = super::apply_partial(patch, &vfs).unwrap();
+        assert_eq!(out.get("a.txt").unwrap(), "A");
+        assert_eq!(out.get("b.txt").unwrap(), "b");
+        assert_eq!(out.get("c.txt").unwrap(), "c");
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].0, 1);
+        assert!(matches!(failures[0].1, crate::error::ZenpatchError::PatchConflict { .. }));
+    }
+
+    #[test]
+    fn test_apply_partial_parse_failure_is_outer_err() {
+        let vfs = Vfs::new();
+        assert!(super::apply_partial("not a patch", &vfs).is_err());
+    }
+
+    #[test]
+    fn test_apply_partial_hunks_keeps_good_hunk_drops_bad() {
         // Two hunks for one file: the first is applyable, the second's context
-        // ("ghost") does not exist. apply_partial must land the good one and skip
+        // ("ghost") does not exist. apply_partial_hunks must land the good one and skip
         // the bad one (where atomic `apply` would reject the whole patch).
         let patch = "*** Begin Patch\n*** Update File: a.txt\n@@\n-a\n+A\n@@\n ghost\n-real\n+REAL\n*** End Patch";
         let vfs = vfs_from_str("a.txt", "a\nb\nreal");
         // atomic apply fails outright
         assert!(super::apply(patch, &vfs).is_err());
         // partial apply lands the good hunk, reports the bad one
-        let (out, report) = super::apply_partial(patch, &vfs).unwrap();
+        let (out, report) = super::apply_partial_hunks(patch, &vfs).unwrap();
         assert_eq!(out.get("a.txt").unwrap(), "A\nb\nreal");
         assert_eq!(report.applied_hunks, 1);
         assert_eq!(report.skipped.len(), 1);
-        assert!(report.skipped[0].contains("ghost") || report.skipped[0].contains("hunk 2"));
+        assert!(report.skipped[0].starts_with("a.txt: hunk 2 skipped: "), "{}", report.skipped[0]);
     }
 
     #[test]
-    fn test_apply_partial_clean_patch_applies_all() {
+    fn test_apply_partial_hunks_clean_patch_applies_all() {
         let patch = "*** Begin Patch\n*** Update File: a.txt\n@@\n-a\n+A\n*** End Patch";
         let vfs = vfs_from_str("a.txt", "a\nz\n");
-        let (out, report) = super::apply_partial(patch, &vfs).unwrap();
+        let (out, report) = super::apply_partial_hunks(patch, &vfs).unwrap();
         assert_eq!(out.get("a.txt").unwrap(), "A\nz\n");
         assert!(report.skipped.is_empty());
         assert_eq!(report.applied_hunks, 1);
@@ -409,6 +1165,216 @@ mod tests {
         assert_eq!(super::resolve_vfs_path(&vfs, "x.rs"), None);
     }
 
+    #[test]
+    fn test_apply_copy_duplicates_file() {
+        let patch = "*** Begin Patch\n*** Copy File: a.txt\n*** To: b.txt\n*** End Patch";
+        let vfs = vfs_from_str("a.txt", "one\ntwo\n");
+        let out = super::apply(patch, &vfs).unwrap();
+        assert_eq!(out.get("a.txt").unwrap(), "one\ntwo\n");
+        assert_eq!(out.get("b.txt").unwrap(), "one\ntwo\n");
+    }
+
+    #[test]
+    fn test_apply_copy_missing_source_fails() {
+        let patch = "*** Begin Patch\n*** Copy File: a.txt\n*** To: b.txt\n*** End Patch";
+        let vfs = Vfs::new();
+        assert_eq!(
+            super::apply(patch, &vfs).unwrap_err(),
+            crate::error::ZenpatchError::FileNotFound("a.txt".to_string())
+        );
+    }
+
+    #[test]
+    fn test_apply_copy_onto_existing_fails() {
+        let patch = "*** Begin Patch\n*** Copy File: a.txt\n*** To: b.txt\n*** End Patch";
+        let mut vfs = vfs_from_str("a.txt", "a");
+        vfs.insert("b.txt".to_string(), "b".to_string());
+        assert_eq!(
+            super::apply(patch, &vfs).unwrap_err(),
+            crate::error::ZenpatchError::FileExists("b.txt".to_string())
+        );
+    }
+
+    #[test]
+    fn test_rename_and_legacy_move_produce_identical_vfs() {
+        let rename = "*** Begin Patch\n*** Rename File: a.txt\n*** To: dir/b.txt\n*** End Patch";
+        let legacy = "*** Begin Patch\n*** Update File: a.txt\n*** Move to: dir/b.txt\n*** End Patch";
+        let vfs = vfs_from_str("a.txt", "one\ntwo\n");
+
+        let renamed = super::apply(rename, &vfs).unwrap();
+        assert_eq!(renamed.get("dir/b.txt").unwrap(), "one\ntwo\n");
+        assert!(!renamed.contains_key("a.txt"));
+        assert_eq!(renamed, super::apply(legacy, &vfs).unwrap());
+    }
+
+    #[test]
+    fn test_rename_errors() {
+        let rename = "*** Begin Patch\n*** Rename File: a.txt\n*** To: b.txt\n*** End Patch";
+        assert_eq!(
+            super::apply(rename, &Vfs::new()).unwrap_err(),
+            crate::error::ZenpatchError::FileNotFound("a.txt".to_string())
+        );
+        let mut vfs = vfs_from_str("a.txt", "a");
+        vfs.insert("b.txt".to_string(), "b".to_string());
+        assert_eq!(
+            super::apply(rename, &vfs).unwrap_err(),
+            crate::error::ZenpatchError::FileExists("b.txt".to_string())
+        );
+    }
+
+    #[test]
+    fn test_no_newline_marker_after_insertion_drops_trailing_newline() {
+        let patch = "*** Begin Patch\n*** Update File: a.js\n@@ -1,2 +1,2 @@\n a\n-b\n+c\n\\ No newline at end of file\n*** End Patch";
+        let vfs = vfs_from_str("a.js", "a\nb\n");
+        assert_eq!(super::apply(patch, &vfs).unwrap().get("a.js").unwrap(), "a\nc");
+    }
+
+    #[test]
+    fn test_no_newline_marker_after_deletion_adds_trailing_newline() {
+        let patch = "*** Begin Patch\n*** Update File: a.js\n@@\n a\n-b\n\\ No newline at end of file\n+c\n*** End Patch";
+        let vfs = vfs_from_str("a.js", "a\nb");
+        assert_eq!(super::apply(patch, &vfs).unwrap().get("a.js").unwrap(), "a\nc\n");
+    }
+
+    #[test]
+    fn test_missing_trailing_newline_preserved_when_last_line_untouched() {
+        let patch = "*** Begin Patch\n*** Update File: a.js\n@@\n-a\n+x\n b\n*** End Patch";
+        let vfs = vfs_from_str("a.js", "a\nb\nc");
+        assert_eq!(super::apply(patch, &vfs).unwrap().get("a.js").unwrap(), "x\nb\nc");
+    }
+
+    #[test]
+    fn test_fuzzy_matching_is_opt_in() {
+        let patch = "*** Begin Patch\n*** Update File: a.rs\n@@\n fn mian() {\n-    old();\n+    new();\n }\n*** End Patch";
+        let vfs = vfs_from_str("a.rs", "fn main() {\n    old();\n}\n");
+        assert!(super::apply(patch, &vfs).is_err());
+
+        let options = crate::apply_options::ApplyOptions { fuzzy_matching: true, ..Default::default() };
+        let (new_vfs, summary) = super::apply_with_summary_and_options(patch, &vfs, &options).unwrap();
+        assert_eq!(new_vfs.get("a.rs").unwrap(), "fn main() {\n    new();\n}\n");
+        assert_eq!(
+            summary.whitespace_mode_used,
+            crate::applier::whitespace_mode::WhitespaceMode::FUZZY
+        );
+    }
+
+    #[test]
+    fn test_ignore_all_whitespace_must_be_requested() {
+        let patch = "*** Begin Patch\n*** Update File: a.js\n@@\n function f(a,b){\n-  return a+b;\n+  return a - b;\n }\n*** End Patch";
+        let vfs = vfs_from_str("a.js", "function f (a, b) {\n        return a + b;\n}\n");
+        assert!(super::apply(patch, &vfs).is_err());
+
+        let options = crate::apply_options::ApplyOptions {
+            whitespace_mode: Some(crate::applier::whitespace_mode::WhitespaceMode::IgnoreAllWhitespace),
+            ..Default::default()
+        };
+        let (new_vfs, summary) = super::apply_with_summary_and_options(patch, &vfs, &options).unwrap();
+        assert_eq!(new_vfs.get("a.js").unwrap(), "function f (a, b) {\n  return a - b;\n}\n");
+        assert_eq!(
+            summary.whitespace_mode_used,
+            crate::applier::whitespace_mode::WhitespaceMode::IgnoreAllWhitespace
+        );
+    }
+
+    #[test]
+    fn test_explicit_whitespace_mode_disables_fallback() {
+        let patch = "*** Begin Patch\n*** Update File: a.txt\n@@\n a\n-b\n+B\n*** End Patch";
+        let vfs = vfs_from_str("a.txt", "  a\nb\n");
+        let options = crate::apply_options::ApplyOptions {
+            whitespace_mode: Some(crate::applier::whitespace_mode::WhitespaceMode::Strict),
+            ..Default::default()
+        };
+        assert!(super::apply_with_options(patch, &vfs, &options).is_err());
+        assert!(super::apply(patch, &vfs).is_ok());
+    }
+
+    #[test]
+    fn test_verify_post_apply_rejects_wrong_trailing_context() {
+        // Leading context and deletion match; the trailing context does not.
+        let patch = "*** Begin Patch\n*** Update File: a.txt\n@@\n a\n-b\n+B\n zzz\n*** End Patch";
+        let vfs = vfs_from_str("a.txt", "a\nb\nc\n");
+        assert!(super::apply(patch, &vfs).is_ok());
+
+        let options = crate::apply_options::ApplyOptions { verify_post_apply: true, ..Default::default() };
+        match super::apply_with_options(patch, &vfs, &options) {
+            Err(crate::error::ZenpatchError::PatchApplicationFailed(msg)) => {
+                assert!(msg.contains("'a.txt' chunk 0") && msg.contains("zzz"), "{}", msg)
+            }
+            other => panic!("expected a verification failure, got {:?}", other),
+        }
+
+        let good = "*** Begin Patch\n*** Update File: a.txt\n@@\n a\n-b\n+B\n c\n*** End Patch";
+        assert_eq!(super::apply_with_options(good, &vfs, &options).unwrap()["a.txt"], "a\nB\nc\n");
+    }
+
+    #[test]
+    fn test_ignore_missing_files_skips_absent_targets() {
+        let patch = "*** Begin Patch\n*** Update File: a.txt\n@@\n-a\n+A\n*** Update File: gone.txt\n@@\n-x\n+y\n*** Delete File: old.txt\n-o\n*** End Patch";
+        let vfs = vfs_from_str("a.txt", "a");
+        assert!(matches!(super::apply(patch, &vfs), Err(crate::error::ZenpatchError::FileNotFound(_))));
+
+        let options = crate::apply_options::ApplyOptions { ignore_missing_files: true, ..Default::default() };
+        let new_vfs = super::apply_with_options(patch, &vfs, &options).unwrap();
+        assert_eq!(new_vfs.len(), 1);
+        assert_eq!(new_vfs["a.txt"], "A");
+
+        let add = "*** Begin Patch\n*** Add File: a.txt\n+a\n*** End Patch";
+        assert!(matches!(
+            super::apply_with_options(add, &vfs, &options),
+            Err(crate::error::ZenpatchError::FileExists(_))
+        ));
+    }
+
+    #[test]
+    fn test_create_missing_as_add_rebuilds_vfs_from_patch_log() {
+        let log = [
+            "*** Begin Patch\n*** Update File: a.txt\n@@\n-old\n+one\n+two\n*** Update File: src/b.rs\n@@\n+fn b() {}\n*** End Patch",
+            "*** Begin Patch\n*** Update File: a.txt\n@@\n one\n-two\n+TWO\n*** End Patch",
+            "*** Begin Patch\n*** Update File: c.txt\n*** Move to: d.txt\n@@\n ctx\n+d\n*** End Patch",
+        ];
+        let options = crate::apply_options::ApplyOptions { create_missing_as_add: true, ..Default::default() };
+        let mut vfs = crate::vfs::Vfs::new();
+        let mut warnings = Vec::new();
+        for patch in log {
+            let (next, summary) = super::apply_with_summary_and_options(patch, &vfs, &options).unwrap();
+            vfs = next;
+            warnings.extend(summary.warnings);
+        }
+        assert_eq!(vfs.len(), 3);
+        assert_eq!(vfs["a.txt"], "one\nTWO");
+        assert_eq!(vfs["src/b.rs"], "fn b() {}");
+        assert_eq!(vfs["d.txt"], "d");
+        assert_eq!(warnings.len(), 3, "{:?}", warnings);
+    }
+
+    #[test]
+    fn test_apply_with_retry_reports_the_mode_used() {
+        use crate::applier::{retry_strategy::RetryStrategy, whitespace_mode::WhitespaceMode};
+        let vfs = vfs_from_str("a.txt", "keep  \nold\n");
+        let patch = "*** Begin Patch\n*** Update File: a.txt\n@@\n keep\n-old\n+new\n*** End Patch";
+
+        assert!(matches!(
+            super::apply_with_retry(patch, &vfs, RetryStrategy::NoRetry),
+            Err(crate::error::ZenpatchError::PatchConflict { .. })
+        ));
+        let (_, mode) = super::apply_with_retry(patch, &vfs, RetryStrategy::LenientFallback).unwrap();
+        assert_eq!(mode, WhitespaceMode::Lenient);
+        let (new_vfs, mode) = super::apply_with_retry(patch, &vfs, RetryStrategy::FullFallback).unwrap();
+        assert_eq!(mode, WhitespaceMode::IgnoreTrailingWhitespace);
+        assert_eq!(new_vfs["a.txt"], "keep  \nnew\n");
+    }
+
+    #[test]
+    fn test_adjacent_chunks_apply_as_one() {
+        let patch = "*** Begin Patch\n*** Update File: a.txt\n@@\n a\n-b\n+B\n c\n@@\n c\n-d\n+D\n*** End Patch";
+        let vfs = vfs_from_str("a.txt", "a\nb\nc\nd\ne\n");
+        assert_eq!(super::apply(patch, &vfs).unwrap().get("a.txt").unwrap(), "a\nB\nc\nD\ne\n");
+
+        // The shared "c" is not one file line here, so the chunks stay apart.
+        let vfs = vfs_from_str("a.txt", "a\nb\nc\nx\nc\nd\n");
+        assert_eq!(super::apply(patch, &vfs).unwrap().get("a.txt").unwrap(), "a\nB\nc\nx\nc\nD\n");
+    }
+
     #[test]
     fn test_apply_add_simple() {
         let patch = "*** Begin Patch\n*** Add File: new.txt\n+hello\n+world\n*** End Patch";
@@ -447,8 +1413,8 @@ mod tests {
         let result = super::apply(patch, &vfs);
         assert!(result.is_err());
         match result.unwrap_err() {
-            crate::error::ZenpatchError::PatchConflict(msg) => {
-                assert!(msg.contains("does not match"));
+            crate::error::ZenpatchError::PatchConflict { message, .. } => {
+                assert!(message.contains("does not match"));
             }
             _ => panic!("Expected PatchConflict error"),
         }
@@ -549,8 +1515,8 @@ mod tests {
         let result = super::apply(patch, &vfs);
         assert!(result.is_err());
         match result.unwrap_err() {
-            crate::error::ZenpatchError::PatchConflict(msg) => {
-                assert!(msg.contains("does not match"));
+            crate::error::ZenpatchError::PatchConflict { message, .. } => {
+                assert!(message.contains("does not match"));
             }
             _ => panic!("Expected PatchConflict error"),
         }
@@ -572,6 +1538,58 @@ mod tests {
         assert_eq!(result_vfs.get("a.txt").unwrap(), "b\nz");
     }
 
+    /// Classic Mac OS files separate lines with a lone `\r`; they must be split
+    /// into lines (not treated as one line) and keep their `\r` endings.
+    #[test]
+    fn test_update_cr_only_line_endings() {
+        let patch = "*** Begin Patch\n*** Update File: a.txt\n@@\n x\n-a\n+b\n*** End Patch";
+        let vfs = vfs_from_str("a.txt", "x\ra\rz\r");
+        let result_vfs = super::apply(patch, &vfs).unwrap();
+        assert_eq!(result_vfs.get("a.txt").unwrap(), "x\rb\rz\r");
+    }
+
+    /// A patch whose lines carry stray `\r`s (e.g. written on Windows and
+    /// re-encoded) matches a CRLF file strictly under `normalize_line_endings`,
+    /// and the result keeps CRLF endings.
+    #[test]
+    fn test_normalize_line_endings_round_trips_crlf() {
+        let patch = "*** Begin Patch\r\n*** Update File: a.txt\r\n@@\r\n x\r\r\n-a\r\r\n+b\r\r\n*** End Patch\r\n";
+        let vfs = vfs_from_str("a.txt", "x\r\na\r\nz\r\n");
+        let options = crate::apply_options::ApplyOptions {
+            normalize_line_endings: true,
+            ..Default::default()
+        };
+        let (out, summary) = super::apply_with_summary_and_options(patch, &vfs, &options).unwrap();
+        assert_eq!(out.get("a.txt").unwrap(), "x\r\nb\r\nz\r\n");
+        assert_eq!(
+            summary.whitespace_mode_used,
+            crate::applier::whitespace_mode::WhitespaceMode::Strict
+        );
+        assert_eq!(
+            crate::detect_line_endings::detect_line_endings(out.get("a.txt").unwrap()),
+            crate::line_ending::LineEnding::Crlf
+        );
+    }
+
+    /// Without `normalize_line_endings` the `\r` left on each hunk line only
+    /// matches once whitespace is relaxed, so a strict-only apply conflicts.
+    #[test]
+    fn test_normalize_line_endings_strips_carriage_returns_from_hunk_lines() {
+        let patch = "*** Begin Patch\r\n*** Update File: a.txt\r\n@@\r\n x\r\r\n-a\r\r\n+b\r\r\n*** End Patch\r\n";
+        let vfs = vfs_from_str("a.txt", "x\na\nz\n");
+        let strict = crate::apply_options::ApplyOptions {
+            whitespace_mode: Some(crate::applier::whitespace_mode::WhitespaceMode::Strict),
+            ..Default::default()
+        };
+        assert!(matches!(
+            super::apply_with_options(patch, &vfs, &strict),
+            Err(crate::error::ZenpatchError::PatchConflict { .. })
+        ));
+
+        let normalized = crate::apply_options::ApplyOptions { normalize_line_endings: true, ..strict };
+        assert_eq!(super::apply_with_options(patch, &vfs, &normalized).unwrap().get("a.txt").unwrap(), "x\nb\nz\n");
+    }
+
     #[test]
     fn test_update_preserves_crlf_and_trailing_newline() {
         let patch = "*** Begin Patch\n*** Update File: a.txt\n@@\n-a\n+b\n*** End Patch";
@@ -645,11 +1663,373 @@ mod tests {
             "*** Begin Patch\n*** Update File: src/a.txt\n@@\n ghost\n-real\n+changed\n*** End Patch";
         let vfs = vfs_from_str("src/a.txt", "real\nother");
         match super::apply(patch, &vfs).unwrap_err() {
-            crate::error::ZenpatchError::PatchConflict(msg) => {
-                assert!(msg.contains("src/a.txt"), "should name the file: {msg}");
-                assert!(msg.contains("ghost"), "should quote the offending line: {msg}");
+            crate::error::ZenpatchError::PatchConflict { path, chunk_index, message } => {
+                assert_eq!(path, "src/a.txt", "should name the file");
+                assert_eq!(chunk_index, 0);
+                assert!(message.contains("ghost"), "should quote the offending line: {message}");
             }
             other => panic!("Expected PatchConflict error, got {other:?}"),
         }
     }
+
+    #[test]
+    fn test_update_conflict_reports_offending_chunk_index() {
+        let patch = "*** Begin Patch\n*** Update File: a.txt\n@@\n-a\n+A\n@@\n-ghost\n+G\n*** End Patch";
+        let vfs = vfs_from_str("a.txt", "a\nb");
+        let err = super::apply(patch, &vfs).unwrap_err();
+        assert!(matches!(
+            err,
+            crate::error::ZenpatchError::PatchConflict { chunk_index: 1, .. }
+        ));
+        assert!(err.to_string().starts_with("Patch conflict in 'a.txt' chunk 1: "), "{err}");
+    }
+
+    #[test]
+    fn test_contextless_insertion_uses_hunk_range_hint() {
+        let patch = "*** Begin Patch\n*** Update File: a.txt\n@@ -2,0 +3 @@\n+inserted\n*** End Patch";
+        let vfs = vfs_from_str("a.txt", "a\nb\nc\n");
+        let new_vfs = super::apply(patch, &vfs).unwrap();
+        assert_eq!(new_vfs.get("a.txt").unwrap(), "a\nb\ninserted\nc\n");
+    }
+
+    /// Git truncates the function name after a range header; it is only a
+    /// display hint, so a hunk whose funcname text matches no line applies.
+    #[test]
+    fn test_truncated_funcname_after_hunk_range_is_not_an_anchor() {
+        let patch = "*** Begin Patch\n*** Update File: a.rs\n@@ -2,2 +2,2 @@ fn my_function(x\n let a = x;\n-a\n+a + 1\n*** End Patch";
+        let vfs = vfs_from_str("a.rs", "fn my_function(x: u32) {\nlet a = x;\na\n}\n");
+        let (new_vfs, summary) = super::apply_with_summary(patch, &vfs).unwrap();
+        assert_eq!(new_vfs.get("a.rs").unwrap(), "fn my_function(x: u32) {\nlet a = x;\na + 1\n}\n");
+        assert_eq!(summary.whitespace_mode_used, crate::applier::whitespace_mode::WhitespaceMode::Strict);
+    }
+
+    #[derive(Default)]
+    struct RecordingObserver {
+        events: Vec<String>,
+    }
+
+    impl crate::observer::patch_observer::PatchObserver for RecordingObserver {
+        fn on_action_start(&mut self, index: usize, action: &crate::data::patch_action::PatchAction) {
+            self.events.push(format!("start {index} {}", action.path));
+        }
+
+        fn on_action_complete(&mut self, index: usize, result: &Result<(), crate::error::ZenpatchError>) {
+            self.events.push(format!("complete {index} ok={}", result.is_ok()));
+        }
+
+        fn on_chunk_applied(&mut self, action_index: usize, chunk_index: usize) {
+            self.events.push(format!("chunk {action_index}.{chunk_index}"));
+        }
+
+        fn on_action_skipped(&mut self, index: usize, action: &crate::data::patch_action::PatchAction) {
+            self.events.push(format!("skipped {index} {}", action.path));
+        }
+
+        fn on_warning(&mut self, message: &str) {
+            self.events.push(format!("warning {message}"));
+        }
+    }
+
+    #[test]
+    fn test_empty_patch_returns_vfs_unchanged_with_warning() {
+        // The context does not even match: an empty patch never searches.
+        let patch = "*** Begin Patch\n*** Update File: a.txt\n@@\n x\n y\n*** End Patch";
+        let vfs = vfs_from_str("a.txt", "a\nb");
+        let mut observer = RecordingObserver::default();
+
+        assert_eq!(super::apply_with_observer(patch, &vfs, &mut observer).unwrap(), vfs);
+        assert_eq!(observer.events.len(), 1);
+        assert!(observer.events[0].starts_with("warning "));
+
+        let (_, summary) = super::apply_with_summary(patch, &vfs).unwrap();
+        assert_eq!(summary.warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_empty_patch_on_missing_file_is_file_not_found() {
+        let patch = "*** Begin Patch\n*** Update File: a.txt\n@@\n a\n*** Update File: missing.txt\n@@\n x\n*** End Patch";
+        let vfs = vfs_from_str("a.txt", "a\nb");
+
+        assert_eq!(
+            super::apply(patch, &vfs),
+            Err(crate::error::ZenpatchError::FileNotFound("missing.txt".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_apply_with_observer_reports_events_in_order() {
+        let patch = "*** Begin Patch\n*** Add File: new.txt\n+n\n*** Update File: a.txt\n@@\n-a\n+A\n@@\n-c\n+C\n*** End Patch";
+        let vfs = vfs_from_str("a.txt", "a\nb\nc");
+        let mut observer = RecordingObserver::default();
+
+        let new_vfs = super::apply_with_observer(patch, &vfs, &mut observer).unwrap();
+
+        assert_eq!(new_vfs.get("a.txt").unwrap(), "A\nb\nC");
+        assert_eq!(
+            observer.events,
+            vec![
+                "start 0 new.txt",
+                "complete 0 ok=true",
+                "start 1 a.txt",
+                "chunk 1.0",
+                "chunk 1.1",
+                "complete 1 ok=true",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_apply_with_observer_reports_failure_and_stops() {
+        let patch = "*** Begin Patch\n*** Delete File: missing.txt\n*** Add File: new.txt\n+n\n*** End Patch";
+        let vfs = vfs_from_str("a.txt", "a");
+        let mut observer = RecordingObserver::default();
+
+        let result = super::apply_with_observer(patch, &vfs, &mut observer);
+
+        assert!(matches!(result, Err(crate::error::ZenpatchError::FileNotFound(_))));
+        assert_eq!(observer.events, vec!["start 0 missing.txt", "complete 0 ok=false"]);
+    }
+
+    #[test]
+    fn test_strip_bom_matches_first_line_and_keeps_bom() {
+        let patch = "*** Begin Patch\n*** Update File: a.txt\n@@\n-first\n+FIRST\n second\n*** End Patch";
+        let vfs = vfs_from_str("a.txt", "\u{FEFF}first\nsecond\n");
+        let options = crate::apply_options::ApplyOptions {
+            strip_bom: true,
+            ..Default::default()
+        };
+
+        let new_vfs = super::apply_with_options(patch, &vfs, &options).unwrap();
+        assert_eq!(new_vfs.get("a.txt").unwrap(), "\u{FEFF}FIRST\nsecond\n");
+        assert!(super::apply(patch, &vfs).is_err(), "BOM is content unless strip_bom is set");
+    }
+
+    #[test]
+    fn test_prefix_strip_p0_p1_p2() {
+        let vfs = vfs_from_str("src/main.rs", "old\n");
+        let with_strip = |n: usize| crate::apply_options::ApplyOptions {
+            prefix_strip: n,
+            ..Default::default()
+        };
+
+        let p0 = "*** Begin Patch\n*** Update File: src/main.rs\n@@\n-old\n+new\n*** End Patch";
+        let p1 = "*** Begin Patch\n*** Update File: a/src/main.rs\n*** Move to: b/src/lib.rs\n@@\n-old\n+new\n*** End Patch";
+        let p2 = "*** Begin Patch\n*** Update File: repo/a/src/main.rs\n@@\n-old\n+new\n*** End Patch";
+
+        assert_eq!(super::apply_with_options(p0, &vfs, &with_strip(0)).unwrap().get("src/main.rs").unwrap(), "new\n");
+        let renamed = super::apply_with_options(p1, &vfs, &with_strip(1)).unwrap();
+        assert_eq!(renamed.get("src/lib.rs").unwrap(), "new\n");
+        assert!(!renamed.contains_key("src/main.rs"));
+        assert_eq!(super::apply_with_options(p2, &vfs, &with_strip(2)).unwrap().get("src/main.rs").unwrap(), "new\n");
+    }
+
+    #[test]
+    fn test_prefix_strip_that_empties_path_is_an_error() {
+        let vfs = vfs_from_str("main.rs", "old\n");
+        let patch = "*** Begin Patch\n*** Update File: a/main.rs\n@@\n-old\n+new\n*** End Patch";
+        let options = crate::apply_options::ApplyOptions {
+            prefix_strip: 2,
+            ..Default::default()
+        };
+        assert!(matches!(
+            super::apply_with_options(patch, &vfs, &options),
+            Err(crate::error::ZenpatchError::InvalidPatchFormat(msg)) if msg.contains("a/main.rs")
+        ));
+    }
+
+    #[test]
+    fn test_apply_ordered_rejects_out_of_order_hunks() {
+        let vfs = vfs_from_str("a.txt", "aaa\nmid\nzzz\n");
+        let patch = "*** Begin Patch\n*** Update File: a.txt\n@@\n-zzz\n+ZZZ\n@@\n-aaa\n+AAA\n*** End Patch";
+        let in_order = "*** Begin Patch\n*** Update File: a.txt\n@@\n-aaa\n+AAA\n@@\n-zzz\n+ZZZ\n*** End Patch";
+
+        assert_eq!(super::apply(patch, &vfs).unwrap().get("a.txt").unwrap(), "AAA\nmid\nZZZ\n");
+        assert!(matches!(super::apply_ordered(patch, &vfs), Err(crate::error::ZenpatchError::PatchConflict { .. })));
+        assert_eq!(super::apply_ordered(in_order, &vfs).unwrap().get("a.txt").unwrap(), "AAA\nmid\nZZZ\n");
+    }
+
+    /// The agent meant to rename `old_name` to `new_name` but wrote the
+    /// markers backwards.
+    #[test]
+    fn test_detect_swapped_markers_reports_backwards_patch() {
+        let vfs = vfs_from_str("a.rs", "fn main() {\n    old_name();\n}\n");
+        let patch = "*** Begin Patch\n*** Update File: a.rs\n@@\n fn main() {\n-    new_name();\n+    old_name();\n }\n*** End Patch";
+        let options = crate::apply_options::ApplyOptions { detect_swapped_markers: true, ..Default::default() };
+
+        assert!(matches!(super::apply(patch, &vfs), Err(crate::error::ZenpatchError::PatchConflict { .. })));
+        assert!(matches!(
+            super::apply_with_options(patch, &vfs, &options),
+            Err(crate::error::ZenpatchError::InvalidPatchFormat(msg)) if msg.contains("swapped (+/- reversed)")
+        ));
+
+        let swapped = crate::parser::parser::Parser::new(patch).parse_with_swapped_markers().unwrap();
+        let (new_vfs, _) = super::apply_actions(swapped, &vfs, &options).unwrap();
+        assert_eq!(new_vfs.get("a.rs").unwrap(), "fn main() {\n    new_name();\n}\n");
+    }
+
+    #[test]
+    fn test_detect_swapped_markers_keeps_genuine_conflicts() {
+        let vfs = vfs_from_str("a.txt", "a\nb\n");
+        let patch = "*** Begin Patch\n*** Update File: a.txt\n@@\n a\n-ghost\n+x\n*** End Patch";
+        let options = crate::apply_options::ApplyOptions { detect_swapped_markers: true, ..Default::default() };
+        assert!(matches!(
+            super::apply_with_options(patch, &vfs, &options),
+            Err(crate::error::ZenpatchError::PatchConflict { path, .. }) if path == "a.txt"
+        ));
+    }
+
+    #[test]
+    fn test_trim_trailing_context_ignores_stale_trailing_lines() {
+        let vfs = vfs_from_str("a.txt", "a\nb\nc\n");
+        let patch = "*** Begin Patch\n*** Update File: a.txt\n@@\n a\n-b\n+B\n stale\n also stale\n*** End Patch";
+        let verified = crate::apply_options::ApplyOptions { verify_post_apply: true, ..Default::default() };
+        let trimmed = crate::apply_options::ApplyOptions { trim_trailing_context: true, ..verified.clone() };
+
+        assert!(super::apply_with_options(patch, &vfs, &verified).is_err());
+        assert_eq!(super::apply_with_options(patch, &vfs, &trimmed).unwrap().get("a.txt").unwrap(), "a\nB\nc\n");
+    }
+
+    #[test]
+    fn test_trim_leading_context_keeps_pure_insertion_anchor() {
+        let vfs = vfs_from_str("a.txt", "a\nb\nc\n");
+        let options = crate::apply_options::ApplyOptions { trim_leading_context: true, ..Default::default() };
+
+        let stale = "*** Begin Patch\n*** Update File: a.txt\n@@\n stale\n-b\n+B\n c\n*** End Patch";
+        assert!(super::apply(stale, &vfs).is_err());
+        assert_eq!(super::apply_with_options(stale, &vfs, &options).unwrap().get("a.txt").unwrap(), "a\nB\nc\n");
+
+        let insertion = "*** Begin Patch\n*** Update File: a.txt\n@@\n b\n+inserted\n*** End Patch";
+        assert_eq!(
+            super::apply_with_options(insertion, &vfs, &options).unwrap().get("a.txt").unwrap(),
+            "a\nb\ninserted\nc\n"
+        );
+    }
+
+    #[test]
+    fn test_chunk_applier_option_replaces_builtin_pipeline() {
+        /// Records each call and replaces the file with a marker line.
+        struct Recording(std::sync::atomic::AtomicUsize);
+        impl crate::applier::chunk_applier::ChunkApplier for Recording {
+            fn apply(
+                &self,
+                _original_lines: &[String],
+                chunks: &[crate::data::chunk::Chunk],
+            ) -> Result<Vec<String>, crate::error::ZenpatchError> {
+                self.0.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                Ok(vec![format!("{} chunks", chunks.len())])
+            }
+
+            fn mode(&self) -> crate::applier::whitespace_mode::WhitespaceMode {
+                crate::applier::whitespace_mode::WhitespaceMode::Strict
+            }
+        }
+        let patch = "*** Begin Patch\n*** Update File: a.txt\n@@\n-a\n+b\n@@\n-c\n+d\n*** End Patch";
+        let vfs = vfs_from_str("a.txt", "a\nc\n");
+        let recording = std::sync::Arc::new(Recording(std::sync::atomic::AtomicUsize::new(0)));
+        let options = crate::apply_options::ApplyOptions { chunk_applier: Some(recording.clone()), ..Default::default() };
+
+        let new_vfs = super::apply_with_options(patch, &vfs, &options).unwrap();
+        assert_eq!(new_vfs.get("a.txt").unwrap(), "2 chunks\n");
+        assert_eq!(recording.0.load(std::sync::atomic::Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_greedy_chunk_applier_does_not_retry_leniently() {
+        let patch = "*** Begin Patch\n*** Update File: a.txt\n@@\n a\n-b\n+c\n*** End Patch";
+        let vfs = vfs_from_str("a.txt", "  a\nb\n");
+        let options = crate::apply_options::ApplyOptions {
+            chunk_applier: Some(std::sync::Arc::new(crate::applier::greedy_applier::GreedyApplier::default())),
+            ..Default::default()
+        };
+
+        assert!(super::apply(patch, &vfs).is_ok(), "the built-in pipeline falls back to lenient matching");
+        assert!(super::apply_with_options(patch, &vfs, &options).is_err());
+    }
+
+    #[test]
+    fn test_summary_reports_chunk_applier_mode() {
+        let patch = "*** Begin Patch\n*** Update File: a.txt\n@@\n a\n-b\n+c\n*** End Patch";
+        let vfs = vfs_from_str("a.txt", "  a\nb\n");
+        let options = crate::apply_options::ApplyOptions {
+            chunk_applier: Some(std::sync::Arc::new(crate::applier::greedy_applier::GreedyApplier {
+                mode: crate::applier::whitespace_mode::WhitespaceMode::Lenient,
+            })),
+            ..Default::default()
+        };
+
+        let (out, summary) = super::apply_with_summary_and_options(patch, &vfs, &options).unwrap();
+        assert_eq!(out.get("a.txt").unwrap(), "  a\nc\n");
+        assert_eq!(summary.whitespace_mode_used, crate::applier::whitespace_mode::WhitespaceMode::Lenient);
+    }
+
+    #[test]
+    fn test_apply_with_path_prefix_map_remaps_path_and_new_path() {
+        let vfs = vfs_from_str("packages/ui/src/Button.tsx", "old\n");
+        let patch = "*** Begin Patch\n*** Update File: src/Button.tsx\n*** Move to: src/Btn.tsx\n@@\n-old\n+new\n*** End Patch";
+
+        let new_vfs = super::apply_with_path_prefix_map(patch, &vfs, |p| format!("packages/ui/{}", p)).unwrap();
+        assert_eq!(new_vfs.get("packages/ui/src/Btn.tsx").unwrap(), "new\n");
+        assert!(!new_vfs.contains_key("packages/ui/src/Button.tsx"));
+        assert!(!new_vfs.contains_key("src/Btn.tsx"));
+    }
+
+    #[test]
+    fn test_path_mapper_option_runs_after_prefix_strip() {
+        let vfs = vfs_from_str("packages/ui/src/a.ts", "a\n");
+        let patch = "*** Begin Patch\n*** Update File: a/src/a.ts\n@@\n-a\n+b\n*** Add File: b/src/new.ts\n+n\n*** End Patch";
+        let options = crate::apply_options::ApplyOptions {
+            prefix_strip: 1,
+            path_mapper: Some(std::sync::Arc::new(|p: &str| format!("packages/ui/{}", p))),
+            ..Default::default()
+        };
+
+        let new_vfs = super::apply_with_options(patch, &vfs, &options).unwrap();
+        assert_eq!(new_vfs.get("packages/ui/src/a.ts").unwrap(), "b\n");
+        assert_eq!(new_vfs.get("packages/ui/src/new.ts").unwrap(), "n");
+        assert!(format!("{:?}", options).contains("path_mapper: Some(\"<fn>\")"));
+    }
+
+    #[test]
+    fn test_apply_filtered_skips_rejected_actions() {
+        let patch = "*** Begin Patch\n*** Update File: src/a.rs\n@@\n-a\n+A\n*** Update File: Cargo.toml\n@@\n-ghost\n+x\n*** Add File: src/b.rs\n+b\n*** End Patch";
+        let vfs = vfs_from_str("src/a.rs", "a");
+        let is_rust = |a: &crate::data::patch_action::PatchAction| a.path.ends_with(".rs");
+
+        // The Cargo.toml action would fail (no such file) but is never attempted.
+        let new_vfs = super::apply_filtered(patch, &vfs, is_rust).unwrap();
+        assert_eq!(new_vfs.get("src/a.rs").unwrap(), "A");
+        assert_eq!(new_vfs.get("src/b.rs").unwrap(), "b");
+        assert!(!new_vfs.contains_key("Cargo.toml"));
+
+        let mut observer = RecordingObserver::default();
+        super::apply_filtered_with_observer(patch, &vfs, is_rust, &mut observer).unwrap();
+        assert!(observer.events.contains(&"skipped 1 Cargo.toml".to_string()), "{:?}", observer.events);
+    }
+
+    #[test]
+    fn test_apply_subset_follows_index_order() {
+        let patch = "*** Begin Patch\n*** Update File: a.txt\n@@\n-a\n+b\n*** Add File: x.txt\n+x\n*** Update File: a.txt\n@@\n-b\n+c\n*** End Patch";
+        let vfs = vfs_from_str("a.txt", "a");
+        let new_vfs = super::apply_subset(patch, &vfs, &[0, 2]).unwrap();
+        assert_eq!(new_vfs.get("a.txt").unwrap(), "c");
+        assert!(!new_vfs.contains_key("x.txt"));
+        // Action 2 expects the output of action 0, so the reverse order conflicts.
+        assert!(super::apply_subset(patch, &vfs, &[2, 0]).is_err());
+        assert!(matches!(
+            super::apply_subset(patch, &vfs, &[1, 3]),
+            Err(crate::error::ZenpatchError::IndexOutOfBounds(_))
+        ));
+    }
+
+    #[test]
+    fn test_apply_filtered_still_fails_on_kept_action_and_parse_error() {
+        let patch = "*** Begin Patch\n*** Update File: a.txt\n@@\n-ghost\n+x\n*** End Patch";
+        let vfs = vfs_from_str("a.txt", "a");
+        assert!(super::apply_filtered(patch, &vfs, |_| true).is_err());
+        assert!(super::apply_filtered(patch, &vfs, |_| false).is_ok());
+        assert!(matches!(
+            super::apply_filtered("garbage", &vfs, |_| false),
+            Err(crate::error::ZenpatchError::InvalidPatchFormat(_))
+        ));
+    }
 }
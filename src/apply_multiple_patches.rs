@@ -0,0 +1,104 @@
+//! Applies a sequence of independently formatted patches in order.
+//!
+//! Each patch carries its own `*** Begin Patch` / `*** End Patch` envelope and
+//! is applied to the VFS produced by the patches before it. Errors report the
+//! index of the failing patch within the sequence.
+
+/// Applies `patches` one after another, threading the resulting VFS forward.
+///
+/// Stops at the first failure and returns `(patch index, error)`; the input
+/// VFS is left untouched in that case.
+pub fn apply_multiple_patches(
+    patches: &[&str],
+    vfs: &crate::vfs::Vfs,
+) -> std::result::Result<crate::vfs::Vfs, (usize, crate::error::ZenpatchError)> {
+    let mut current = vfs.clone();
+    for (index, patch_text) in patches.iter().enumerate() {
+        current = crate::apply::apply(patch_text, &current).map_err(|e| (index, e))?;
+    }
+    std::result::Result::Ok(current)
+}
+
+/// Like [`apply_multiple_patches`], but returns the failing patch's error
+/// without its index.
+pub fn apply_patch_sequence(
+    patches: &[&str],
+    vfs: &crate::vfs::Vfs,
+) -> std::result::Result<crate::vfs::Vfs, crate::error::ZenpatchError> {
+    apply_multiple_patches(patches, vfs).map_err(|(_, e)| e)
+}
+
+/// Partial variant of [`apply_multiple_patches`]: a failing patch is skipped
+/// (it changes nothing) and the remaining patches are still applied.
+///
+/// Returns the final VFS and one `(patch index, error)` per skipped patch.
+pub fn apply_multiple_patches_partial(
+    patches: &[&str],
+    vfs: &crate::vfs::Vfs,
+) -> (crate::vfs::Vfs, std::vec::Vec<(usize, crate::error::ZenpatchError)>) {
+    let mut current = vfs.clone();
+    let mut failures = std::vec::Vec::new();
+    for (index, patch_text) in patches.iter().enumerate() {
+        match crate::apply::apply(patch_text, &current) {
+            std::result::Result::Ok(updated) => current = updated,
+            std::result::Result::Err(e) => failures.push((index, e)),
+        }
+    }
+    (current, failures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{apply_multiple_patches, apply_multiple_patches_partial, apply_patch_sequence};
+    use crate::vfs::Vfs;
+
+    fn vfs_from_str(path: &str, content: &str) -> Vfs {
+        let mut vfs = Vfs::new();
+        vfs.insert(path.to_string(), content.to_string());
+        vfs
+    }
+
+    #[test]
+    fn test_patches_are_chained_in_order() {
+        let first = "*** Begin Patch\n*** Update File: a.txt\n@@\n-a\n+b\n*** End Patch";
+        // Only applies on top of the first patch's result.
+        let second = "*** Begin Patch\n*** Update File: a.txt\n@@\n-b\n+c\n*** End Patch";
+        let vfs = vfs_from_str("a.txt", "a");
+        let out = apply_multiple_patches(&[first, second], &vfs).unwrap();
+        assert_eq!(out.get("a.txt").unwrap(), "c");
+    }
+
+    #[test]
+    fn test_failure_reports_patch_index() {
+        let ok = "*** Begin Patch\n*** Update File: a.txt\n@@\n-a\n+b\n*** End Patch";
+        let bad = "*** Begin Patch\n*** Update File: a.txt\n@@\n-ghost\n+x\n*** End Patch";
+        let vfs = vfs_from_str("a.txt", "a");
+        let (index, err) = apply_multiple_patches(&[ok, bad, ok], &vfs).unwrap_err();
+        assert_eq!(index, 1);
+        assert!(matches!(err, crate::error::ZenpatchError::PatchConflict { .. }));
+    }
+
+    #[test]
+    fn test_partial_skips_failing_patch_and_continues() {
+        let bad = "*** Begin Patch\n*** Update File: a.txt\n@@\n-ghost\n+x\n*** End Patch";
+        let add = "*** Begin Patch\n*** Add File: b.txt\n+b\n*** End Patch";
+        let vfs = vfs_from_str("a.txt", "a");
+        let (out, failures) = apply_multiple_patches_partial(&[bad, add], &vfs);
+        assert_eq!(out.get("a.txt").unwrap(), "a");
+        assert_eq!(out.get("b.txt").unwrap(), "b");
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].0, 0);
+    }
+
+    #[test]
+    fn test_apply_patch_sequence_chains_and_propagates_errors() {
+        let first = "*** Begin Patch\n*** Update File: a.txt\n@@\n-a\n+b\n*** End Patch";
+        let second = "*** Begin Patch\n*** Update File: a.txt\n@@\n-b\n+c\n*** End Patch";
+        let vfs = vfs_from_str("a.txt", "a");
+        assert_eq!(apply_patch_sequence(&[first, second], &vfs).unwrap().get("a.txt").unwrap(), "c");
+        assert!(matches!(
+            apply_patch_sequence(&[second], &vfs),
+            Err(crate::error::ZenpatchError::PatchConflict { .. })
+        ));
+    }
+}
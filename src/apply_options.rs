@@ -0,0 +1,172 @@
+//! Defines the `ApplyOptions` struct for tuning patch application.
+//!
+//! Every option defaults to the behaviour of the plain [`crate::apply::apply`]
+//! entry point, so `ApplyOptions::default()` is always a safe starting point.
+//! Conforms to rust coding guidelines (one item per file).
+
+/// Rewrites a patch path into the VFS key it refers to; see [`ApplyOptions::path_mapper`].
+pub type PathMapper = std::sync::Arc<dyn Fn(&str) -> std::string::String + Send + Sync>;
+
+/// Optional behaviours for [`crate::apply::apply_with_options`].
+#[derive(Clone, Default)]
+pub struct ApplyOptions {
+    /// Suppresses an inserted line when it is byte-identical to the file line
+    /// currently at the insertion position. This makes re-applying an
+    /// insertion-heavy patch (a common double-application artifact) idempotent.
+    ///
+    /// Tradeoff: a patch that INTENTIONALLY inserts a copy of the line that
+    /// follows the insertion point (e.g. duplicating a `}` or a blank line) has
+    /// that insertion silently dropped. Leave this off unless double application
+    /// is a realistic risk for the caller.
+    pub skip_redundant_insertions: bool,
+    /// For a pure-deletion hunk with no context lines whose deleted lines occur
+    /// more than once in the file, delete the FIRST occurrence instead of
+    /// rejecting the patch as ambiguous.
+    ///
+    /// Risk: nothing in such a hunk says which occurrence was meant, so if the
+    /// author meant a later one the wrong lines are silently removed. Prefer
+    /// adding context to the hunk; use this only when "first" is known to be right.
+    pub prefer_first_occurrence: bool,
+    /// What to do when the search cannot prove a single result. Under
+    /// `AcceptFirst`, accepted ambiguities are reported in `PatchSummary::warnings`.
+    pub ambiguity_policy: crate::applier::ambiguity_policy::AmbiguityPolicy,
+    /// Overrides the search's node cap (`None` keeps the built-in default).
+    pub max_backtrack_nodes: std::option::Option<usize>,
+    /// Strips every `\r` from the patch's hunk lines before matching, so hunk
+    /// lines that still carry a carriage return (e.g. CRLF text converted to
+    /// CRLF again, leaving `\r\r\n`) match strictly. The file side needs no
+    /// option: its lines are split on `\n`, `\r\n` and a lone `\r` (see
+    /// [`crate::split_lines::split_lines`]), and an updated file keeps its
+    /// own line endings either way.
+    pub normalize_line_endings: bool,
+    /// Ignores a UTF-8 byte order mark at the start of a file while matching
+    /// (and when comparing a `Delete` against the file). The BOM is kept in
+    /// the updated file.
+    pub strip_bom: bool,
+    /// Strips this many leading `/`-separated components from every action's
+    /// `path` and `new_path` before applying, like `patch -p<n>`: with `1`,
+    /// `a/src/main.rs` becomes `src/main.rs`. A path left empty is an error.
+    pub prefix_strip: usize,
+    /// Escalates with [`crate::applier::retry_strategy::RetryStrategy::FullFallback`]
+    /// whatever `retry_strategy` says, ending with `WhitespaceMode::FUZZY`,
+    /// which accepts context and deleted lines up to two character edits away
+    /// from the file.
+    ///
+    /// Risk: a near-miss line may be a genuinely different line (`x += 1` vs
+    /// `x -= 1`), so a patch can land where it was not meant to. The mode used
+    /// is reported in `PatchSummary::whitespace_mode_used`.
+    pub fuzzy_matching: bool,
+    /// The whitespace modes tried, in order, when a file's chunks do not
+    /// match strictly. Defaults to strict, then lenient matching.
+    pub retry_strategy: crate::applier::retry_strategy::RetryStrategy,
+    /// Matches in exactly this mode instead of trying strict, then lenient
+    /// (then, with `fuzzy_matching`, looser) matching. `None` keeps the
+    /// automatic fallback. The only way to use
+    /// `WhitespaceMode::IgnoreAllWhitespace`, which is never tried on its own.
+    pub whitespace_mode: std::option::Option<crate::applier::whitespace_mode::WhitespaceMode>,
+    /// After each `Update`, checks that every chunk's trailing context lines
+    /// directly follow its changes in the result (compared in the whitespace
+    /// mode that succeeded), failing with `PatchApplicationFailed` if not.
+    /// Matching only anchors a chunk by its leading context and deletions, so
+    /// this catches a chunk placed where its trailing context does not fit.
+    pub verify_post_apply: bool,
+    /// Skips an `Update` or `Delete` whose file is not in the VFS instead of
+    /// failing with `FileNotFound`, for patches generated against a full tree
+    /// but applied to a VFS holding only part of it. An `Add` of an existing
+    /// file still fails.
+    pub ignore_missing_files: bool,
+    /// Turns an `Update` whose file is not in the VFS into an `Add` of the
+    /// chunks' inserted lines (deletions and context are dropped), written to
+    /// the `Move to:` path if there is one. Each such fallback is reported in
+    /// `PatchSummary::warnings`. Takes precedence over `ignore_missing_files`
+    /// for `Update`s; meant for replaying a patch log onto an empty VFS.
+    pub create_missing_as_add: bool,
+    /// Called on every action's `path` and `new_path` (after `prefix_strip`)
+    /// before the VFS is consulted, e.g. to re-root a patch generated inside
+    /// `packages/ui/` onto a monorepo VFS. Shared through an `Arc` so the
+    /// options stay `Clone`.
+    pub path_mapper: std::option::Option<PathMapper>,
+    /// Drops the `Context` lines that follow each chunk's last change before
+    /// matching, for patches whose changes are right but whose trailing
+    /// context is stale or runs past the end of the file. Chunks anchored
+    /// with `*** End of File` keep theirs, as do chunks made only of context.
+    /// Matching mostly anchors on leading context, so this matters where
+    /// trailing context is checked: disambiguation and `verify_post_apply`.
+    pub trim_trailing_context: bool,
+    /// Drops the `Context` lines before each chunk's first change before
+    /// matching, so the deleted lines alone locate the chunk. Pure insertions
+    /// keep theirs, since it is their only anchor, as do chunks made only of
+    /// context. Fewer context lines make a chunk more likely to be ambiguous.
+    pub trim_leading_context: bool,
+    /// When an `Update` fails with a `PatchConflict` but applies strictly with
+    /// its `+` and `-` lines swapped, fails with `InvalidPatchFormat` saying
+    /// the markers appear swapped instead, so an agent can regenerate (or
+    /// re-parse with `Parser::parse_with_swapped_markers`). An already-applied
+    /// patch looks the same, which the message also mentions. Off by default
+    /// because conflict policies only resolve `PatchConflict`s.
+    pub detect_swapped_markers: bool,
+    /// Rejects (with a `PatchConflict`) an `Update` whose hunks only apply out
+    /// of document order, instead of falling back to placing them in any
+    /// order; see [`crate::apply::apply_ordered`].
+    pub require_ordered_application: bool,
+    /// Places every `Update`'s chunks with this applier instead of the
+    /// built-in pipeline (greedy placement backed by the backtracking search,
+    /// escalating through `retry_strategy`'s whitespace modes). A custom
+    /// applier gets one attempt, and `PatchSummary::whitespace_mode_used`
+    /// reports its [`ChunkApplier::mode`](crate::applier::chunk_applier::ChunkApplier::mode).
+    ///
+    /// `None` (the default) keeps the built-in pipeline rather than defaulting
+    /// to a `BacktrackingApplier`: a single applier matches in one mode, so it
+    /// can't reproduce the strict → lenient escalation. It is an `Arc` rather
+    /// than a `Box` so `ApplyOptions` stays `Clone`.
+    pub chunk_applier: std::option::Option<std::sync::Arc<dyn crate::applier::chunk_applier::ChunkApplier>>,
+}
+
+impl std::fmt::Debug for ApplyOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ApplyOptions")
+            .field("skip_redundant_insertions", &self.skip_redundant_insertions)
+            .field("prefer_first_occurrence", &self.prefer_first_occurrence)
+            .field("ambiguity_policy", &self.ambiguity_policy)
+            .field("max_backtrack_nodes", &self.max_backtrack_nodes)
+            .field("normalize_line_endings", &self.normalize_line_endings)
+            .field("strip_bom", &self.strip_bom)
+            .field("prefix_strip", &self.prefix_strip)
+            .field("fuzzy_matching", &self.fuzzy_matching)
+            .field("retry_strategy", &self.retry_strategy)
+            .field("whitespace_mode", &self.whitespace_mode)
+            .field("verify_post_apply", &self.verify_post_apply)
+            .field("ignore_missing_files", &self.ignore_missing_files)
+            .field("create_missing_as_add", &self.create_missing_as_add)
+            .field("path_mapper", &self.path_mapper.as_ref().map(|_| "<fn>"))
+            .field("trim_trailing_context", &self.trim_trailing_context)
+            .field("trim_leading_context", &self.trim_leading_context)
+            .field("detect_swapped_markers", &self.detect_swapped_markers)
+            .field("require_ordered_application", &self.require_ordered_application)
+            .field("chunk_applier", &self.chunk_applier.as_ref().map(|_| "<dyn ChunkApplier>"))
+            .finish()
+    }
+}
+
+impl ApplyOptions {
+    /// The applier-level options these apply options translate to.
+    pub(crate) fn backtracking_options(&self) -> crate::applier::backtracking_options::BacktrackingOptions {
+        crate::applier::backtracking_options::BacktrackingOptions {
+            skip_redundant_insertions: self.skip_redundant_insertions,
+            prefer_first_occurrence: self.prefer_first_occurrence,
+            ambiguity_policy: self.ambiguity_policy,
+            max_nodes: self
+                .max_backtrack_nodes
+                .unwrap_or(crate::applier::backtracking_patcher::MAX_BACKTRACK_NODES),
+            node_counter: std::option::Option::None,
+            retry_strategy: if self.fuzzy_matching {
+                crate::applier::retry_strategy::RetryStrategy::FullFallback
+            } else {
+                self.retry_strategy
+            },
+            whitespace_mode: self.whitespace_mode,
+            require_ordered_application: self.require_ordered_application,
+            chunk_applier: self.chunk_applier.clone(),
+        }
+    }
+}
@@ -0,0 +1,78 @@
+//! Async entry points for tokio applications (behind the `async` feature).
+//!
+//! Patching is CPU-bound and the directory variant does blocking filesystem
+//! work, so both run the synchronous implementations on tokio's blocking
+//! thread pool instead of the async executor.
+
+/// Async variant of [`crate::apply::apply`], run with `tokio::task::spawn_blocking`.
+///
+/// Takes owned arguments because the work moves to another thread.
+pub async fn apply_async(
+    patch_text: std::string::String,
+    vfs: crate::vfs::Vfs,
+) -> std::result::Result<crate::vfs::Vfs, crate::error::ZenpatchError> {
+    run_blocking(move || crate::apply::apply(&patch_text, &vfs)).await
+}
+
+/// Async variant of [`crate::fs_apply::apply_from_file`]: reads the patch at
+/// `patch_path` with `tokio::fs`, then applies it to the files under `root`
+/// with [`crate::fs_apply::apply_to_directory`] on the blocking pool.
+pub async fn apply_to_directory_async(
+    patch_path: std::path::PathBuf,
+    root: std::path::PathBuf,
+) -> std::result::Result<(), crate::error::ZenpatchError> {
+    let patch_text = tokio::fs::read_to_string(&patch_path)
+        .await
+        .map_err(|e| crate::error::ZenpatchError::IoError {
+            message: format!("{}: {}", patch_path.display(), e),
+            source: std::option::Option::Some(std::boxed::Box::new(e)),
+        })?;
+    run_blocking(move || crate::fs_apply::apply_to_directory(&patch_text, &root)).await
+}
+
+/// Runs `f` on the blocking pool, re-raising a panic from it on the caller.
+async fn run_blocking<T: Send + 'static>(f: impl FnOnce() -> T + Send + 'static) -> T {
+    tokio::task::spawn_blocking(f)
+        .await
+        .unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic()))
+}
+
+#[cfg(test)]
+mod tests {
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[test]
+    fn test_apply_async_matches_apply() {
+        let mut vfs = crate::vfs::Vfs::new();
+        vfs.insert("a.txt".to_string(), "one\n".to_string());
+        let patch = "*** Begin Patch\n*** Update File: a.txt\n@@\n-one\n+two\n*** End Patch";
+
+        let new_vfs = block_on(super::apply_async(patch.to_string(), vfs.clone())).unwrap();
+
+        assert_eq!(new_vfs, crate::apply::apply(patch, &vfs).unwrap());
+    }
+
+    #[test]
+    fn test_apply_to_directory_async() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "one\n").unwrap();
+        let patch_path = dir.path().join("change.patch");
+        std::fs::write(&patch_path, "*** Begin Patch\n*** Update File: a.txt\n@@\n-one\n+two\n*** End Patch").unwrap();
+
+        block_on(super::apply_to_directory_async(patch_path, dir.path().to_path_buf())).unwrap();
+
+        assert_eq!(std::fs::read_to_string(dir.path().join("a.txt")).unwrap(), "two\n");
+    }
+
+    #[test]
+    fn test_apply_to_directory_async_missing_patch_is_io_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = block_on(super::apply_to_directory_async(dir.path().join("missing.patch"), dir.path().to_path_buf()));
+        assert!(matches!(result, Err(crate::error::ZenpatchError::IoError { .. })));
+    }
+}
@@ -0,0 +1,440 @@
+//! Conflict handling policies for applying a patch whose hunks do not all fit.
+//!
+//! `apply_with_conflict_policy` applies a patch action by action and, when an
+//! action hits a `PatchConflict`, resolves it according to a
+//! `ConflictResolution` instead of always failing, recording each resolution
+//! as a `ConflictReport`. `apply_best_effort` does the same for automated
+//! pipelines, also surviving actions that fail to parse or apply for other
+//! reasons.
+
+/// What [`apply_with_conflict_policy`] does with an action that conflicts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConflictResolution {
+    /// Fail the whole patch with the conflict error, like [`crate::apply::apply`].
+    #[default]
+    Abort,
+    /// Drop the conflicting action and keep applying the rest.
+    Skip,
+    /// Leave the conflicting file as it was just before the failing action
+    /// (earlier actions on it stay applied) and ignore every later action on it.
+    KeepOriginal,
+    /// Apply the hunks that fit and write git-style conflict markers in
+    /// place of each hunk that does not.
+    UseConflictMarkers,
+}
+
+/// One conflict resolved by [`apply_with_conflict_policy`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictReport {
+    /// File the conflicting action targets, as written in the patch.
+    pub path: std::string::String,
+    /// Zero-based index of the conflicting chunk within the action.
+    pub chunk_index: usize,
+    /// The policy that was applied.
+    pub policy: ConflictResolution,
+}
+
+/// Applies `patch_text` to `vfs`, resolving `PatchConflict`s with `policy`.
+///
+/// Each action is applied atomically on top of the ones before it. Errors
+/// other than `PatchConflict`, and every error under
+/// [`ConflictResolution::Abort`], are returned unchanged.
+///
+/// With [`ConflictResolution::UseConflictMarkers`] each failing hunk is
+/// replaced, at the place where it comes closest to matching, by:
+///
+/// ```text
+/// <<<<<<< file
+/// (the lines actually present)
+/// ||||||| patch expected
+/// (the context and deleted lines the patch expected)
+/// =======
+/// (the context and inserted lines the patch wanted)
+/// >>>>>>> patch
+/// ```
+pub fn apply_with_conflict_policy(
+    patch_text: &str,
+    vfs: &crate::vfs::Vfs,
+    policy: ConflictResolution,
+) -> std::result::Result<(crate::vfs::Vfs, std::vec::Vec<ConflictReport>), crate::error::ZenpatchError> {
+    let actions = crate::parser::text_to_patch::text_to_patch(patch_text)?;
+    let options = crate::apply_options::ApplyOptions::default();
+    let mut new_vfs = vfs.clone();
+    let mut reports = std::vec::Vec::new();
+    let mut kept: std::collections::HashSet<std::string::String> = std::collections::HashSet::new();
+
+    for action in actions {
+        let touches_kept = action.affected_paths().iter().any(|path| kept.contains(*path));
+        if touches_kept {
+            continue;
+        }
+        let chunk_index = match crate::apply::apply_actions(std::vec![action.clone()], &new_vfs, &options) {
+            std::result::Result::Ok((updated, _)) => {
+                new_vfs = updated;
+                continue;
+            }
+            std::result::Result::Err(crate::error::ZenpatchError::PatchConflict { chunk_index, .. })
+                if policy != ConflictResolution::Abort =>
+            {
+                chunk_index
+            }
+            std::result::Result::Err(e) => return std::result::Result::Err(e),
+        };
+        match policy {
+            ConflictResolution::Abort | ConflictResolution::Skip => {
+                reports.push(ConflictReport { path: action.path.clone(), chunk_index, policy });
+            }
+            // The failed action left `new_vfs` as it was just before it.
+            ConflictResolution::KeepOriginal => {
+                reports.push(ConflictReport { path: action.path.clone(), chunk_index, policy });
+                kept.insert(action.path.clone());
+            }
+            ConflictResolution::UseConflictMarkers => {
+                apply_with_markers(&mut new_vfs, &action, &mut reports);
+            }
+        }
+    }
+
+    std::result::Result::Ok((new_vfs, reports))
+}
+
+/// Outcome of [`apply_best_effort`].
+#[derive(Debug)]
+pub struct BestEffortResult {
+    /// The VFS with every successful action (and any conflict resolution) applied.
+    pub vfs: crate::vfs::Vfs,
+    /// Indices of the actions that applied cleanly.
+    pub succeeded: std::vec::Vec<usize>,
+    /// Indices of the actions that failed to parse or apply, with the error.
+    pub failed: std::vec::Vec<(usize, crate::error::ZenpatchError)>,
+}
+
+/// Attempts every action of `patch_text` against `vfs`, keeping those that
+/// apply and resolving each failure with `policy`.
+///
+/// Unlike [`crate::apply::apply_partial`], a patch that does not parse as a
+/// whole is split at its file directives and each section is parsed on its
+/// own; a section that still does not parse is a failed action. Action
+/// indices count these sections. The outer `Err` is returned only when no
+/// section parses.
+///
+/// A failing action leaves the VFS as it was, except that under
+/// [`ConflictResolution::UseConflictMarkers`] a `PatchConflict` writes
+/// conflict markers (see [`apply_with_conflict_policy`]) and under
+/// [`ConflictResolution::KeepOriginal`] the file keeps its state from just
+/// before the failing action (earlier actions on it stay applied and in
+/// `succeeded`) and later actions on it fail. [`ConflictResolution::Abort`] stops at the first failure and
+/// returns what was applied before it.
+pub fn apply_best_effort(
+    patch_text: &str,
+    vfs: &crate::vfs::Vfs,
+    policy: ConflictResolution,
+) -> std::result::Result<BestEffortResult, crate::error::ZenpatchError> {
+    let sections = parse_sections(patch_text)?;
+    let options = crate::apply_options::ApplyOptions::default();
+    let mut result =
+        BestEffortResult { vfs: vfs.clone(), succeeded: std::vec::Vec::new(), failed: std::vec::Vec::new() };
+    let mut kept: std::collections::HashSet<std::string::String> = std::collections::HashSet::new();
+
+    for (index, section) in sections.into_iter().enumerate() {
+        let action = match section {
+            std::result::Result::Ok(action) => action,
+            std::result::Result::Err(e) => {
+                result.failed.push((index, e));
+                if policy == ConflictResolution::Abort {
+                    break;
+                }
+                continue;
+            }
+        };
+        if let std::option::Option::Some(path) = action.affected_paths().into_iter().find(|p| kept.contains(*p)) {
+            result.failed.push((
+                index,
+                crate::error::ZenpatchError::PatchApplicationFailed(std::format!(
+                    "'{}' was restored after an earlier conflict",
+                    path
+                )),
+            ));
+            continue;
+        }
+        let error = match crate::apply::apply_actions(std::vec![action.clone()], &result.vfs, &options) {
+            std::result::Result::Ok((updated, _)) => {
+                result.vfs = updated;
+                result.succeeded.push(index);
+                continue;
+            }
+            std::result::Result::Err(e) => e,
+        };
+        let is_conflict = std::matches!(error, crate::error::ZenpatchError::PatchConflict { .. });
+        result.failed.push((index, error));
+        match policy {
+            ConflictResolution::Abort => break,
+            ConflictResolution::Skip => {}
+            // The failed action left `result.vfs` as it was just before it,
+            // which is the state to keep: earlier successes stay applied.
+            ConflictResolution::KeepOriginal => {
+                kept.insert(action.path.clone());
+            }
+            ConflictResolution::UseConflictMarkers => {
+                if is_conflict {
+                    apply_with_markers(&mut result.vfs, &action, &mut std::vec::Vec::new());
+                }
+            }
+        }
+    }
+
+    std::result::Result::Ok(result)
+}
+
+/// The actions of `patch_text`, or, when it does not parse as a whole, the
+/// result of parsing each file-directive section on its own. Fails with the
+/// whole-patch error when no section parses.
+fn parse_sections(
+    patch_text: &str,
+) -> std::result::Result<
+    std::vec::Vec<std::result::Result<crate::data::patch_action::PatchAction, crate::error::ZenpatchError>>,
+    crate::error::ZenpatchError,
+> {
+    let error = match crate::parser::text_to_patch::text_to_patch(patch_text) {
+        std::result::Result::Ok(actions) => {
+            return std::result::Result::Ok(actions.into_iter().map(std::result::Result::Ok).collect())
+        }
+        std::result::Result::Err(e) => e,
+    };
+    const DIRECTIVES: [&str; 5] =
+        ["*** Add File:", "*** Update File:", "*** Delete File:", "*** Copy File:", "*** Rename File:"];
+    let mut sections: std::vec::Vec<std::vec::Vec<&str>> = std::vec::Vec::new();
+    for line in patch_text.lines() {
+        if line.trim() == "*** End Patch" {
+            break;
+        }
+        if DIRECTIVES.iter().any(|d| line.starts_with(d)) {
+            sections.push(std::vec![line]);
+        } else if let std::option::Option::Some(section) = sections.last_mut() {
+            section.push(line);
+        }
+    }
+    let parsed: std::vec::Vec<_> = sections
+        .iter()
+        .map(|section| {
+            let text = std::format!("*** Begin Patch\n{}\n*** End Patch", section.join("\n"));
+            crate::parser::text_to_patch::text_to_patch(&text).and_then(|mut actions| {
+                match actions.len() {
+                    1 => std::result::Result::Ok(actions.remove(0)),
+                    _ => std::result::Result::Err(crate::error::ZenpatchError::InvalidPatchFormat(std::format!(
+                        "section '{}' does not hold exactly one action",
+                        section[0]
+                    ))),
+                }
+            })
+        })
+        .collect();
+    if parsed.iter().all(std::result::Result::is_err) {
+        return std::result::Result::Err(error);
+    }
+    std::result::Result::Ok(parsed)
+}
+
+/// Applies each chunk of the Update `action` on its own, writing conflict
+/// markers for the ones that do not fit.
+fn apply_with_markers(
+    vfs: &mut crate::vfs::Vfs,
+    action: &crate::data::patch_action::PatchAction,
+    reports: &mut std::vec::Vec<ConflictReport>,
+) {
+    let std::option::Option::Some(key) = crate::apply::resolve_vfs_path(vfs, &action.path) else {
+        return;
+    };
+    let content = vfs.get(&key).cloned().unwrap_or_default();
+    let mut lines = crate::split_lines::split_lines(&content);
+    for (chunk_index, chunk) in action.chunks.iter().enumerate() {
+        match crate::apply::apply_one_chunk(&lines, chunk) {
+            std::result::Result::Ok(updated) => lines = updated,
+            std::result::Result::Err(_) => {
+                reports.push(ConflictReport {
+                    path: action.path.clone(),
+                    chunk_index,
+                    policy: ConflictResolution::UseConflictMarkers,
+                });
+                lines = with_conflict_markers(&lines, chunk);
+            }
+        }
+    }
+    let updated = crate::apply::rejoin(&content, &lines);
+    match &action.new_path {
+        std::option::Option::Some(new_path) => {
+            vfs.remove(&key);
+            vfs.insert(new_path.clone(), updated);
+        }
+        std::option::Option::None => {
+            vfs.insert(key, updated);
+        }
+    }
+}
+
+/// `lines` with the region closest to `chunk` wrapped in conflict markers.
+fn with_conflict_markers(
+    lines: &[std::string::String],
+    chunk: &crate::data::chunk::Chunk,
+) -> std::vec::Vec<std::string::String> {
+    let side = |keep: crate::data::line_type::LineType| {
+        chunk
+            .lines
+            .iter()
+            .filter(move |(lt, _)| *lt == crate::data::line_type::LineType::Context || *lt == keep)
+            .map(|(_, content)| content.clone())
+    };
+    let expected: std::vec::Vec<std::string::String> = side(crate::data::line_type::LineType::Deletion).collect();
+    let start = crate::diagnostics::closest_match(lines, chunk).min(lines.len());
+    let end = (start + expected.len()).min(lines.len());
+
+    let mut out = lines[..start].to_vec();
+    out.push("<<<<<<< file".to_string());
+    out.extend_from_slice(&lines[start..end]);
+    out.push("||||||| patch expected".to_string());
+    out.extend(expected);
+    out.push("=======".to_string());
+    out.extend(side(crate::data::line_type::LineType::Insertion));
+    out.push(">>>>>>> patch".to_string());
+    out.extend_from_slice(&lines[end..]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{apply_with_conflict_policy, ConflictReport, ConflictResolution};
+
+    const PATCH: &str = "*** Begin Patch\n\
+*** Update File: a.txt\n\
+@@\n a\n-b\n+B\n\
+@@\n x\n-missing\n+M\n\
+*** Update File: c.txt\n\
+@@\n-c\n+C\n\
+*** End Patch";
+
+    fn vfs() -> crate::vfs::Vfs {
+        let mut vfs = crate::vfs::Vfs::new();
+        vfs.insert("a.txt".to_string(), "a\nb\nx\ny\n".to_string());
+        vfs.insert("c.txt".to_string(), "c\n".to_string());
+        vfs
+    }
+
+    fn report(chunk_index: usize, policy: ConflictResolution) -> ConflictReport {
+        ConflictReport { path: "a.txt".to_string(), chunk_index, policy }
+    }
+
+    #[test]
+    fn test_best_effort_keeps_actions_around_a_failure() {
+        let patch = "*** Begin Patch\n\
+*** Update File: a.txt\n@@\n a\n-b\n+B\n\
+*** Update File: missing.txt\n@@\n-x\n+y\n\
+*** Update File: c.txt\n@@\n-c\n+C\n\
+*** End Patch";
+        let result = super::apply_best_effort(patch, &vfs(), ConflictResolution::Skip).unwrap();
+        assert_eq!(result.vfs["a.txt"], "a\nB\nx\ny\n");
+        assert_eq!(result.vfs["c.txt"], "C\n");
+        assert_eq!(result.succeeded, vec![0, 2]);
+        assert_eq!(result.failed.len(), 1);
+        assert!(matches!(result.failed[0], (1, crate::error::ZenpatchError::FileNotFound(_))));
+
+        let aborted = super::apply_best_effort(patch, &vfs(), ConflictResolution::Abort).unwrap();
+        assert_eq!(aborted.succeeded, vec![0]);
+        assert_eq!(aborted.vfs["c.txt"], "c\n");
+    }
+
+    #[test]
+    fn test_best_effort_keep_original_keeps_earlier_actions_on_the_file() {
+        let patch = "*** Begin Patch\n\
+*** Update File: a.txt\n@@\n a\n-b\n+B\n\
+*** Update File: a.txt\n@@\n-missing\n+M\n\
+*** Update File: a.txt\n@@\n x\n-y\n+Y\n\
+*** End Patch";
+        let result = super::apply_best_effort(patch, &vfs(), ConflictResolution::KeepOriginal).unwrap();
+        assert_eq!(result.succeeded, vec![0]);
+        assert_eq!(result.vfs["a.txt"], "a\nB\nx\ny\n");
+        assert_eq!(result.failed.iter().map(|(index, _)| *index).collect::<Vec<_>>(), vec![1, 2]);
+
+        let renamed = "*** Begin Patch\n\
+*** Rename File: c.txt\n*** To: d.txt\n\
+*** Update File: d.txt\n@@\n-missing\n+M\n\
+*** End Patch";
+        let result = super::apply_best_effort(renamed, &vfs(), ConflictResolution::KeepOriginal).unwrap();
+        assert_eq!(result.succeeded, vec![0]);
+        assert_eq!(result.vfs.get("d.txt").map(String::as_str), Some("c\n"));
+    }
+
+    #[test]
+    fn test_best_effort_applies_sections_that_parse() {
+        let patch = "*** Begin Patch\n\
+*** Update File: a.txt\n@@\n a\n-b\n+B\n\
+*** Copy File: a.txt\n\
+*** Update File: c.txt\n@@\n-c\n+C\n\
+*** End Patch";
+        assert!(crate::parser::text_to_patch::text_to_patch(patch).is_err());
+        let result = super::apply_best_effort(patch, &vfs(), ConflictResolution::Skip).unwrap();
+        assert_eq!(result.succeeded, vec![0, 2]);
+        assert!(matches!(result.failed[..], [(1, crate::error::ZenpatchError::InvalidPatchFormat(_))]));
+        assert_eq!(result.vfs["c.txt"], "C\n");
+
+        assert!(super::apply_best_effort("garbage", &vfs(), ConflictResolution::Skip).is_err());
+    }
+
+    #[test]
+    fn test_best_effort_writes_markers_for_conflicts() {
+        let result = super::apply_best_effort(PATCH, &vfs(), ConflictResolution::UseConflictMarkers).unwrap();
+        assert_eq!(result.succeeded, vec![1]);
+        assert!(result.vfs["a.txt"].contains("<<<<<<< file"));
+    }
+
+    #[test]
+    fn test_abort_returns_conflict() {
+        let result = apply_with_conflict_policy(PATCH, &vfs(), ConflictResolution::Abort);
+        assert!(matches!(result, Err(crate::error::ZenpatchError::PatchConflict { chunk_index: 1, .. })));
+    }
+
+    #[test]
+    fn test_skip_drops_conflicting_action() {
+        let (out, reports) = apply_with_conflict_policy(PATCH, &vfs(), ConflictResolution::Skip).unwrap();
+        assert_eq!(out["a.txt"], "a\nb\nx\ny\n");
+        assert_eq!(out["c.txt"], "C\n");
+        assert_eq!(reports, vec![report(1, ConflictResolution::Skip)]);
+    }
+
+    #[test]
+    fn test_keep_original_ignores_later_actions_on_file() {
+        let patch = "*** Begin Patch\n\
+*** Update File: a.txt\n@@\n a\n-b\n+B\n\
+*** Update File: a.txt\n@@\n-missing\n+M\n\
+*** Update File: a.txt\n@@\n x\n-y\n+Y\n\
+*** End Patch";
+        let (out, reports) = apply_with_conflict_policy(patch, &vfs(), ConflictResolution::KeepOriginal).unwrap();
+        assert_eq!(out["a.txt"], "a\nB\nx\ny\n");
+        assert_eq!(reports, vec![report(0, ConflictResolution::KeepOriginal)]);
+    }
+
+    #[test]
+    fn test_keep_original_keeps_files_created_by_earlier_actions() {
+        let patch = "*** Begin Patch\n\
+*** Add File: new.txt\n+n\n\
+*** Update File: new.txt\n@@\n-missing\n+M\n\
+*** Rename File: c.txt\n*** To: d.txt\n\
+*** Update File: d.txt\n@@\n-missing\n+M\n\
+*** End Patch";
+        let (out, reports) = apply_with_conflict_policy(patch, &vfs(), ConflictResolution::KeepOriginal).unwrap();
+        assert_eq!(out.get("new.txt").map(String::as_str), Some("n"));
+        assert_eq!(out.get("d.txt").map(String::as_str), Some("c\n"));
+        assert_eq!(reports.len(), 2);
+    }
+
+    #[test]
+    fn test_conflict_markers_show_file_and_patch_sides() {
+        let (out, reports) =
+            apply_with_conflict_policy(PATCH, &vfs(), ConflictResolution::UseConflictMarkers).unwrap();
+        assert_eq!(
+            out["a.txt"],
+            "a\nB\n<<<<<<< file\nx\ny\n||||||| patch expected\nx\nmissing\n=======\nx\nM\n>>>>>>> patch\n"
+        );
+        assert_eq!(out["c.txt"], "C\n");
+        assert_eq!(reports, vec![report(1, ConflictResolution::UseConflictMarkers)]);
+    }
+}
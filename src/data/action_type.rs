@@ -1,15 +1,20 @@
 //! Defines the type of action represented in a patch operation.
 //!
-//! Represents whether a patch file indicates adding, deleting, or updating a file.
+//! Represents whether a patch file indicates adding, deleting, updating, copying, or renaming a file.
 //! Used within the PatchAction structure to categorize changes.
 //! Derived traits support serialization, comparison, and debugging.
 //! Conforms to the one-item-per-file rule.
 
-#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ActionType {
     Add,
     Delete,
     Update,
+    /// Duplicates `path` verbatim to `new_path`; carries no chunks.
+    Copy,
+    /// Moves `path` to `new_path` without touching its content; carries no chunks.
+    Rename,
 }
 
 #[cfg(test)]
@@ -28,6 +33,7 @@ mod tests {
         std::assert_eq!(std::format!("{:?}", add), "Add");
         std::assert_eq!(std::format!("{:?}", delete), "Delete");
         std::assert_eq!(std::format!("{:?}", update), "Update");
+        std::assert_eq!(std::format!("{:?}", super::ActionType::Copy), "Copy");
     }
 
     #[test]
@@ -49,4 +55,9 @@ mod tests {
 
         std::assert_eq!(original, cloned); // Cloned value should be equal to original.
     }
+
+    #[test]
+    fn test_rename_variant_debug() {
+        std::assert_eq!(std::format!("{:?}", super::ActionType::Rename), "Rename");
+    }
 }
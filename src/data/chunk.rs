@@ -5,7 +5,8 @@
 //! Conforms to the one-item-per-file rule and uses fully qualified paths.
 
 /// Represents a single contiguous block of changes (context/additions/deletions) within a file patch.
-#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Chunk {
     /// The line index in the original file where this chunk's changes apply.
     /// Note: This corresponds to the line number before the first deletion or insertion.
@@ -20,6 +21,17 @@ pub struct Chunk {
     pub change_context: std::option::Option<std::string::String>,
     /// True when `*** End of File` was present after this chunk's lines.
     pub is_end_of_file: bool,
+    /// Escape hatch: the zero-based line where the chunk's leading context
+    /// must start, skipping the position search. Only the deleted lines are
+    /// checked there. Never set by the parser and not part of the text form.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub forced_position: std::option::Option<usize>,
+    /// Text after the `@@` header's line numbers, e.g. the enclosing function
+    /// name git emits in `@@ -42,7 +42,9 @@ fn my_function()`. Kept only for
+    /// display and round-tripping through [`Chunk::to_unified_string`]; it
+    /// has no effect on where the chunk applies.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub context_hint: std::option::Option<std::string::String>,
 }
 
 impl Chunk {
@@ -31,14 +43,414 @@ impl Chunk {
             ins_lines: std::vec::Vec::new(),
             change_context: std::option::Option::None,
             is_end_of_file: false,
+            forced_position: std::option::Option::None,
+            context_hint: std::option::Option::None,
         }
     }
+
+    /// How many lines applying this chunk adds to (positive) or removes from
+    /// (negative) the file.
+    #[must_use]
+    pub fn net_line_delta(&self) -> isize {
+        self.ins_lines.len() as isize - self.del_lines.len() as isize
+    }
+
+    /// Number of `Context` lines at the start of `lines`.
+    #[must_use]
+    pub fn leading_context_count(&self) -> usize {
+        self.lines.iter().take_while(|(lt, _)| *lt == crate::data::line_type::LineType::Context).count()
+    }
+
+    /// Number of `Context` lines at the end of `lines`. For a chunk made only
+    /// of context this equals [`Chunk::leading_context_count`].
+    #[must_use]
+    pub fn trailing_context_count(&self) -> usize {
+        self.lines.iter().rev().take_while(|(lt, _)| *lt == crate::data::line_type::LineType::Context).count()
+    }
+
+    /// The trailing context lines, in file order: the `Context` entries at the
+    /// end of `lines`, the counterpart of the leading context the matcher
+    /// anchors on. For a chunk made only of context this is every line.
+    #[must_use]
+    pub fn post_context_lines(&self) -> std::vec::Vec<&str> {
+        self.lines[self.lines.len() - self.trailing_context_count()..]
+            .iter()
+            .map(|(_, content)| content.as_str())
+            .collect()
+    }
+
+    /// The deleted lines, read from `lines` rather than `del_lines`, so the
+    /// result is right even when `del_lines` was never filled in or has gone
+    /// stale. This does not save memory: the parser still stores
+    /// `del_lines` (and `ins_lines`), which roughly doubles the memory for
+    /// changed lines (`cargo bench --bench chunk_memory` measures it).
+    pub fn del_lines_iter(&self) -> impl std::iter::Iterator<Item = &str> {
+        self.lines_of_type(crate::data::line_type::LineType::Deletion)
+    }
+
+    /// The inserted lines, read from `lines` rather than `ins_lines`; see
+    /// [`Chunk::del_lines_iter`].
+    pub fn ins_lines_iter(&self) -> impl std::iter::Iterator<Item = &str> {
+        self.lines_of_type(crate::data::line_type::LineType::Insertion)
+    }
+
+    fn lines_of_type(
+        &self,
+        wanted: crate::data::line_type::LineType,
+    ) -> impl std::iter::Iterator<Item = &str> {
+        self.lines
+            .iter()
+            .filter(move |(lt, _)| *lt == wanted)
+            .map(|(_, content)| content.as_str())
+    }
+
+    /// Rebuilds `del_lines` and `ins_lines` from the `Deletion` and
+    /// `Insertion` entries of `lines`, in order.
+    pub fn recompute_del_ins_lines(&mut self) {
+        self.del_lines = self.del_lines_iter().map(std::string::String::from).collect();
+        self.ins_lines = self.ins_lines_iter().map(std::string::String::from).collect();
+    }
+
+    /// Splits the chunk into `lines[..line_index]` and `lines[line_index..]`.
+    ///
+    /// The second chunk's `orig_index` moves past the context and deleted
+    /// lines of the first, and only the second keeps `is_end_of_file`; both
+    /// keep `change_context`. Returns `None` when either half would be empty or
+    /// when the split falls inside a run of deletions or a run of insertions.
+    #[must_use]
+    pub fn split_at(&self, line_index: usize) -> std::option::Option<(Chunk, Chunk)> {
+        if line_index == 0 || line_index >= self.lines.len() {
+            return std::option::Option::None;
+        }
+        let before = self.lines[line_index - 1].0;
+        if before == self.lines[line_index].0
+            && std::matches!(
+                before,
+                crate::data::line_type::LineType::Deletion | crate::data::line_type::LineType::Insertion
+            )
+        {
+            return std::option::Option::None;
+        }
+
+        let mut first = Chunk {
+            lines: self.lines[..line_index].to_vec(),
+            is_end_of_file: false,
+            ..self.clone()
+        };
+        let consumed = first
+            .lines
+            .iter()
+            .filter(|(lt, _)| {
+                std::matches!(lt, crate::data::line_type::LineType::Context | crate::data::line_type::LineType::Deletion)
+            })
+            .count();
+        let mut second = Chunk {
+            orig_index: self.orig_index + consumed,
+            forced_position: self.forced_position.map(|pos| pos + consumed),
+            lines: self.lines[line_index..].to_vec(),
+            ..self.clone()
+        };
+        first.recompute_del_ins_lines();
+        second.recompute_del_ins_lines();
+        std::option::Option::Some((first, second))
+    }
+
+    /// A 64-bit hash of everything that decides how the chunk applies: its
+    /// lines with their types, `orig_index`, `change_context`,
+    /// `is_end_of_file` and `forced_position`. Equal chunks get equal
+    /// fingerprints; the value is stable across runs, platforms and Rust
+    /// releases, so it can be stored.
+    #[must_use]
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = crate::data::fingerprint_hasher::FingerprintHasher::new();
+        self.write_fingerprint(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Feeds the fields [`Chunk::fingerprint`] covers to `hasher`.
+    pub(crate) fn write_fingerprint(&self, hasher: &mut crate::data::fingerprint_hasher::FingerprintHasher) {
+        hasher.write_u64(self.orig_index as u64);
+        hasher.write_u64(self.lines.len() as u64);
+        for (line_type, content) in &self.lines {
+            hasher.write_bytes(&[line_type.prefix() as u8]);
+            hasher.write_str(content);
+        }
+        hasher.write_opt_str(self.change_context.as_deref());
+        hasher.write_bytes(&[u8::from(self.is_end_of_file)]);
+        match self.forced_position {
+            std::option::Option::Some(pos) => {
+                hasher.write_bytes(&[1]);
+                hasher.write_u64(pos as u64);
+            }
+            std::option::Option::None => hasher.write_bytes(&[0]),
+        }
+    }
+
+    /// Renders the chunk as a unified-diff hunk: an `@@ -a,b +c,d @@` header
+    /// computed from `orig_index` and the chunk's spans, followed by
+    /// `context_hint` when present, then one prefixed line per entry in
+    /// `lines`. The chunk alone does not know how earlier hunks shifted the
+    /// new file, so the new-side start assumes they did not.
+    #[must_use]
+    pub fn to_unified_string(&self) -> std::string::String {
+        let mut out = self.range_header();
+        if let std::option::Option::Some(hint) = &self.context_hint {
+            out.push(' ');
+            out.push_str(hint);
+        }
+        self.push_prefixed_lines(&mut out);
+        out
+    }
+
+    /// Renders the chunk in the zenpatch text format, the inverse of how the
+    /// parser reads an `Update File` hunk: the `@@` header, one prefixed line
+    /// per entry in `lines`, and `*** End of File` when `is_end_of_file` is
+    /// set. A chunk with a `change_context` gets a bare `@@ <change_context>`
+    /// header; otherwise, when `orig_index > 0`, the header carries the
+    /// `-a,b +c,d @@` range followed by `context_hint`, so the position hint
+    /// survives re-parsing. The parser never sets `change_context` together
+    /// with a range, so unlike the `Display` output, which never writes a
+    /// range, this loses no field the parser sets.
+    #[must_use]
+    pub fn to_patch_text(&self) -> std::string::String {
+        let (mut out, text) = match &self.change_context {
+            std::option::Option::None if self.orig_index > 0 => (self.range_header(), &self.context_hint),
+            _ => (std::string::String::from("@@"), &self.change_context),
+        };
+        if let std::option::Option::Some(text) = text {
+            out.push(' ');
+            out.push_str(text);
+        }
+        self.push_prefixed_lines(&mut out);
+        if self.is_end_of_file {
+            out.push_str("\n*** End of File");
+        }
+        out
+    }
+
+    /// The `@@ -a,b +c,d @@` header for `orig_index` and the chunk's spans.
+    fn range_header(&self) -> std::string::String {
+        let span = |excluded: crate::data::line_type::LineType| {
+            self.lines.iter().filter(|(lt, _)| *lt != excluded).count()
+        };
+        let start = |count: usize| if count == 0 { self.orig_index } else { self.orig_index + 1 };
+        let old_count = span(crate::data::line_type::LineType::Insertion);
+        let new_count = span(crate::data::line_type::LineType::Deletion);
+        std::format!("@@ -{},{} +{},{} @@", start(old_count), old_count, start(new_count), new_count)
+    }
+
+    fn push_prefixed_lines(&self, out: &mut std::string::String) {
+        for (line_type, content) in self {
+            out.push('\n');
+            out.push(line_type.prefix());
+            out.push_str(content);
+        }
+    }
+
+    /// True when the chunk deletes nothing, so it only adds lines around its context.
+    #[must_use]
+    pub fn is_pure_insertion(&self) -> bool {
+        self.del_lines.is_empty()
+    }
+
+    /// True when the chunk inserts nothing, so it only removes lines.
+    #[must_use]
+    pub fn is_pure_deletion(&self) -> bool {
+        self.ins_lines.is_empty()
+    }
+
+    /// True when the chunk neither deletes nor inserts lines (nor carries a
+    /// `\ No newline at end of file` marker), so applying it changes nothing.
+    #[must_use]
+    pub fn is_context_only(&self) -> bool {
+        self.is_pure_insertion() && self.is_pure_deletion() && self.trailing_newline().is_none()
+    }
+
+    /// True when both chunks, placed at their `orig_index`, touch a common
+    /// original line. A chunk touches its leading context and its deleted
+    /// lines, [`Chunk::original_range`]; trailing context is not counted, and
+    /// a pure insertion without leading context touches nothing. Used by
+    /// [`crate::patch_ops::merge_patches`] to detect conflicting chunks.
+    #[must_use]
+    pub fn overlaps_with(&self, other: &Chunk) -> bool {
+        let (a, b) = (self.original_range(), other.original_range());
+        a.start.max(b.start) < a.end.min(b.end)
+    }
+
+    /// `orig_index .. orig_index + leading_context_count() + del_lines.len()`:
+    /// the original lines [`Chunk::overlaps_with`] compares.
+    #[must_use]
+    pub fn original_range(&self) -> std::ops::Range<usize> {
+        self.orig_index..self.orig_index + self.leading_context_count() + self.del_lines.len()
+    }
+
+    /// Checks that `del_lines` and `ins_lines` are exactly the `Deletion` and
+    /// `Insertion` entries of `lines`, in order.
+    pub fn validate(&self) -> std::result::Result<(), crate::error::ZenpatchError> {
+        let expected = |wanted: crate::data::line_type::LineType| {
+            self.lines
+                .iter()
+                .filter(|(lt, _)| *lt == wanted)
+                .map(|(_, content)| content)
+                .collect::<std::vec::Vec<_>>()
+        };
+        if self.del_lines.iter().collect::<std::vec::Vec<_>>() != expected(crate::data::line_type::LineType::Deletion) {
+            return std::result::Result::Err(crate::error::ZenpatchError::InvalidPatchFormat(
+                "chunk del_lines do not match its Deletion lines".to_string(),
+            ));
+        }
+        if self.ins_lines.iter().collect::<std::vec::Vec<_>>() != expected(crate::data::line_type::LineType::Insertion) {
+            return std::result::Result::Err(crate::error::ZenpatchError::InvalidPatchFormat(
+                "chunk ins_lines do not match its Insertion lines".to_string(),
+            ));
+        }
+        std::result::Result::Ok(())
+    }
+
+    /// What a trailing `\ No newline at end of file` marker says about the
+    /// patched file: `Some(false)` when it follows an insertion or context line
+    /// (the new file ends without a newline), `Some(true)` when it only follows
+    /// a deletion (the old file lacked one, the new file does not), and `None`
+    /// when the chunk carries no marker.
+    #[must_use]
+    pub fn trailing_newline(&self) -> std::option::Option<bool> {
+        let marker = self
+            .lines
+            .iter()
+            .rposition(|(lt, _)| *lt == crate::data::line_type::LineType::NoNewlineAtEOF)?;
+        let described = self.lines[..marker]
+            .iter()
+            .rev()
+            .find(|(lt, _)| *lt != crate::data::line_type::LineType::NoNewlineAtEOF)?;
+        std::option::Option::Some(described.0 == crate::data::line_type::LineType::Deletion)
+    }
+
+    /// Consumes the chunk and yields its `lines`, so they can be moved into
+    /// derived structures without cloning. Same as `into_iter()`.
+    pub fn into_lines(self) -> std::vec::IntoIter<(crate::data::line_type::LineType, std::string::String)> {
+        self.lines.into_iter()
+    }
+}
+
+impl std::default::Default for Chunk {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Iterates over the chunk's `lines`, consuming it.
+impl std::iter::IntoIterator for Chunk {
+    type Item = (crate::data::line_type::LineType, std::string::String);
+    type IntoIter = std::vec::IntoIter<(crate::data::line_type::LineType, std::string::String)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.lines.into_iter()
+    }
+}
+
+/// Iterates over the chunk's `lines` as `(type, content)` pairs borrowing the content.
+impl<'a> std::iter::IntoIterator for &'a Chunk {
+    type Item = (crate::data::line_type::LineType, &'a str);
+    type IntoIter = std::iter::Map<
+        std::slice::Iter<'a, (crate::data::line_type::LineType, std::string::String)>,
+        fn(&'a (crate::data::line_type::LineType, std::string::String)) -> (crate::data::line_type::LineType, &'a str),
+    >;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.lines.iter().map(|(line_type, content)| (*line_type, content.as_str()))
+    }
+}
+
+/// Renders the chunk as an `@@` hunk: the header (with `change_context` if
+/// any), one prefixed line per entry in `lines`, and a trailing
+/// `*** End of File` marker when `is_end_of_file` is set.
+impl std::fmt::Display for Chunk {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.change_context {
+            std::option::Option::Some(ctx) => write!(f, "@@ {}", ctx)?,
+            std::option::Option::None => write!(f, "@@")?,
+        }
+        for (line_type, content) in self {
+            write!(f, "\n{}{}", line_type.prefix(), content)?;
+        }
+        if self.is_end_of_file {
+            write!(f, "\n*** End of File")?;
+        }
+        std::result::Result::Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     // Access struct and types via `super::` and fully qualified paths.
 
+    #[test]
+    fn test_fingerprint_follows_equality() {
+        let mut chunk = super::Chunk::new();
+        chunk.lines = std::vec![(crate::data::line_type::LineType::Deletion, "a".to_string())];
+        std::assert_eq!(chunk.fingerprint(), chunk.clone().fingerprint());
+
+        let mut moved = chunk.clone();
+        moved.orig_index = 3;
+        let mut retyped = chunk.clone();
+        retyped.lines[0].0 = crate::data::line_type::LineType::Insertion;
+        std::assert_ne!(chunk.fingerprint(), moved.fingerprint());
+        std::assert_ne!(chunk.fingerprint(), retyped.fingerprint());
+    }
+
+    #[test]
+    fn test_to_unified_string_round_trips_git_hunk() {
+        let hunk = "@@ -2,3 +2,4 @@ fn my_function()\n a\n-b\n+B\n+C\n c";
+        let text = std::format!("*** Begin Patch\n*** Update File: f.rs\n{hunk}\n*** End Patch");
+        let actions = crate::parser::text_to_patch::text_to_patch(&text).unwrap();
+        let chunk = &actions[0].chunks[0];
+        std::assert_eq!(chunk.context_hint.as_deref(), std::option::Option::Some("fn my_function()"));
+        std::assert_eq!(chunk.to_unified_string(), hunk);
+    }
+
+    #[test]
+    fn test_to_patch_text_round_trips_through_parser() {
+        let hunks = [
+            "@@\n a\n-b\n+B",
+            "@@ fn main()\n-x\n+y\n z",
+            "@@ -3,2 +3,3 @@\n c\n-d\n+D\n+E",
+            "@@ -5,0 +6,1 @@ impl Foo\n+added\n*** End of File",
+        ];
+        for hunk in hunks {
+            let wrap = |body: &str| std::format!("*** Begin Patch\n*** Update File: f.rs\n{body}\n*** End Patch");
+            let actions = crate::parser::text_to_patch::text_to_patch(&wrap(hunk)).unwrap();
+            let chunk = &actions[0].chunks[0];
+
+            std::assert_eq!(chunk.to_patch_text(), hunk);
+            let reparsed = crate::parser::text_to_patch::text_to_patch(&wrap(&chunk.to_patch_text())).unwrap();
+            std::assert_eq!(&reparsed[0].chunks[0], chunk);
+        }
+    }
+
+    #[test]
+    fn test_overlaps_with_compares_leading_context_and_deleted_lines() {
+        let chunk = |hunk: &str| {
+            let text = std::format!("*** Begin Patch\n*** Update File: f.txt\n{hunk}\n*** End Patch");
+            crate::parser::text_to_patch::text_to_patch(&text).unwrap().remove(0).chunks.remove(0)
+        };
+        // File lines 2, 3, 4, 5 are z, a, b, c.
+        let deletes_b = chunk("@@ -3,2 +3,2 @@\n a\n-b\n+B");
+        let deletes_b_bare = chunk("@@ -4,1 +4,1 @@\n-b\n+X");
+        let after_b = chunk("@@ -4,2 +4,2 @@\n b\n-c\n+C");
+        let deletes_c = chunk("@@ -5,1 +5,1 @@\n-c\n+C");
+        let before_a = chunk("@@ -2,2 +2,2 @@\n-z\n+Z\n a");
+        let bare_insertion = chunk("@@ -3,0 +4,1 @@\n+new");
+
+        std::assert_eq!(deletes_b.original_range(), 2..4);
+        std::assert!(deletes_b.overlaps_with(&deletes_b_bare));
+        std::assert!(deletes_b_bare.overlaps_with(&deletes_b));
+        std::assert!(deletes_b.overlaps_with(&after_b), "leading context counts");
+        std::assert!(!deletes_b.overlaps_with(&deletes_c));
+        std::assert!(!deletes_b.overlaps_with(&before_a), "trailing context does not count");
+        std::assert!(!bare_insertion.overlaps_with(&deletes_b));
+        std::assert!(!bare_insertion.overlaps_with(&bare_insertion));
+    }
+
     #[test]
     fn test_chunk_creation_empty() {
         // Test creating an empty Chunk.
@@ -49,6 +461,8 @@ mod tests {
             ins_lines: std::vec::Vec::new(),
             change_context: std::option::Option::None,
             is_end_of_file: false,
+            forced_position: std::option::Option::None,
+            context_hint: std::option::Option::None,
         };
         std::assert_eq!(chunk.orig_index, 0);
         std::assert!(chunk.lines.is_empty());
@@ -79,6 +493,8 @@ mod tests {
             ins_lines: ins_lines_data.clone(),
             change_context: std::option::Option::None,
             is_end_of_file: false,
+            forced_position: std::option::Option::None,
+            context_hint: std::option::Option::None,
         };
 
         std::assert_eq!(chunk.orig_index, 10);
@@ -105,6 +521,8 @@ mod tests {
             ins_lines: std::vec::Vec::new(),
             change_context: std::option::Option::None,
             is_end_of_file: false,
+            forced_position: std::option::Option::None,
+            context_hint: std::option::Option::None,
         };
         let chunk2 = chunk1.clone(); // Clone
         let chunk3 = super::Chunk {
@@ -114,6 +532,8 @@ mod tests {
             ins_lines: std::vec::Vec::new(),
             change_context: std::option::Option::None,
             is_end_of_file: false,
+            forced_position: std::option::Option::None,
+            context_hint: std::option::Option::None,
         };
          let chunk4 = super::Chunk {
             orig_index: 5,
@@ -122,6 +542,8 @@ mod tests {
             ins_lines: std::vec::Vec::new(),
             change_context: std::option::Option::None,
             is_end_of_file: false,
+            forced_position: std::option::Option::None,
+            context_hint: std::option::Option::None,
         };
 
 
@@ -129,4 +551,187 @@ mod tests {
         std::assert_ne!(chunk1, chunk3); // Different index should not be equal
         std::assert_ne!(chunk1, chunk4); // Different line type should not be equal
     }
+
+    #[test]
+    fn test_del_and_ins_lines_iter_follow_lines() {
+        let mut chunk = super::Chunk::new();
+        chunk.lines = std::vec![
+            (crate::data::line_type::LineType::Context, std::string::String::from("a")),
+            (crate::data::line_type::LineType::Deletion, std::string::String::from("b")),
+            (crate::data::line_type::LineType::Insertion, std::string::String::from("B")),
+            (crate::data::line_type::LineType::Deletion, std::string::String::from("c")),
+        ];
+        std::assert_eq!(chunk.del_lines_iter().collect::<std::vec::Vec<_>>(), std::vec!["b", "c"]);
+        std::assert_eq!(chunk.ins_lines_iter().collect::<std::vec::Vec<_>>(), std::vec!["B"]);
+    }
+
+    #[test]
+    fn test_leading_and_trailing_context_counts() {
+        let mut chunk = super::Chunk::new();
+        std::assert_eq!((chunk.leading_context_count(), chunk.trailing_context_count()), (0, 0));
+
+        let line = |lt, s: &str| (lt, std::string::String::from(s));
+        chunk.lines = std::vec![
+            line(crate::data::line_type::LineType::Context, "a"),
+            line(crate::data::line_type::LineType::Context, "b"),
+            line(crate::data::line_type::LineType::Deletion, "c"),
+            line(crate::data::line_type::LineType::Context, "d"),
+        ];
+        std::assert_eq!((chunk.leading_context_count(), chunk.trailing_context_count()), (2, 1));
+    }
+
+    #[test]
+    fn test_post_context_lines() {
+        let line = |lt, s: &str| (lt, std::string::String::from(s));
+        let mut chunk = super::Chunk::new();
+        std::assert!(chunk.post_context_lines().is_empty());
+
+        chunk.lines = std::vec![
+            line(crate::data::line_type::LineType::Context, "a"),
+            line(crate::data::line_type::LineType::Insertion, "b"),
+            line(crate::data::line_type::LineType::Context, "c"),
+            line(crate::data::line_type::LineType::Context, "d"),
+        ];
+        std::assert_eq!(chunk.post_context_lines(), std::vec!["c", "d"]);
+
+        chunk.lines.push(line(crate::data::line_type::LineType::Deletion, "e"));
+        std::assert!(chunk.post_context_lines().is_empty());
+    }
+
+    #[test]
+    fn test_chunk_default_matches_new() {
+        std::assert_eq!(super::Chunk::default(), super::Chunk::new());
+    }
+
+    #[test]
+    fn test_chunk_display() {
+        let mut chunk = super::Chunk::new();
+        chunk.change_context = std::option::Option::Some(std::string::String::from("fn main"));
+        chunk.lines = std::vec![
+            (crate::data::line_type::LineType::Context, std::string::String::from("a")),
+            (crate::data::line_type::LineType::Deletion, std::string::String::from("b")),
+            (crate::data::line_type::LineType::Insertion, std::string::String::from("c")),
+        ];
+        chunk.is_end_of_file = true;
+
+        std::assert_eq!(std::format!("{}", chunk), "@@ fn main\n a\n-b\n+c\n*** End of File");
+    }
+
+    #[test]
+    fn test_chunk_validate() {
+        let mut chunk = super::Chunk::new();
+        chunk.lines = std::vec![
+            (crate::data::line_type::LineType::Context, std::string::String::from("a")),
+            (crate::data::line_type::LineType::Deletion, std::string::String::from("b")),
+            (crate::data::line_type::LineType::Insertion, std::string::String::from("c")),
+        ];
+        std::assert!(chunk.validate().is_err(), "derived vectors not yet populated");
+
+        chunk.del_lines = std::vec![std::string::String::from("b")];
+        chunk.ins_lines = std::vec![std::string::String::from("c")];
+        std::assert!(chunk.validate().is_ok());
+
+        chunk.ins_lines.push(std::string::String::from("extra"));
+        std::assert!(std::matches!(
+            chunk.validate(),
+            std::result::Result::Err(crate::error::ZenpatchError::InvalidPatchFormat(msg)) if msg.contains("ins_lines")
+        ));
+    }
+
+    #[test]
+    fn test_net_line_delta() {
+        let mut chunk = super::Chunk::new();
+        std::assert_eq!(chunk.net_line_delta(), 0);
+        chunk.del_lines = std::vec![std::string::String::from("a"), std::string::String::from("b")];
+        chunk.ins_lines = std::vec![std::string::String::from("c")];
+        std::assert_eq!(chunk.net_line_delta(), -1);
+    }
+
+    #[test]
+    fn test_trailing_newline_from_marker() {
+        let mut chunk = super::Chunk::new();
+        std::assert_eq!(chunk.trailing_newline(), std::option::Option::None);
+        chunk.lines = std::vec![
+            (crate::data::line_type::LineType::Deletion, std::string::String::from("a")),
+            (crate::data::line_type::LineType::NoNewlineAtEOF, std::string::String::from(" No newline at end of file")),
+            (crate::data::line_type::LineType::Insertion, std::string::String::from("b")),
+        ];
+        std::assert_eq!(chunk.trailing_newline(), std::option::Option::Some(true));
+        chunk.lines.push((crate::data::line_type::LineType::NoNewlineAtEOF, std::string::String::from(" No newline at end of file")));
+        std::assert_eq!(chunk.trailing_newline(), std::option::Option::Some(false));
+    }
+
+    #[test]
+    fn test_pure_insertion_and_deletion() {
+        let mut chunk = super::Chunk::new();
+        chunk.ins_lines = std::vec![std::string::String::from("a")];
+        std::assert!(chunk.is_pure_insertion());
+        std::assert!(!chunk.is_pure_deletion());
+        chunk.del_lines = std::vec![std::string::String::from("b")];
+        std::assert!(!chunk.is_pure_insertion());
+        chunk.ins_lines.clear();
+        std::assert!(chunk.is_pure_deletion());
+    }
+
+    #[test]
+    fn test_is_context_only() {
+        let mut chunk = super::Chunk::new();
+        chunk.lines = std::vec![(crate::data::line_type::LineType::Context, std::string::String::from("a"))];
+        std::assert!(chunk.is_context_only());
+        chunk.lines.push((crate::data::line_type::LineType::NoNewlineAtEOF, std::string::String::from(" No newline at end of file")));
+        std::assert!(!chunk.is_context_only());
+        chunk.lines.pop();
+        chunk.del_lines = std::vec![std::string::String::from("a")];
+        std::assert!(!chunk.is_context_only());
+    }
+
+    #[test]
+    fn test_into_iterator_yields_lines() {
+        let mut chunk = super::Chunk::new();
+        chunk.lines = std::vec![
+            (crate::data::line_type::LineType::Context, std::string::String::from("a")),
+            (crate::data::line_type::LineType::Insertion, std::string::String::from("b")),
+        ];
+        let borrowed: std::vec::Vec<_> = (&chunk).into_iter().collect();
+        std::assert_eq!(
+            borrowed,
+            std::vec![(crate::data::line_type::LineType::Context, "a"), (crate::data::line_type::LineType::Insertion, "b")]
+        );
+        let owned: std::vec::Vec<_> = chunk.clone().into_iter().collect();
+        std::assert_eq!(owned, chunk.lines);
+        let moved: std::vec::Vec<_> = chunk.clone().into_lines().collect();
+        std::assert_eq!(moved, owned);
+    }
+
+    #[test]
+    fn test_split_at() {
+        let mut chunk = super::Chunk::new();
+        chunk.orig_index = 4;
+        chunk.is_end_of_file = true;
+        chunk.lines = std::vec![
+            (crate::data::line_type::LineType::Context, std::string::String::from("a")),
+            (crate::data::line_type::LineType::Deletion, std::string::String::from("b")),
+            (crate::data::line_type::LineType::Insertion, std::string::String::from("B")),
+            (crate::data::line_type::LineType::Context, std::string::String::from("c")),
+            (crate::data::line_type::LineType::Deletion, std::string::String::from("d")),
+            (crate::data::line_type::LineType::Deletion, std::string::String::from("e")),
+        ];
+        chunk.recompute_del_ins_lines();
+
+        let (first, second) = chunk.split_at(3).unwrap();
+        std::assert_eq!(first.lines, chunk.lines[..3]);
+        std::assert_eq!(first.del_lines, std::vec!["b"]);
+        std::assert_eq!(first.ins_lines, std::vec!["B"]);
+        std::assert!(!first.is_end_of_file);
+        std::assert_eq!(second.orig_index, 6);
+        std::assert_eq!(second.del_lines, std::vec!["d", "e"]);
+        std::assert!(second.ins_lines.is_empty());
+        std::assert!(second.is_end_of_file);
+        first.validate().unwrap();
+        second.validate().unwrap();
+
+        std::assert!(chunk.split_at(5).is_none(), "inside a deletion run");
+        std::assert!(chunk.split_at(0).is_none());
+        std::assert!(chunk.split_at(6).is_none());
+    }
 }
@@ -1,11 +1,13 @@
-//! Defines the type of line within a patch hunk (Context, Deletion, Insertion).
+//! Defines the type of line within a patch hunk (Context, Deletion, Insertion,
+//! and the unified-diff `\ No newline at end of file` marker).
 //!
 //! This enum is used by the `Chunk` struct to represent the structure
 //! of changes within a file update, including context lines necessary for application.
 //! Adheres to the one-item-per-file rule.
 
 /// Represents the type of a line within a patch hunk.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LineType {
     /// A context line, unchanged between versions (starts with ' ').
     Context,
@@ -13,6 +15,34 @@ pub enum LineType {
     Deletion,
     /// A line inserted into the new file (starts with '+').
     Insertion,
+    /// The unified-diff `\ No newline at end of file` marker (starts with '\\').
+    /// It refers to the line before it: that line is the last in its file
+    /// version and has no trailing newline.
+    NoNewlineAtEOF,
+}
+
+impl LineType {
+    /// The character that introduces a line of this type in a patch hunk.
+    pub fn prefix(&self) -> char {
+        match self {
+            LineType::Context => ' ',
+            LineType::Deletion => '-',
+            LineType::Insertion => '+',
+            LineType::NoNewlineAtEOF => '\\',
+        }
+    }
+
+    /// Maps a hunk line's leading character back to its `LineType`, or `None`
+    /// if the character is not a line prefix.
+    pub fn from_prefix(c: char) -> std::option::Option<LineType> {
+        match c {
+            ' ' => std::option::Option::Some(LineType::Context),
+            '-' => std::option::Option::Some(LineType::Deletion),
+            '+' => std::option::Option::Some(LineType::Insertion),
+            '\\' => std::option::Option::Some(LineType::NoNewlineAtEOF),
+            _ => std::option::Option::None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -35,6 +65,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::clone_on_copy)]
     fn test_line_type_copy_clone() {
         // Test that the enum derives Copy and Clone.
         let context1 = super::LineType::Context;
@@ -44,4 +75,26 @@ mod tests {
         std::assert_eq!(context1, context2);
         std::assert_eq!(context1, context3);
     }
+
+    #[test]
+    fn test_prefix_round_trips() {
+        for lt in [
+            super::LineType::Context,
+            super::LineType::Deletion,
+            super::LineType::Insertion,
+            super::LineType::NoNewlineAtEOF,
+        ] {
+            std::assert_eq!(super::LineType::from_prefix(lt.prefix()), std::option::Option::Some(lt));
+        }
+        std::assert_eq!(super::LineType::Context.prefix(), ' ');
+        std::assert_eq!(super::LineType::Deletion.prefix(), '-');
+        std::assert_eq!(super::LineType::Insertion.prefix(), '+');
+        std::assert_eq!(super::LineType::NoNewlineAtEOF.prefix(), '\\');
+    }
+
+    #[test]
+    fn test_from_prefix_unrecognized_is_none() {
+        std::assert_eq!(super::LineType::from_prefix('@'), std::option::Option::None);
+        std::assert_eq!(super::LineType::from_prefix('*'), std::option::Option::None);
+    }
 }
@@ -6,5 +6,8 @@
 //! TypeScript implementation, adapted to Rust's type system and coding standards.
 pub mod action_type;
 pub mod chunk;
+pub(crate) mod fingerprint_hasher;
 pub mod line_type;
+pub mod patch;
 pub mod patch_action;
+pub mod patch_metadata;
@@ -0,0 +1,260 @@
+//! Defines `Patch`, an owned, parsed zenpatch document.
+//!
+//! `Patch` wraps the `PatchAction`s produced by `text_to_patch` so a patch can
+//! be parsed with `str::parse`, iterated as a slice, and applied directly.
+//! Conforms to the one-item-per-file rule and uses fully qualified paths.
+
+/// A parsed patch: the ordered list of file actions it contains.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Patch(pub std::vec::Vec<crate::data::patch_action::PatchAction>);
+
+impl Patch {
+    /// Applies the patch to `vfs` with default options, like [`crate::apply::apply`]
+    /// but without re-parsing the text.
+    pub fn apply(&self, vfs: &crate::vfs::Vfs) -> std::result::Result<crate::vfs::Vfs, crate::error::ZenpatchError> {
+        crate::apply::apply_actions(self.0.clone(), vfs, &crate::apply_options::ApplyOptions::default())
+            .map(|(new_vfs, _)| new_vfs)
+    }
+
+    /// Cheaply checks that the patch could apply to `vfs`, without searching
+    /// for the placement that applying would pick:
+    ///
+    /// 1. every path an action reads exists (in `vfs` or created by an
+    ///    earlier action) and every path it creates does not;
+    /// 2. every `Update` chunk has at least one candidate position, matching
+    ///    as leniently as the default applier eventually does;
+    /// 3. no two chunks of a file can only be placed over the same deleted lines.
+    ///
+    /// Passing does not guarantee that applying succeeds (the placement may
+    /// still be ambiguous), but failing means it cannot. Chunks of a file
+    /// already changed by an earlier action are not checked, since its
+    /// content is only known after applying.
+    pub fn validate_against(&self, vfs: &crate::vfs::Vfs) -> std::result::Result<(), crate::error::ZenpatchError> {
+        let mut present: std::collections::HashSet<std::string::String> = vfs.keys().cloned().collect();
+        let mut touched: std::collections::HashSet<std::string::String> = std::collections::HashSet::new();
+        let resolve = |present: &std::collections::HashSet<std::string::String>, path: &str| {
+            if present.contains(path) {
+                return std::result::Result::Ok(path.to_string());
+            }
+            crate::apply::resolve_vfs_path(vfs, path)
+                .filter(|key| present.contains(key))
+                .ok_or_else(|| crate::error::ZenpatchError::FileNotFound(path.to_string()))
+        };
+        let create = |present: &mut std::collections::HashSet<std::string::String>, path: &str| {
+            if !present.insert(path.to_string()) {
+                return std::result::Result::Err(crate::error::ZenpatchError::FileExists(path.to_string()));
+            }
+            std::result::Result::Ok(())
+        };
+
+        for action in &self.0 {
+            let destination = action.new_path.as_deref();
+            match action.type_ {
+                crate::data::action_type::ActionType::Add => {
+                    create(&mut present, &action.path)?;
+                    touched.insert(action.path.clone());
+                }
+                crate::data::action_type::ActionType::Delete => {
+                    let key = resolve(&present, &action.path)?;
+                    present.remove(&key);
+                    touched.insert(key);
+                }
+                crate::data::action_type::ActionType::Update => {
+                    let key = resolve(&present, &action.path)?;
+                    if !touched.contains(&key) {
+                        if let std::option::Option::Some(content) = vfs.get(&key) {
+                            check_chunks(&crate::split_lines::split_lines(content), &action.chunks)
+                                .map_err(|e| e.with_path(&action.path))?;
+                        }
+                    }
+                    if let std::option::Option::Some(new_path) = destination.filter(|new_path| *new_path != key) {
+                        present.remove(&key);
+                        create(&mut present, new_path)?;
+                        touched.insert(new_path.to_string());
+                    }
+                    touched.insert(key);
+                }
+                crate::data::action_type::ActionType::Copy | crate::data::action_type::ActionType::Rename => {
+                    let key = resolve(&present, &action.path)?;
+                    let new_path = destination.ok_or_else(|| {
+                        crate::error::ZenpatchError::InvalidPatchFormat(format!("{} has no destination", action.path))
+                    })?;
+                    if action.type_ == crate::data::action_type::ActionType::Rename {
+                        present.remove(&key);
+                        touched.insert(key);
+                    }
+                    create(&mut present, new_path)?;
+                    touched.insert(new_path.to_string());
+                }
+            }
+        }
+        std::result::Result::Ok(())
+    }
+}
+
+/// Checks 2 and 3 of [`Patch::validate_against`] for one file's chunks.
+fn check_chunks(
+    lines: &[std::string::String],
+    chunks: &[crate::data::chunk::Chunk],
+) -> std::result::Result<(), crate::error::ZenpatchError> {
+    // Mirrors the patcher's shortcut: an empty file takes any insertions.
+    if lines.is_empty() && chunks.iter().all(|c| c.del_lines.is_empty()) {
+        return std::result::Result::Ok(());
+    }
+    let mode = crate::applier::whitespace_mode::WhitespaceMode::Lenient;
+    let mut ranges: std::vec::Vec<std::vec::Vec<std::ops::Range<usize>>> = std::vec::Vec::with_capacity(chunks.len());
+    for (chunk_index, chunk) in chunks.iter().enumerate() {
+        let positions = crate::applier::backtracking_patcher::valid_positions_for_chunk(lines, chunk, mode);
+        if positions.is_empty() {
+            return std::result::Result::Err(crate::error::ZenpatchError::PatchConflict {
+                path: std::string::String::new(),
+                chunk_index,
+                message: "no position in the file matches the chunk's context and deleted lines".to_string(),
+            });
+        }
+        let chunk_ranges: std::vec::Vec<std::ops::Range<usize>> = positions
+            .iter()
+            .map(|&pos| crate::applier::backtracking_patcher::affected_range(chunk, pos, mode))
+            .collect();
+        let overlaps = |a: &std::ops::Range<usize>, b: &std::ops::Range<usize>| a.start < b.end && b.start < a.end;
+        if let std::option::Option::Some(earlier) = ranges
+            .iter()
+            .position(|other| chunk_ranges.iter().all(|a| other.iter().all(|b| overlaps(a, b))))
+        {
+            return std::result::Result::Err(crate::error::ZenpatchError::PatchConflict {
+                path: std::string::String::new(),
+                chunk_index,
+                message: format!("every candidate position deletes lines that chunk {} also deletes", earlier),
+            });
+        }
+        ranges.push(chunk_ranges);
+    }
+    std::result::Result::Ok(())
+}
+
+impl std::str::FromStr for Patch {
+    type Err = crate::error::ZenpatchError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        crate::parser::text_to_patch::text_to_patch(s).map(Patch)
+    }
+}
+
+impl std::ops::Deref for Patch {
+    type Target = [crate::data::patch_action::PatchAction];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_patch_from_str_and_deref() {
+        let patch: super::Patch = "*** Begin Patch\n*** Add File: a.txt\n+a\n*** Delete File: b.txt\n*** End Patch"
+            .parse()
+            .unwrap();
+
+        std::assert_eq!(patch.len(), 2);
+        let paths: std::vec::Vec<&str> = patch.iter().map(|a| a.path.as_str()).collect();
+        std::assert_eq!(paths, std::vec!["a.txt", "b.txt"]);
+    }
+
+    #[test]
+    fn test_patch_from_str_propagates_parse_errors() {
+        let result: std::result::Result<super::Patch, _> = "not a patch".parse();
+        std::assert!(std::matches!(result, std::result::Result::Err(crate::error::ZenpatchError::InvalidPatchFormat(_))));
+    }
+
+    #[test]
+    fn test_patch_apply_matches_free_function() {
+        let text = "*** Begin Patch\n*** Update File: a.txt\n@@\n-old\n+new\n*** End Patch";
+        let mut vfs = crate::vfs::Vfs::new();
+        vfs.insert("a.txt".to_string(), "old\n".to_string());
+
+        let patch: super::Patch = text.parse().unwrap();
+
+        std::assert_eq!(patch.apply(&vfs).unwrap(), crate::apply::apply(text, &vfs).unwrap());
+        std::assert_eq!(patch.apply(&vfs).unwrap().get("a.txt").unwrap(), "new\n");
+    }
+
+    fn vfs_with(files: &[(&str, &str)]) -> crate::vfs::Vfs {
+        files.iter().map(|(path, content)| (path.to_string(), content.to_string())).collect()
+    }
+
+    #[test]
+    fn test_validate_against_accepts_applicable_patch() {
+        let vfs = vfs_with(&[("a.txt", "one\ntwo\nthree\n"), ("b.txt", "x\n")]);
+        let patch: super::Patch = "*** Begin Patch\n*** Update File: a.txt\n@@\n one\n-two\n+TWO\n*** Delete File: b.txt\n*** Add File: b.txt\n+y\n*** End Patch"
+            .parse()
+            .unwrap();
+
+        std::assert_eq!(patch.validate_against(&vfs), std::result::Result::Ok(()));
+    }
+
+    #[test]
+    fn test_validate_against_reports_missing_and_existing_paths() {
+        let vfs = vfs_with(&[("a.txt", "a\n")]);
+
+        let missing: super::Patch = "*** Begin Patch\n*** Delete File: gone.txt\n*** End Patch".parse().unwrap();
+        std::assert_eq!(
+            missing.validate_against(&vfs),
+            std::result::Result::Err(crate::error::ZenpatchError::FileNotFound("gone.txt".to_string()))
+        );
+
+        let existing: super::Patch = "*** Begin Patch\n*** Add File: a.txt\n+b\n*** End Patch".parse().unwrap();
+        std::assert_eq!(
+            existing.validate_against(&vfs),
+            std::result::Result::Err(crate::error::ZenpatchError::FileExists("a.txt".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_validate_against_follows_earlier_actions() {
+        let vfs = crate::vfs::Vfs::new();
+        let patch: super::Patch = "*** Begin Patch\n*** Add File: new.txt\n+a\n*** Update File: new.txt\n@@\n-a\n+b\n*** End Patch"
+            .parse()
+            .unwrap();
+
+        std::assert_eq!(patch.validate_against(&vfs), std::result::Result::Ok(()));
+    }
+
+    #[test]
+    fn test_validate_against_reports_chunk_without_candidates() {
+        let vfs = vfs_with(&[("a.txt", "one\ntwo\n")]);
+        let patch: super::Patch = "*** Begin Patch\n*** Update File: a.txt\n@@\n-missing\n+new\n*** End Patch"
+            .parse()
+            .unwrap();
+
+        let result = patch.validate_against(&vfs);
+        std::assert!(
+            std::matches!(
+                &result,
+                std::result::Result::Err(crate::error::ZenpatchError::PatchConflict { path, chunk_index: 0, .. }) if path == "a.txt"
+            ),
+            "{:?}",
+            result
+        );
+        std::assert!(vfs.get("a.txt").unwrap() == "one\ntwo\n");
+    }
+
+    #[test]
+    fn test_validate_against_reports_chunks_competing_for_the_same_lines() {
+        let vfs = vfs_with(&[("a.txt", "one\ntwo\nthree\n")]);
+        let patch: super::Patch =
+            "*** Begin Patch\n*** Update File: a.txt\n@@\n-two\n+2\n@@\n-two\n+II\n*** End Patch".parse().unwrap();
+
+        let result = patch.validate_against(&vfs);
+        std::assert!(
+            std::matches!(
+                &result,
+                std::result::Result::Err(crate::error::ZenpatchError::PatchConflict { chunk_index: 1, message, .. })
+                    if message.contains("chunk 0")
+            ),
+            "{:?}",
+            result
+        );
+    }
+}
@@ -7,7 +7,8 @@
 //! Conforms to the one-item-per-file rule and uses fully qualified paths.
 
 /// Represents a single file operation derived from a patch.
-#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PatchAction {
     /// The type of action (Add, Delete, Update).
     pub type_: crate::data::action_type::ActionType,
@@ -16,7 +17,7 @@ pub struct PatchAction {
     /// For `Delete`, this is the path of the file to delete.
     /// For `Update`, this is the path of the file to update.
     pub path: std::string::String,
-    /// The destination path for a move/rename operation. Only used with `Update`.
+    /// The destination path for a move (`Update`), a `Copy`, or a `Rename`.
     pub new_path: std::option::Option<std::string::String>,
     /// The list of changes (hunks) to apply for an `Update` or `Add` action.
     pub chunks: std::vec::Vec<crate::data::chunk::Chunk>,
@@ -31,12 +32,230 @@ impl PatchAction {
             chunks: std::vec::Vec::new(),
         }
     }
+
+    /// Sum of [`crate::data::chunk::Chunk::net_line_delta`] over all chunks.
+    #[must_use]
+    pub fn net_line_delta(&self) -> isize {
+        self.chunks.iter().map(|c| c.net_line_delta()).sum()
+    }
+
+    /// Number of lines inserted across all chunks.
+    #[must_use]
+    pub fn total_insertions(&self) -> usize {
+        self.chunks.iter().map(|c| c.ins_lines.len()).sum()
+    }
+
+    /// Number of lines deleted across all chunks.
+    #[must_use]
+    pub fn total_deletions(&self) -> usize {
+        self.chunks.iter().map(|c| c.del_lines.len()).sum()
+    }
+
+    /// True when no chunk deletes a line, so the action cannot remove existing content.
+    #[must_use]
+    pub fn is_pure_insertion(&self) -> bool {
+        self.chunks.iter().all(crate::data::chunk::Chunk::is_pure_insertion)
+    }
+
+    /// True when no chunk inserts a line; applying it may leave the file empty.
+    #[must_use]
+    pub fn is_pure_deletion(&self) -> bool {
+        self.chunks.iter().all(crate::data::chunk::Chunk::is_pure_deletion)
+    }
+
+    /// Every path the action reads from or writes to: `path`, followed by
+    /// `new_path` for a move, copy or rename.
+    #[must_use]
+    pub fn affected_paths(&self) -> std::vec::Vec<&str> {
+        std::iter::once(self.path.as_str()).chain(self.new_path.as_deref()).collect()
+    }
+
+    /// A 64-bit hash of `type_`, `path`, `new_path` and every chunk's
+    /// [`crate::data::chunk::Chunk::fingerprint`] fields. Equal actions get
+    /// equal fingerprints; the value is stable across runs, platforms and
+    /// Rust releases, so it can be stored.
+    #[must_use]
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = crate::data::fingerprint_hasher::FingerprintHasher::new();
+        let type_tag: u8 = match self.type_ {
+            crate::data::action_type::ActionType::Add => 0,
+            crate::data::action_type::ActionType::Delete => 1,
+            crate::data::action_type::ActionType::Update => 2,
+            crate::data::action_type::ActionType::Copy => 3,
+            crate::data::action_type::ActionType::Rename => 4,
+        };
+        hasher.write_bytes(&[type_tag]);
+        hasher.write_str(&self.path);
+        hasher.write_opt_str(self.new_path.as_deref());
+        hasher.write_u64(self.chunks.len() as u64);
+        for chunk in &self.chunks {
+            chunk.write_fingerprint(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    /// Validates every chunk (see [`crate::data::chunk::Chunk::validate`]) and
+    /// checks that the chunk content fits the action: an `Add` may not delete
+    /// lines and a `Delete` may not insert any.
+    pub fn validate(&self) -> std::result::Result<(), crate::error::ZenpatchError> {
+        for (chunk_index, chunk) in self.chunks.iter().enumerate() {
+            chunk.validate().map_err(|e| match e {
+                crate::error::ZenpatchError::InvalidPatchFormat(msg) => crate::error::ZenpatchError::InvalidPatchFormat(
+                    std::format!("{}: chunk {}: {}", self.path, chunk_index, msg),
+                ),
+                other => other,
+            })?;
+            let forbidden = match self.type_ {
+                crate::data::action_type::ActionType::Add if !chunk.del_lines.is_empty() => {
+                    std::option::Option::Some("an Add action cannot delete lines")
+                }
+                crate::data::action_type::ActionType::Delete if !chunk.ins_lines.is_empty() => {
+                    std::option::Option::Some("a Delete action cannot insert lines")
+                }
+                _ => std::option::Option::None,
+            };
+            if let std::option::Option::Some(msg) = forbidden {
+                return std::result::Result::Err(crate::error::ZenpatchError::InvalidPatchFormat(
+                    std::format!("{}: chunk {}: {}", self.path, chunk_index, msg),
+                ));
+            }
+        }
+        std::result::Result::Ok(())
+    }
+
+    /// Consumes the action and yields its chunks, so they can be moved into
+    /// derived structures without cloning. Same as `into_iter()`, under a
+    /// name that reads clearly at the call site.
+    pub fn into_chunks(self) -> std::vec::IntoIter<crate::data::chunk::Chunk> {
+        self.chunks.into_iter()
+    }
+}
+
+/// An `Update` of the empty path with no chunks.
+impl std::default::Default for PatchAction {
+    fn default() -> Self {
+        Self::new(crate::data::action_type::ActionType::Update, std::string::String::new())
+    }
+}
+
+/// Iterates over the action's chunks, consuming it.
+impl std::iter::IntoIterator for PatchAction {
+    type Item = crate::data::chunk::Chunk;
+    type IntoIter = std::vec::IntoIter<crate::data::chunk::Chunk>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.chunks.into_iter()
+    }
+}
+
+/// Iterates over the action's chunks by reference.
+impl<'a> std::iter::IntoIterator for &'a PatchAction {
+    type Item = &'a crate::data::chunk::Chunk;
+    type IntoIter = std::slice::Iter<'a, crate::data::chunk::Chunk>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.chunks.iter()
+    }
+}
+
+/// Parses patch text that must contain exactly one action; zero or several
+/// actions are an `InvalidPatchFormat` error.
+impl std::convert::TryFrom<&str> for PatchAction {
+    type Error = crate::error::ZenpatchError;
+
+    fn try_from(text: &str) -> std::result::Result<Self, Self::Error> {
+        let mut actions = crate::parser::text_to_patch::text_to_patch(text)?;
+        if actions.len() != 1 {
+            return std::result::Result::Err(crate::error::ZenpatchError::InvalidPatchFormat(std::format!(
+                "expected exactly one action, found {}",
+                actions.len()
+            )));
+        }
+        std::result::Result::Ok(actions.remove(0))
+    }
+}
+
+/// Renders a single-action patch with `*** Begin Patch` / `*** End Patch`
+/// markers, after checking the action with [`PatchAction::validate`] so the
+/// text parses back.
+impl std::convert::TryFrom<PatchAction> for std::string::String {
+    type Error = crate::error::ZenpatchError;
+
+    fn try_from(action: PatchAction) -> std::result::Result<Self, Self::Error> {
+        action.validate()?;
+        std::result::Result::Ok(crate::writer::patch_to_text::patch_to_text(std::slice::from_ref(&action)))
+    }
+}
+
+/// Renders the action in the zenpatch text format: the file directive
+/// (plus `*** Move to:` / `*** To:` where applicable) followed by its body.
+/// Wrapped in `*** Begin Patch` / `*** End Patch`, the output re-parses
+/// with `text_to_patch` to an equal action.
+impl std::fmt::Display for PatchAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.type_ {
+            crate::data::action_type::ActionType::Add => {
+                write!(f, "*** Add File: {}", self.path)?;
+                write_bare_lines(f, &self.chunks)
+            }
+            crate::data::action_type::ActionType::Delete => {
+                write!(f, "*** Delete File: {}", self.path)?;
+                write_bare_lines(f, &self.chunks)
+            }
+            // A content-free move is written with the explicit Rename directive.
+            crate::data::action_type::ActionType::Update if self.chunks.is_empty() && self.new_path.is_some() => {
+                write!(f, "*** Rename File: {}", self.path)?;
+                if let std::option::Option::Some(new_path) = &self.new_path {
+                    write!(f, "\n*** To: {}", new_path)?;
+                }
+                std::result::Result::Ok(())
+            }
+            crate::data::action_type::ActionType::Update => {
+                write!(f, "*** Update File: {}", self.path)?;
+                if let std::option::Option::Some(new_path) = &self.new_path {
+                    write!(f, "\n*** Move to: {}", new_path)?;
+                }
+                for chunk in self {
+                    write!(f, "\n{}", chunk)?;
+                }
+                std::result::Result::Ok(())
+            }
+            crate::data::action_type::ActionType::Copy | crate::data::action_type::ActionType::Rename => {
+                let directive = if self.type_ == crate::data::action_type::ActionType::Copy { "Copy" } else { "Rename" };
+                write!(f, "*** {} File: {}", directive, self.path)?;
+                if let std::option::Option::Some(new_path) = &self.new_path {
+                    write!(f, "\n*** To: {}", new_path)?;
+                }
+                std::result::Result::Ok(())
+            }
+        }
+    }
+}
+
+/// Add and Delete bodies are prefixed lines without an `@@` header.
+fn write_bare_lines(
+    f: &mut std::fmt::Formatter<'_>,
+    chunks: &[crate::data::chunk::Chunk],
+) -> std::fmt::Result {
+    for (line_type, content) in chunks.iter().flat_map(|c| c.lines.iter()) {
+        write!(f, "\n{}{}", line_type.prefix(), content)?;
+    }
+    std::result::Result::Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     // Access struct and types via `super::` and fully qualified paths.
 
+    #[test]
+    fn test_patch_action_default_is_empty_update() {
+        let action = super::PatchAction::default();
+        assert_eq!(action.type_, crate::data::action_type::ActionType::Update);
+        assert!(action.path.is_empty());
+        assert!(action.new_path.is_none());
+        assert!(action.chunks.is_empty());
+    }
+
     #[test]
     fn test_patch_action_add() {
         // Test creating an 'Add' action.
@@ -64,6 +283,8 @@ mod tests {
             ins_lines: std::vec![std::string::String::from("new line")],
             change_context: std::option::Option::None,
             is_end_of_file: false,
+            forced_position: std::option::Option::None,
+            context_hint: std::option::Option::None,
         };
         let action = super::PatchAction {
             type_: crate::data::action_type::ActionType::Update,
@@ -103,6 +324,8 @@ mod tests {
             ins_lines: std::vec![std::string::String::from("added line")],
             change_context: std::option::Option::None,
             is_end_of_file: false,
+            forced_position: std::option::Option::None,
+            context_hint: std::option::Option::None,
         };
         let action = super::PatchAction {
             type_: crate::data::action_type::ActionType::Update, // Or could be Add depending on patch format interpretation for moves
@@ -131,6 +354,8 @@ mod tests {
                 ins_lines: std::vec![std::string::String::from("a")],
                 change_context: std::option::Option::None,
                 is_end_of_file: false,
+                forced_position: std::option::Option::None,
+                context_hint: std::option::Option::None,
             }],
         };
         let action2 = action1.clone();
@@ -144,4 +369,171 @@ mod tests {
         std::assert_eq!(action1, action2); // Cloned should be equal
         std::assert_ne!(action1, action3); // Different actions should not be equal
     }
+
+    #[test]
+    fn test_patch_action_display_round_trips() {
+        let patch_text = "*** Begin Patch\n\
+*** Add File: new.txt\n\
++hello\n\
++\n\
++world\n\
+*** Update File: src/lib.rs\n\
+*** Move to: src/main.rs\n\
+@@ fn main\n\
+ keep\n\
+-old\n\
++new\n\
+@@\n\
+ tail\n\
++appended\n\
+*** End of File\n\
+*** Delete File: gone.txt\n\
+*** Copy File: a.txt\n\
+*** To: b.txt\n\
+*** Rename File: c.txt\n\
+*** To: d.txt\n\
+*** End Patch";
+        let actions = crate::parser::text_to_patch::text_to_patch(patch_text).unwrap();
+        std::assert_eq!(actions.len(), 5);
+
+        for action in &actions {
+            let rendered = std::format!("*** Begin Patch\n{}\n*** End Patch", action);
+            let reparsed = crate::parser::text_to_patch::text_to_patch(&rendered).unwrap();
+            std::assert_eq!(reparsed, std::vec![action.clone()], "rendered:\n{}", rendered);
+        }
+    }
+
+    #[test]
+    fn test_patch_action_validate() {
+        let mut chunk = crate::data::chunk::Chunk::new();
+        chunk.lines = std::vec![(crate::data::line_type::LineType::Deletion, std::string::String::from("x"))];
+        chunk.del_lines = std::vec![std::string::String::from("x")];
+
+        let mut action = super::PatchAction::new(crate::data::action_type::ActionType::Delete, "a.txt".to_string());
+        action.chunks.push(chunk);
+        std::assert!(action.validate().is_ok());
+
+        action.type_ = crate::data::action_type::ActionType::Add;
+        std::assert!(std::matches!(
+            action.validate(),
+            std::result::Result::Err(crate::error::ZenpatchError::InvalidPatchFormat(msg)) if msg.contains("cannot delete")
+        ));
+
+        action.type_ = crate::data::action_type::ActionType::Update;
+        action.chunks[0].del_lines.clear();
+        std::assert!(std::matches!(
+            action.validate(),
+            std::result::Result::Err(crate::error::ZenpatchError::InvalidPatchFormat(msg)) if msg.starts_with("a.txt: chunk 0")
+        ));
+    }
+
+    #[test]
+    fn test_patch_action_line_totals() {
+        let patch_text = "*** Begin Patch\n*** Update File: a.txt\n@@\n ctx\n-a\n+b\n+c\n@@\n-d\n-e\n-f\n*** End Patch";
+        let action = &crate::parser::text_to_patch::text_to_patch(patch_text).unwrap()[0];
+
+        std::assert_eq!(action.total_insertions(), 2);
+        std::assert_eq!(action.total_deletions(), 4);
+        std::assert_eq!(action.net_line_delta(), -2);
+    }
+
+    #[test]
+    fn test_content_free_move_displays_as_rename() {
+        let mut action = super::PatchAction::new(crate::data::action_type::ActionType::Update, "a.txt".to_string());
+        action.new_path = std::option::Option::Some("b.txt".to_string());
+        std::assert_eq!(std::format!("{}", action), "*** Rename File: a.txt\n*** To: b.txt");
+    }
+
+    #[test]
+    fn test_pure_insertion_and_deletion_predicates() {
+        let add = crate::parser::text_to_patch::text_to_patch("*** Begin Patch\n*** Add File: a\n+x\n*** End Patch").unwrap();
+        std::assert!(add[0].is_pure_insertion());
+        std::assert!(!add[0].is_pure_deletion());
+
+        let update =
+            crate::parser::text_to_patch::text_to_patch("*** Begin Patch\n*** Update File: a\n@@\n ctx\n-x\n*** End Patch")
+                .unwrap();
+        std::assert!(update[0].is_pure_deletion());
+        std::assert!(!update[0].is_pure_insertion());
+    }
+
+    #[test]
+    fn test_into_iterator_yields_chunks() {
+        let actions = crate::parser::text_to_patch::text_to_patch(
+            "*** Begin Patch\n*** Update File: a\n@@\n-x\n+y\n@@\n-z\n*** End Patch",
+        )
+        .unwrap();
+        let action = &actions[0];
+        let mut deletions = std::vec::Vec::new();
+        for chunk in action {
+            for (line_type, content) in chunk {
+                if line_type == crate::data::line_type::LineType::Deletion {
+                    deletions.push(content);
+                }
+            }
+        }
+        std::assert_eq!(deletions, std::vec!["x", "z"]);
+        std::assert_eq!(action.clone().into_iter().count(), 2);
+    }
+
+    #[test]
+    fn test_into_chunks_moves_lines_out() {
+        let mut actions = crate::parser::text_to_patch::text_to_patch(
+            "*** Begin Patch\n*** Update File: a\n@@\n-x\n+y\n@@\n-z\n*** End Patch",
+        )
+        .unwrap();
+        let contents: std::vec::Vec<std::string::String> =
+            actions.remove(0).into_chunks().flat_map(|chunk| chunk.into_lines().map(|(_, content)| content)).collect();
+        std::assert_eq!(contents, std::vec!["x", "y", "z"]);
+    }
+
+    #[test]
+    fn test_try_from_round_trips_single_action() {
+        let text = "*** Begin Patch\n*** Update File: a.txt\n@@\n ctx\n-old\n+new\n*** End Patch";
+        let action: super::PatchAction = text.try_into().unwrap();
+        std::assert_eq!(action.path, "a.txt");
+        let rendered: std::string::String = action.try_into().unwrap();
+        std::assert_eq!(rendered, text);
+
+        let two = "*** Begin Patch\n*** Add File: a\n+a\n*** Add File: b\n+b\n*** End Patch";
+        std::assert!(std::matches!(
+            super::PatchAction::try_from(two),
+            std::result::Result::Err(crate::error::ZenpatchError::InvalidPatchFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_fingerprint_distinguishes_paths_and_types() {
+        let action = super::PatchAction::new(crate::data::action_type::ActionType::Update, "a.txt".to_string());
+        std::assert_eq!(action.fingerprint(), action.clone().fingerprint());
+        let mut renamed = action.clone();
+        renamed.new_path = std::option::Option::Some("b.txt".to_string());
+        let deleted = super::PatchAction::new(crate::data::action_type::ActionType::Delete, "a.txt".to_string());
+        std::assert_ne!(action.fingerprint(), renamed.fingerprint());
+        std::assert_ne!(action.fingerprint(), deleted.fingerprint());
+    }
+
+    #[test]
+    fn test_affected_paths() {
+        let mut action = super::PatchAction::new(crate::data::action_type::ActionType::Update, "a.txt".to_string());
+        std::assert_eq!(action.affected_paths(), std::vec!["a.txt"]);
+        action.new_path = std::option::Option::Some("b.txt".to_string());
+        std::assert_eq!(action.affected_paths(), std::vec!["a.txt", "b.txt"]);
+        action.type_ = crate::data::action_type::ActionType::Copy;
+        std::assert_eq!(action.affected_paths(), std::vec!["a.txt", "b.txt"]);
+        let delete = super::PatchAction::new(crate::data::action_type::ActionType::Delete, "c.txt".to_string());
+        std::assert_eq!(delete.affected_paths(), std::vec!["c.txt"]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_json_round_trip() {
+        let actions = crate::parser::text_to_patch::text_to_patch(
+            "*** Begin Patch\n*** Update File: a\n@@ fn main\n ctx\n-x\n+y\n*** End Patch",
+        )
+        .unwrap();
+        let json = serde_json::to_string(&actions).unwrap();
+        let decoded: std::vec::Vec<super::PatchAction> = serde_json::from_str(&json).unwrap();
+        std::assert_eq!(decoded, actions);
+    }
 }
@@ -0,0 +1,33 @@
+//! Defines `PatchMetadata`, the non-action content carried by a patch.
+//!
+//! Annotations such as `*** Comment: <text>` lines and `git format-patch`
+//! style headers (`*** Author:`, `*** Date:`, ...) do not affect how a patch
+//! applies; the parser collects them here so callers can still read them.
+//! Conforms to the one-item-per-file rule and uses fully qualified paths.
+
+/// Annotations collected while parsing a patch.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PatchMetadata {
+    /// Text of every `*** Comment: <text>` and `# <text>` line, in patch order.
+    pub comments: std::vec::Vec<std::string::String>,
+    /// The `*** Author: <name>` header.
+    pub author: std::option::Option<std::string::String>,
+    /// The `*** Date: <iso8601>` header, kept as written.
+    pub date: std::option::Option<std::string::String>,
+    /// The `*** Subject: <text>` header.
+    pub subject: std::option::Option<std::string::String>,
+    /// The `*** Description: <text>` header.
+    pub description: std::option::Option<std::string::String>,
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_patch_metadata_default_is_empty() {
+        let metadata = super::PatchMetadata::default();
+        std::assert!(metadata.comments.is_empty());
+        std::assert!(metadata.author.is_none() && metadata.date.is_none());
+        std::assert!(metadata.subject.is_none() && metadata.description.is_none());
+    }
+}
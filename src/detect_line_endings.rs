@@ -0,0 +1,32 @@
+//! Provides `detect_line_endings`, classifying a text's line terminators.
+//!
+//! Conforms to rust coding guidelines (one item per file).
+
+/// Reports which line terminator `content` uses: `Crlf`, `Lf`, `Cr`, or
+/// `Mixed` when several kinds occur. Text without terminators reports `Lf`.
+pub fn detect_line_endings(content: &str) -> crate::line_ending::LineEnding {
+    let crlf = content.matches("\r\n").count();
+    let lf = content.matches('\n').count() - crlf;
+    let cr = content.matches('\r').count() - crlf;
+    match (crlf > 0, lf > 0, cr > 0) {
+        (true, false, false) => crate::line_ending::LineEnding::Crlf,
+        (false, false, true) => crate::line_ending::LineEnding::Cr,
+        (false, _, false) => crate::line_ending::LineEnding::Lf,
+        _ => crate::line_ending::LineEnding::Mixed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::detect_line_endings;
+    use crate::line_ending::LineEnding;
+
+    #[test]
+    fn test_detect_line_endings() {
+        assert_eq!(detect_line_endings("a\r\nb\r\n"), LineEnding::Crlf);
+        assert_eq!(detect_line_endings("a\nb\n"), LineEnding::Lf);
+        assert_eq!(detect_line_endings("a\rb\r"), LineEnding::Cr);
+        assert_eq!(detect_line_endings("a\r\nb\n"), LineEnding::Mixed);
+        assert_eq!(detect_line_endings("no terminator"), LineEnding::Lf);
+    }
+}
@@ -0,0 +1,129 @@
+//! Human-readable explanations of why a chunk fails to apply.
+//!
+//! `explain_conflict` shows where a chunk comes closest to matching a file and
+//! which of its expected lines differ, for embedding in error messages or logs.
+//! Conforms to rust coding guidelines (one item per file).
+
+/// Explains why `chunk` does not apply to `original`.
+///
+/// The chunk's expected lines (context and deletions, in order) are anchored
+/// at an exact whitespace-lenient match if one exists, otherwise at the
+/// position where the most expected lines match. The report lists that
+/// region with `context_radius` surrounding file lines: `=` marks an expected
+/// line that matches, `!` one that does not, followed by what was found.
+pub fn explain_conflict(original: &str, chunk: &crate::data::chunk::Chunk, context_radius: usize) -> std::string::String {
+    let lines = crate::split_lines::split_lines(original);
+    let expected = expected_lines(chunk);
+
+    if expected.is_empty() {
+        return "the chunk has no context or deleted lines to anchor it; it applies at its position hint".to_string();
+    }
+
+    let matches_at = |start: usize, j: usize| matches_expected(&lines, &expected, start, j);
+    let score = |start: usize| (0..expected.len()).filter(|&j| matches_at(start, j)).count();
+    let start = closest_match(&lines, chunk);
+
+    let mut out = std::format!(
+        "closest match at line {} ({} of {} expected lines match):\n",
+        start + 1,
+        score(start),
+        expected.len()
+    );
+    let surrounding = |out: &mut std::string::String, from: usize, to: usize| {
+        for (n, line) in lines.iter().enumerate().take(to).skip(from) {
+            out.push_str(&std::format!("    {:>5}  {}\n", n + 1, line));
+        }
+    };
+    surrounding(&mut out, start.saturating_sub(context_radius), start);
+    for (j, expected_line) in expected.iter().enumerate() {
+        let n = start + j;
+        let found = lines.get(n).map_or("<end of file>", std::string::String::as_str);
+        if matches_at(start, j) {
+            out.push_str(&std::format!("  = {:>5}  {}\n", n + 1, found));
+        } else {
+            out.push_str(&std::format!("  ! {:>5}  expected: {}\n", n + 1, expected_line));
+            out.push_str(&std::format!("           found:    {}\n", found));
+        }
+    }
+    let after = start + expected.len();
+    surrounding(&mut out, after, after + context_radius);
+    out
+}
+
+/// Index in `lines` where `chunk` fits best: its first exact
+/// whitespace-lenient match if one exists, otherwise the position where the
+/// most of its context and deleted lines match, ties going to the earliest.
+pub(crate) fn closest_match(lines: &[std::string::String], chunk: &crate::data::chunk::Chunk) -> usize {
+    let lenient = crate::applier::whitespace_mode::WhitespaceMode::Lenient;
+    let exact = crate::applier::backtracking_patcher::valid_positions_for_chunk(lines, chunk, lenient);
+    if let std::option::Option::Some(&pos) = exact.first() {
+        return pos;
+    }
+    let expected = expected_lines(chunk);
+    let score = |start: usize| (0..expected.len()).filter(|&j| matches_expected(lines, &expected, start, j)).count();
+    // `max_by_key` keeps the last maximum, so iterate backwards.
+    (0..lines.len().max(1)).rev().max_by_key(|&start| score(start)).unwrap_or(0)
+}
+
+/// The chunk's context and deleted lines, in order.
+fn expected_lines(chunk: &crate::data::chunk::Chunk) -> std::vec::Vec<&str> {
+    chunk
+        .lines
+        .iter()
+        .filter(|(lt, _)| {
+            matches!(lt, crate::data::line_type::LineType::Context | crate::data::line_type::LineType::Deletion)
+        })
+        .map(|(_, content)| content.as_str())
+        .collect()
+}
+
+fn matches_expected(lines: &[std::string::String], expected: &[&str], start: usize, j: usize) -> bool {
+    lines.get(start + j).is_some_and(|l| {
+        crate::applier::backtracking_patcher::match_line(
+            l,
+            expected[j],
+            crate::applier::whitespace_mode::WhitespaceMode::Lenient,
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::explain_conflict;
+
+    fn chunk_from(patch_body: &str) -> crate::data::chunk::Chunk {
+        let text = std::format!("*** Begin Patch\n*** Update File: a.txt\n@@\n{}\n*** End Patch", patch_body);
+        crate::parser::text_to_patch::text_to_patch(&text).unwrap()[0].chunks[0].clone()
+    }
+
+    #[test]
+    fn test_explain_conflict_marks_mismatched_line() {
+        let original = "one\ntwo\nfn main() {\n    let x = 1;\n}\nsix\n";
+        let chunk = chunk_from(" fn main() {\n-    let x = 2;\n+    let x = 3;\n }");
+
+        let report = explain_conflict(original, &chunk, 1);
+
+        assert!(report.starts_with("closest match at line 3 (2 of 3 expected lines match):"), "{report}");
+        assert!(report.contains("        2  two\n"), "{report}");
+        assert!(report.contains("  =     3  fn main() {\n"), "{report}");
+        assert!(report.contains("  !     4  expected:     let x = 2;\n"), "{report}");
+        assert!(report.contains("found:        let x = 1;\n"), "{report}");
+        assert!(report.contains("        6  six\n"), "{report}");
+    }
+
+    #[test]
+    fn test_explain_conflict_reports_past_end_of_file() {
+        let chunk = chunk_from(" a\n-b\n-c");
+        let report = explain_conflict("x\na", &chunk, 0);
+        assert!(report.starts_with("closest match at line 2 (1 of 3 expected lines match):"), "{report}");
+        assert!(report.contains("found:    <end of file>"), "{report}");
+    }
+
+    #[test]
+    fn test_explain_conflict_for_matching_chunk_shows_all_equal() {
+        let chunk = chunk_from(" a\n-b\n+B");
+        let report = explain_conflict("a\nb\n", &chunk, 2);
+        assert!(report.contains("(2 of 2 expected lines match)"), "{report}");
+        assert!(!report.contains("  ! "), "{report}");
+    }
+}
@@ -0,0 +1,446 @@
+//! Computes the patch that turns one `Vfs` into another.
+//!
+//! The inverse of `apply`: applying `diff_vfs(before, after)` to `before`
+//! yields `after`. Updates are built from a line-level Myers diff
+//! ([`lcs_diff`]).
+
+/// Lines of unchanged context kept around each change before uniqueness
+/// expansion.
+const DEFAULT_CONTEXT: usize = 3;
+
+/// Returns the actions that turn `before` into `after`, ordered by path.
+///
+/// Files only in `before` become `Delete`s, files only in `after` become
+/// `Add`s, and changed files become `Update`s whose hunks carry enough
+/// context to match exactly one place in the original. When an `Update`
+/// cannot express the change (a different trailing newline or line-ending
+/// style, or hunks that would not apply back exactly), the file is deleted
+/// and re-added instead.
+pub fn diff_vfs(before: &crate::vfs::Vfs, after: &crate::vfs::Vfs) -> std::vec::Vec<crate::data::patch_action::PatchAction> {
+    let mut paths: std::vec::Vec<&std::string::String> = before.keys().chain(after.keys()).collect();
+    paths.sort();
+    paths.dedup();
+
+    let mut actions = std::vec::Vec::new();
+    for path in paths {
+        match (before.get(path), after.get(path)) {
+            (std::option::Option::Some(old), std::option::Option::None) => actions.push(delete_action(path, old)),
+            (std::option::Option::None, std::option::Option::Some(new)) => actions.push(add_action(path, new)),
+            (std::option::Option::Some(old), std::option::Option::Some(new)) if old != new => {
+                let old_lines = crate::split_lines::split_lines(old);
+                let new_lines = crate::split_lines::split_lines(new);
+                let mut action =
+                    crate::data::patch_action::PatchAction::new(crate::data::action_type::ActionType::Update, path.clone());
+                action.chunks = diff_chunks(&old_lines, &new_lines);
+                if crate::apply::rejoin(old, &new_lines) == *new && update_reproduces(&action, old, new) {
+                    actions.push(action);
+                } else {
+                    actions.push(delete_action(path, old));
+                    actions.push(add_action(path, new));
+                }
+            }
+            _ => {}
+        }
+    }
+    actions
+}
+
+/// Whether applying `action` to `old` really yields `new`. Generated chunks
+/// are matched by content like any other patch, and a few repetitive layouts
+/// (e.g. a context line equal to the deleted line after it) can still land
+/// elsewhere; such files are deleted and re-added instead.
+/// Half-open ranges `(start, end)` of `before` lines that the LCS diff
+/// against `after` keeps unchanged, limited to runs of at least
+/// `min_context` lines, in file order.
+///
+/// These are the candidate context for hunks; callers building their own
+/// chunks can check uniqueness with the applier before emitting them.
+pub fn find_unchanged_regions(
+    before: &[std::string::String],
+    after: &[std::string::String],
+    min_context: usize,
+) -> std::vec::Vec<(usize, usize)> {
+    let mut regions = std::vec::Vec::new();
+    let mut old_at = 0;
+    let mut run_start = std::option::Option::None;
+    for (line_type, _) in line_ops(before, after) {
+        match line_type {
+            crate::data::line_type::LineType::Context => {
+                run_start.get_or_insert(old_at);
+                old_at += 1;
+                continue;
+            }
+            crate::data::line_type::LineType::Deletion => old_at += 1,
+            _ => {}
+        }
+        if let std::option::Option::Some(start) = run_start.take() {
+            // The deletion just counted is not part of the run.
+            let end = if line_type == crate::data::line_type::LineType::Deletion { old_at - 1 } else { old_at };
+            regions.push((start, end));
+        }
+    }
+    if let std::option::Option::Some(start) = run_start {
+        regions.push((start, old_at));
+    }
+    regions.retain(|&(start, end)| end - start >= min_context.max(1));
+    regions
+}
+
+fn update_reproduces(action: &crate::data::patch_action::PatchAction, old: &str, new: &str) -> bool {
+    let vfs: crate::vfs::Vfs = std::iter::once((action.path.clone(), old.to_string())).collect();
+    crate::apply::apply_actions(std::vec![action.clone()], &vfs, &crate::apply_options::ApplyOptions::default())
+        .is_ok_and(|(applied, _)| applied.get(&action.path).map(std::string::String::as_str) == std::option::Option::Some(new))
+}
+
+fn delete_action(path: &str, content: &str) -> crate::data::patch_action::PatchAction {
+    let mut action = crate::data::patch_action::PatchAction::new(crate::data::action_type::ActionType::Delete, path.to_string());
+    let lines = crate::split_lines::split_lines(content);
+    if !lines.is_empty() {
+        action.chunks.push(chunk_from_lines(
+            0,
+            lines.into_iter().map(|l| (crate::data::line_type::LineType::Deletion, l)).collect(),
+        ));
+    }
+    action
+}
+
+/// `Add` content is the insertions joined with `\n`, so splitting on `\n`
+/// alone (keeping any `\r` and a final empty line) reproduces `content` exactly.
+fn add_action(path: &str, content: &str) -> crate::data::patch_action::PatchAction {
+    let mut action = crate::data::patch_action::PatchAction::new(crate::data::action_type::ActionType::Add, path.to_string());
+    action.chunks.push(chunk_from_lines(
+        0,
+        content
+            .split('\n')
+            .map(|l| (crate::data::line_type::LineType::Insertion, l.to_string()))
+            .collect(),
+    ));
+    action
+}
+
+fn chunk_from_lines(
+    orig_index: usize,
+    lines: std::vec::Vec<(crate::data::line_type::LineType, std::string::String)>,
+) -> crate::data::chunk::Chunk {
+    let mut chunk = crate::data::chunk::Chunk { orig_index, lines, ..crate::data::chunk::Chunk::new() };
+    chunk.recompute_del_ins_lines();
+    chunk
+}
+
+/// One step of a line diff, borrowing the line from the side it comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffOp<'a> {
+    /// A line present in both inputs.
+    Keep(&'a str),
+    /// A line only in `after`.
+    Insert(&'a str),
+    /// A line only in `before`.
+    Delete(&'a str),
+}
+
+/// Shortest edit script turning `before` into `after`, in order, computed with
+/// Myers' O((N+M)·D) algorithm (D being the number of inserted plus deleted lines).
+///
+/// Keeping every `Keep` and `Delete` line reproduces `before`; keeping every
+/// `Keep` and `Insert` line reproduces `after`. This is the backend of
+/// [`diff_vfs`], and so of `generate_patch_text(&diff_vfs(..))`.
+pub fn lcs_diff<'a>(before: &'a [std::string::String], after: &'a [std::string::String]) -> std::vec::Vec<DiffOp<'a>> {
+    let prefix = before.iter().zip(after).take_while(|(a, b)| a == b).count();
+    let suffix = before[prefix..]
+        .iter()
+        .rev()
+        .zip(after[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (a, b) = (&before[prefix..before.len() - suffix], &after[prefix..after.len() - suffix]);
+
+    let mut ops: std::vec::Vec<DiffOp<'a>> = before[..prefix].iter().map(|l| DiffOp::Keep(l)).collect();
+    ops.extend(myers_middle(a, b));
+    ops.extend(before[before.len() - suffix..].iter().map(|l| DiffOp::Keep(l)));
+    ops
+}
+
+/// Myers' greedy forward search over lines with no common prefix or suffix.
+/// `trace[d][k + d]` is the furthest `x` reached on diagonal `k = x - y` with
+/// `d` edits; the script is recovered by walking those frontiers back from the
+/// end. The trace costs O(D²) memory, which the prefix/suffix trim keeps small
+/// for the localized edits patches usually describe.
+fn myers_middle<'a>(a: &'a [std::string::String], b: &'a [std::string::String]) -> std::vec::Vec<DiffOp<'a>> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let follow_snake = |mut x: isize, k: isize| {
+        while x < n && x - k < m && a[x as usize] == b[(x - k) as usize] {
+            x += 1;
+        }
+        x
+    };
+    // Whether diagonal k is best reached by an insertion from k + 1 (rather
+    // than a deletion from k - 1), given the frontier after d - 1 edits.
+    let from_above = |prev: &[isize], d: isize, k: isize| {
+        k == -d || (k != d && prev[(k + d - 2) as usize] < prev[(k + d) as usize])
+    };
+
+    let mut trace: std::vec::Vec<std::vec::Vec<isize>> = std::vec::Vec::new();
+    'search: for d in 0..=(n + m) {
+        let mut frontier = std::vec![0; 2 * d as usize + 1];
+        for k in (-d..=d).step_by(2) {
+            let x = match trace.last() {
+                std::option::Option::None => 0,
+                std::option::Option::Some(prev) if from_above(prev, d, k) => prev[(k + d) as usize],
+                std::option::Option::Some(prev) => prev[(k + d - 2) as usize] + 1,
+            };
+            let x = follow_snake(x, k);
+            frontier[(k + d) as usize] = x;
+            if x >= n && x - k >= m {
+                trace.push(frontier);
+                break 'search;
+            }
+        }
+        trace.push(frontier);
+    }
+
+    let mut ops = std::vec::Vec::new();
+    let (mut x, mut y) = (n, m);
+    for d in (1..trace.len() as isize).rev() {
+        let prev = &trace[d as usize - 1];
+        let k = x - y;
+        let inserted = from_above(prev, d, k);
+        let prev_k = if inserted { k + 1 } else { k - 1 };
+        let prev_x = prev[(prev_k + d - 1) as usize];
+        let prev_y = prev_x - prev_k;
+        let step_end_x = if inserted { prev_x } else { prev_x + 1 };
+        while x > step_end_x {
+            x -= 1;
+            ops.push(DiffOp::Keep(&a[x as usize]));
+        }
+        ops.push(if inserted { DiffOp::Insert(&b[prev_y as usize]) } else { DiffOp::Delete(&a[prev_x as usize]) });
+        (x, y) = (prev_x, prev_y);
+    }
+    while x > 0 {
+        x -= 1;
+        ops.push(DiffOp::Keep(&a[x as usize]));
+    }
+    ops.reverse();
+    ops
+}
+
+/// Line operations turning `old` into `new`: `Context` keeps a line,
+/// `Deletion` drops one from `old`, `Insertion` adds one from `new`.
+fn line_ops(old: &[std::string::String], new: &[std::string::String]) -> std::vec::Vec<(crate::data::line_type::LineType, std::string::String)> {
+    lcs_diff(old, new)
+        .into_iter()
+        .map(|op| match op {
+            DiffOp::Keep(l) => (crate::data::line_type::LineType::Context, l.to_string()),
+            DiffOp::Insert(l) => (crate::data::line_type::LineType::Insertion, l.to_string()),
+            DiffOp::Delete(l) => (crate::data::line_type::LineType::Deletion, l.to_string()),
+        })
+        .collect()
+}
+
+/// One chunk per run of changed lines, in the shape the applier matches:
+/// leading context, deletions, insertions, trailing context. Leading context
+/// starts at `DEFAULT_CONTEXT` lines and grows until the chunk has exactly one
+/// candidate position in `old`; a run that cannot be made unique that way is
+/// anchored to the end of the file instead, absorbing every later run.
+fn diff_chunks(old: &[std::string::String], new: &[std::string::String]) -> std::vec::Vec<crate::data::chunk::Chunk> {
+    let ops = line_ops(old, new);
+    let mut chunks = std::vec::Vec::new();
+    let (mut k, mut old_at, mut new_at) = (0, 0, 0);
+    while k < ops.len() {
+        if ops[k].0 == crate::data::line_type::LineType::Context {
+            k += 1;
+            old_at += 1;
+            new_at += 1;
+            continue;
+        }
+        let (old_start, new_start) = (old_at, new_at);
+        while k < ops.len() && ops[k].0 != crate::data::line_type::LineType::Context {
+            if ops[k].0 == crate::data::line_type::LineType::Deletion {
+                old_at += 1;
+            } else {
+                new_at += 1;
+            }
+            k += 1;
+        }
+
+        let mut context = DEFAULT_CONTEXT.min(old_start);
+        loop {
+            let post_end = (old_at + DEFAULT_CONTEXT).min(old.len());
+            let chunk = run_chunk(old, new, old_start - context, (old_start, old_at), (new_start, new_at), post_end);
+            // Uniqueness is judged without the line-number hint, which the
+            // text form of the patch does not carry.
+            let positions = crate::applier::backtracking_patcher::valid_positions_for_chunk(
+                old,
+                &crate::data::chunk::Chunk { orig_index: 0, ..chunk.clone() },
+                crate::applier::whitespace_mode::WhitespaceMode::Strict,
+            );
+            if positions.len() == 1 {
+                chunks.push(chunk);
+                break;
+            }
+            if context < old_start {
+                context = (context * 2).max(1).min(old_start);
+                continue;
+            }
+            let mut tail = run_chunk(old, new, old_start - context, (old_start, old.len()), (new_start, new.len()), old.len());
+            tail.is_end_of_file = true;
+            chunks.push(tail);
+            return chunks;
+        }
+    }
+    chunks
+}
+
+/// A chunk with leading context `old[pre_start..dels.0]`, deletions
+/// `old[dels.0..dels.1]`, insertions `new[ins.0..ins.1]`, and trailing context
+/// `old[dels.1..post_end]`.
+fn run_chunk(
+    old: &[std::string::String],
+    new: &[std::string::String],
+    pre_start: usize,
+    dels: (usize, usize),
+    ins: (usize, usize),
+    post_end: usize,
+) -> crate::data::chunk::Chunk {
+    fn tagged(
+        lt: crate::data::line_type::LineType,
+        lines: &[std::string::String],
+    ) -> impl Iterator<Item = (crate::data::line_type::LineType, std::string::String)> + '_ {
+        lines.iter().map(move |l| (lt, l.clone()))
+    }
+    let lines = tagged(crate::data::line_type::LineType::Context, &old[pre_start..dels.0])
+        .chain(tagged(crate::data::line_type::LineType::Deletion, &old[dels.0..dels.1]))
+        .chain(tagged(crate::data::line_type::LineType::Insertion, &new[ins.0..ins.1]))
+        .chain(tagged(crate::data::line_type::LineType::Context, &old[dels.1..post_end]))
+        .collect();
+    chunk_from_lines(pre_start, lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::diff_vfs;
+
+    #[test]
+    fn test_find_unchanged_regions() {
+        let lines = |text: &str| text.lines().map(std::string::String::from).collect::<std::vec::Vec<_>>();
+        let before = lines("a\nb\nc\nd\ne\nf\ng");
+        let after = lines("a\nb\nX\nd\ne\nf\nY\ng");
+
+        assert_eq!(super::find_unchanged_regions(&before, &after, 1), vec![(0, 2), (3, 6), (6, 7)]);
+        assert_eq!(super::find_unchanged_regions(&before, &after, 2), vec![(0, 2), (3, 6)]);
+        assert_eq!(super::find_unchanged_regions(&before, &before, 3), vec![(0, 7)]);
+    }
+
+    fn lines(text: &str) -> std::vec::Vec<std::string::String> {
+        text.chars().map(std::string::String::from).collect()
+    }
+
+    #[test]
+    fn test_lcs_diff_is_minimal_and_reconstructs_both_sides() {
+        use super::DiffOp;
+        // The example from Myers' paper: the shortest edit script has D = 5.
+        let (before, after) = (lines("ABCABBA"), lines("CBABAC"));
+        let ops = super::lcs_diff(&before, &after);
+
+        let edits = ops.iter().filter(|op| !matches!(op, DiffOp::Keep(_))).count();
+        assert_eq!(edits, 5, "ops: {ops:?}");
+        let side = |keep_inserts: bool| -> std::vec::Vec<&str> {
+            ops.iter()
+                .filter_map(|op| match *op {
+                    DiffOp::Keep(l) => Some(l),
+                    DiffOp::Insert(l) if keep_inserts => Some(l),
+                    DiffOp::Delete(l) if !keep_inserts => Some(l),
+                    _ => None,
+                })
+                .collect()
+        };
+        assert_eq!(side(false), before);
+        assert_eq!(side(true), after);
+    }
+
+    #[test]
+    fn test_lcs_diff_edge_cases() {
+        use super::DiffOp;
+        assert!(super::lcs_diff(&[], &[]).is_empty());
+        assert_eq!(super::lcs_diff(&lines("ab"), &[]), vec![DiffOp::Delete("a"), DiffOp::Delete("b")]);
+        assert_eq!(super::lcs_diff(&[], &lines("a")), vec![DiffOp::Insert("a")]);
+        assert_eq!(super::lcs_diff(&lines("ab"), &lines("ab")), vec![DiffOp::Keep("a"), DiffOp::Keep("b")]);
+    }
+
+    #[test]
+    fn test_lcs_diff_patch_applies_to_after() {
+        let before_text = "fn main() {\n    let a = 1;\n    let b = 2;\n    println!(\"{}\", a + b);\n}\n\nfn unused() {}\n";
+        let after_text = "fn main() {\n    let a = 1;\n    let c = 3;\n    let b = 2;\n    println!(\"{}\", a + b + c);\n}\n";
+        let before = vfs(&[("main.rs", before_text)]);
+        let after = vfs(&[("main.rs", after_text)]);
+
+        let actions = diff_vfs(&before, &after);
+        assert_eq!(actions[0].type_, crate::data::action_type::ActionType::Update);
+        let patch = crate::writer::generate_patch_text::generate_patch_text(&actions);
+        assert_eq!(crate::apply::apply(&patch, &before).unwrap(), after, "patch:\n{patch}");
+    }
+
+    fn vfs(entries: &[(&str, &str)]) -> crate::vfs::Vfs {
+        entries.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    fn assert_round_trip(before: &crate::vfs::Vfs, after: &crate::vfs::Vfs) {
+        let actions = diff_vfs(before, after);
+        let applied = crate::data::patch::Patch(actions.clone()).apply(before).unwrap();
+        assert_eq!(&applied, after, "actions: {actions:#?}");
+    }
+
+    #[test]
+    fn test_diff_vfs_add_delete_update_round_trip() {
+        let before = vfs(&[("keep.txt", "same\n"), ("gone.txt", "bye\n"), ("f.txt", "a\nb\nc\nd\ne\nf\ng\nh\n")]);
+        let after = vfs(&[("keep.txt", "same\n"), ("new.txt", "hello\nworld\n"), ("f.txt", "a\nB\nc\nd\ne\nf\ng\nh\ni\n")]);
+
+        let actions = diff_vfs(&before, &after);
+        let kinds: std::vec::Vec<(&str, crate::data::action_type::ActionType)> =
+            actions.iter().map(|a| (a.path.as_str(), a.type_.clone())).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("f.txt", crate::data::action_type::ActionType::Update),
+                ("gone.txt", crate::data::action_type::ActionType::Delete),
+                ("new.txt", crate::data::action_type::ActionType::Add),
+            ]
+        );
+        assert_round_trip(&before, &after);
+    }
+
+    #[test]
+    fn test_diff_vfs_expands_context_until_unique() {
+        let before = vfs(&[("f.txt", "}\n}\n}\n}\nx\n}\n}\n}\n}\n")]);
+        let after = vfs(&[("f.txt", "}\n}\n}\n}\nx\n}\n}\n}\n}\n}\n")]);
+        assert_round_trip(&before, &after);
+
+        let before = vfs(&[("f.txt", "a\na\na\na\na\na\na\na\na\n")]);
+        let after = vfs(&[("f.txt", "a\na\na\na\nb\na\na\na\na\n")]);
+        assert_round_trip(&before, &after);
+    }
+
+    #[test]
+    fn test_diff_vfs_trailing_newline_and_crlf_changes() {
+        assert_round_trip(&vfs(&[("f.txt", "a\nb\n")]), &vfs(&[("f.txt", "a\nb")]));
+        assert_round_trip(&vfs(&[("f.txt", "a\nb\n")]), &vfs(&[("f.txt", "a\r\nb\r\n")]));
+        assert_round_trip(&vfs(&[("f.txt", "a\r\nb\r\n")]), &vfs(&[("f.txt", "a\r\nc\r\n")]));
+        assert_round_trip(&vfs(&[("f.txt", "")]), &vfs(&[("f.txt", "x\n")]));
+        assert_round_trip(&vfs(&[("f.txt", "x\n")]), &vfs(&[]));
+    }
+
+    #[test]
+    fn test_diff_vfs_identical_is_empty() {
+        let state = vfs(&[("a.txt", "a\n")]);
+        assert!(diff_vfs(&state, &state).is_empty());
+    }
+
+    #[test]
+    fn test_diff_vfs_update_hunks_match_uniquely() {
+        let before = vfs(&[("f.txt", "x\n}\n}\n}\ny\n}\n}\n}\nz\n")]);
+        let after = vfs(&[("f.txt", "x\n}\n}\n}\ny\n}\n}\n}\nw\nz\n")]);
+        let actions = diff_vfs(&before, &after);
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].type_, crate::data::action_type::ActionType::Update);
+        assert_round_trip(&before, &after);
+    }
+}
@@ -0,0 +1,129 @@
+//! Applies patches to files in a legacy encoding (behind the `encoding` feature).
+//!
+//! The applier works on UTF-8 `String`s, so [`apply_to_binary_vfs`] decodes
+//! every file from the caller's encoding, applies the patch to the decoded
+//! [`crate::vfs::Vfs`], and encodes the result back.
+
+/// A VFS of raw file bytes, for files that are not UTF-8.
+pub type BinaryVfs = std::collections::HashMap<std::string::String, std::vec::Vec<u8>>;
+
+/// Applies `patch_text` to files stored in `source_encoding` (e.g.
+/// `encoding_rs::WINDOWS_1252`, which also covers Latin-1).
+///
+/// The patch text itself is UTF-8, like any Rust string. Files the patch
+/// leaves unchanged keep their original bytes. Fails with an `IoError` when a
+/// file is not valid in `source_encoding`, when the patched text has
+/// characters the encoding cannot represent, or when the encoding cannot be
+/// written at all (UTF-16 and `replacement`, which `encoding_rs` only decodes).
+pub fn apply_to_binary_vfs(
+    patch_text: &str,
+    vfs: &BinaryVfs,
+    source_encoding: &'static encoding_rs::Encoding,
+) -> std::result::Result<BinaryVfs, crate::error::ZenpatchError> {
+    if source_encoding.output_encoding() != source_encoding {
+        return std::result::Result::Err(encoding_error(std::format!(
+            "cannot write files back in {}",
+            source_encoding.name()
+        )));
+    }
+
+    let mut decoded = crate::vfs::Vfs::with_capacity(vfs.len());
+    for (path, bytes) in vfs {
+        let text = source_encoding
+            .decode_without_bom_handling_and_without_replacement(bytes)
+            .ok_or_else(|| encoding_error(std::format!("{}: not valid {}", path, source_encoding.name())))?;
+        decoded.insert(path.clone(), text.into_owned());
+    }
+
+    let patched = crate::apply::apply(patch_text, &decoded)?;
+
+    let mut result = BinaryVfs::with_capacity(patched.len());
+    for (path, text) in patched {
+        if decoded.get(&path) == std::option::Option::Some(&text) {
+            let original = vfs.get(&path).cloned().unwrap_or_default();
+            result.insert(path, original);
+            continue;
+        }
+        let (bytes, _, unmappable) = source_encoding.encode(&text);
+        if unmappable {
+            return std::result::Result::Err(encoding_error(std::format!(
+                "{}: the patched text has characters {} cannot represent",
+                path,
+                source_encoding.name()
+            )));
+        }
+        result.insert(path, bytes.into_owned());
+    }
+    std::result::Result::Ok(result)
+}
+
+fn encoding_error(message: std::string::String) -> crate::error::ZenpatchError {
+    crate::error::ZenpatchError::IoError { message, source: std::option::Option::None }
+}
+
+#[cfg(test)]
+mod tests {
+    fn latin1_vfs() -> super::BinaryVfs {
+        let mut vfs = super::BinaryVfs::new();
+        // "café\nnaïve\n" in Latin-1: é is 0xE9 and ï is 0xEF.
+        vfs.insert("menu.txt".to_string(), b"caf\xe9\nna\xefve\n".to_vec());
+        vfs.insert("other.txt".to_string(), b"\x81 untouched\n".to_vec());
+        vfs
+    }
+
+    #[test]
+    fn test_patches_latin1_file_and_writes_latin1_back() {
+        let patch = "*** Begin Patch\n*** Update File: menu.txt\n@@\n café\n-naïve\n+crème brûlée\n*** End Patch";
+
+        let result = super::apply_to_binary_vfs(patch, &latin1_vfs(), encoding_rs::WINDOWS_1252).unwrap();
+
+        std::assert_eq!(result["menu.txt"], b"caf\xe9\ncr\xe8me br\xfbl\xe9e\n".to_vec());
+        std::assert_eq!(result["other.txt"], b"\x81 untouched\n".to_vec());
+    }
+
+    #[test]
+    fn test_added_files_are_encoded_too() {
+        let patch = "*** Begin Patch\n*** Add File: new.txt\n+déjà vu\n*** End Patch";
+
+        let result = super::apply_to_binary_vfs(patch, &super::BinaryVfs::new(), encoding_rs::WINDOWS_1252).unwrap();
+
+        std::assert_eq!(result["new.txt"], b"d\xe9j\xe0 vu".to_vec());
+    }
+
+    #[test]
+    fn test_unrepresentable_characters_are_an_error() {
+        let patch = "*** Begin Patch\n*** Update File: menu.txt\n@@\n-naïve\n+日本\n*** End Patch";
+
+        let result = super::apply_to_binary_vfs(patch, &latin1_vfs(), encoding_rs::WINDOWS_1252);
+
+        std::assert!(
+            std::matches!(
+                &result,
+                std::result::Result::Err(crate::error::ZenpatchError::IoError { message, .. })
+                    if message.starts_with("menu.txt:")
+            ),
+            "{:?}",
+            result
+        );
+    }
+
+    #[test]
+    fn test_invalid_source_bytes_are_an_error() {
+        let mut vfs = super::BinaryVfs::new();
+        vfs.insert("bad.txt".to_string(), b"\xff\xfe\xfd".to_vec());
+        let patch = "*** Begin Patch\n*** Delete File: bad.txt\n*** End Patch";
+
+        let result = super::apply_to_binary_vfs(patch, &vfs, encoding_rs::UTF_8);
+
+        std::assert!(std::matches!(result, std::result::Result::Err(crate::error::ZenpatchError::IoError { .. })));
+    }
+
+    #[test]
+    fn test_decode_only_encodings_are_rejected() {
+        let patch = "*** Begin Patch\n*** End Patch";
+
+        let result = super::apply_to_binary_vfs(patch, &super::BinaryVfs::new(), encoding_rs::UTF_16LE);
+
+        std::assert!(std::matches!(result, std::result::Result::Err(crate::error::ZenpatchError::IoError { .. })));
+    }
+}
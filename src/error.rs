@@ -5,7 +5,11 @@
 //! It provides detailed variants to pinpoint the source of the error.
 //! Corresponds to the TypeScript `DiffError` type.
 
-#[derive(Debug, PartialEq)]
+/// Boxed underlying error kept by [`ZenpatchError::IoError`] and
+/// [`ZenpatchError::AnyhowError`] for [`std::error::Error::source`].
+pub type BoxedSource = std::boxed::Box<dyn std::error::Error + Send + Sync>;
+
+#[derive(Debug)]
 pub enum ZenpatchError {
     InvalidPatchFormat(std::string::String),
     FileNotFound(std::string::String),
@@ -16,32 +20,200 @@ pub enum ZenpatchError {
     InvalidContext(usize, std::string::String), // index, context text
     InvalidEOFContext(usize, std::string::String), // index, context text
     IndexOutOfBounds(std::string::String), // General index error message
-    IoError(std::string::String), // Wrap std::io::Error messages
-    PatchConflict(std::string::String), // Conflict between patch and file content
-    ContextNotFound(std::string::String), // Context lines not found in the file
-    AmbiguousPatch(std::string::String), // Patch context matches in multiple valid, non-overlapping ways
-    AnyhowError(String),
+    /// A filesystem operation failed.
+    IoError {
+        /// What failed, usually prefixed with the path involved.
+        message: std::string::String,
+        /// The underlying `std::io::Error`, when there was one (path
+        /// validation failures have none).
+        source: std::option::Option<BoxedSource>,
+    },
+    /// Conflict between patch and file content.
+    PatchConflict {
+        /// File the conflicting hunk targets (empty when not yet known).
+        path: std::string::String,
+        /// Zero-based index of the offending chunk within its file's hunks.
+        chunk_index: usize,
+        message: std::string::String,
+    },
+    /// Context lines not found in the file.
+    ContextNotFound {
+        path: std::string::String,
+        chunk_index: usize,
+        message: std::string::String,
+    },
+    /// Patch context matches in multiple valid, non-overlapping ways.
+    AmbiguousPatch {
+        path: std::string::String,
+        chunk_index: usize,
+        message: std::string::String,
+    },
+    /// An error from outside the crate (e.g. an `anyhow::Error` converted
+    /// with `.into()`), kept whole as the error's `source`. A `&str` or
+    /// `String` message converts too.
+    AnyhowError(BoxedSource),
     PatchApplicationFailed(String),
 }
 
 impl ZenpatchError {
-    /// Prefix the failing file's path to a location error so a multi-file patch reports
-    /// WHICH file's hunk could not be applied (e.g. `in src/lib.rs: Patch conflict: …`).
+    /// Records the failing file's path on a location error so a multi-file patch reports
+    /// WHICH file's hunk could not be applied (e.g. `Patch conflict in 'src/lib.rs' chunk 0: …`).
     /// Errors that are not tied to a single file's content are returned unchanged.
     pub fn with_path(self, path: &str) -> Self {
         match self {
-            ZenpatchError::PatchConflict(m) => {
-                ZenpatchError::PatchConflict(format!("in {}: {}", path, m))
+            ZenpatchError::PatchConflict { chunk_index, message, .. } => {
+                ZenpatchError::PatchConflict { path: path.to_string(), chunk_index, message }
+            }
+            ZenpatchError::AmbiguousPatch { chunk_index, message, .. } => {
+                ZenpatchError::AmbiguousPatch { path: path.to_string(), chunk_index, message }
+            }
+            ZenpatchError::ContextNotFound { chunk_index, message, .. } => {
+                ZenpatchError::ContextNotFound { path: path.to_string(), chunk_index, message }
+            }
+            other => other,
+        }
+    }
+
+    /// Replaces the chunk index of a location error, for callers that apply
+    /// hunks one at a time (where the patcher only ever sees chunk 0).
+    /// Other errors are returned unchanged.
+    pub fn with_chunk_index(self, chunk_index: usize) -> Self {
+        match self {
+            ZenpatchError::PatchConflict { path, message, .. } => {
+                ZenpatchError::PatchConflict { path, chunk_index, message }
             }
-            ZenpatchError::AmbiguousPatch(m) => {
-                ZenpatchError::AmbiguousPatch(format!("in {}: {}", path, m))
+            ZenpatchError::AmbiguousPatch { path, message, .. } => {
+                ZenpatchError::AmbiguousPatch { path, chunk_index, message }
             }
-            ZenpatchError::ContextNotFound(m) => {
-                ZenpatchError::ContextNotFound(format!("in {}: {}", path, m))
+            ZenpatchError::ContextNotFound { path, message, .. } => {
+                ZenpatchError::ContextNotFound { path, chunk_index, message }
             }
             other => other,
         }
     }
+
+    /// Stable snake_case name of the variant, used as `error_type` in [`Self::to_json`].
+    fn error_type(&self) -> &'static str {
+        match self {
+            ZenpatchError::InvalidPatchFormat(_) => "invalid_patch_format",
+            ZenpatchError::FileNotFound(_) => "file_not_found",
+            ZenpatchError::DuplicatePath(_) => "duplicate_path",
+            ZenpatchError::MissingFile(_) => "missing_file",
+            ZenpatchError::FileExists(_) => "file_exists",
+            ZenpatchError::InvalidLine(_) => "invalid_line",
+            ZenpatchError::InvalidContext(..) => "invalid_context",
+            ZenpatchError::InvalidEOFContext(..) => "invalid_eof_context",
+            ZenpatchError::IndexOutOfBounds(_) => "index_out_of_bounds",
+            ZenpatchError::IoError { .. } => "io_error",
+            ZenpatchError::PatchConflict { .. } => "patch_conflict",
+            ZenpatchError::ContextNotFound { .. } => "context_not_found",
+            ZenpatchError::AmbiguousPatch { .. } => "ambiguous_patch",
+            ZenpatchError::AnyhowError(_) => "anyhow_error",
+            ZenpatchError::PatchApplicationFailed(_) => "patch_application_failed",
+        }
+    }
+
+    /// Stable numeric code for telemetry. Codes are never reused or renumbered;
+    /// new variants take the next free value.
+    pub fn error_code(&self) -> u32 {
+        match self {
+            ZenpatchError::InvalidPatchFormat(_) => 1001,
+            ZenpatchError::FileNotFound(_) => 1002,
+            ZenpatchError::DuplicatePath(_) => 1003,
+            ZenpatchError::MissingFile(_) => 1004,
+            ZenpatchError::FileExists(_) => 1005,
+            ZenpatchError::InvalidLine(_) => 1006,
+            ZenpatchError::InvalidContext(..) => 1007,
+            ZenpatchError::InvalidEOFContext(..) => 1008,
+            ZenpatchError::IndexOutOfBounds(_) => 1009,
+            ZenpatchError::IoError { .. } => 1010,
+            ZenpatchError::PatchConflict { .. } => 1011,
+            ZenpatchError::ContextNotFound { .. } => 1012,
+            ZenpatchError::AmbiguousPatch { .. } => 1013,
+            ZenpatchError::AnyhowError(_) => 1014,
+            ZenpatchError::PatchApplicationFailed(_) => 1015,
+        }
+    }
+
+    /// Serializes the error as a single-line JSON object for machine consumers.
+    ///
+    /// Every object carries `error_type` (snake_case variant name), `code`
+    /// ([`Self::error_code`]) and `message` (the `Display` text). Variants with
+    /// structured payloads add their fields: `path` for file errors, `line` for
+    /// [`ZenpatchError::InvalidLine`], `index`/`context` for the context errors, and
+    /// `path`/`chunk_index`/`detail` for location errors (`detail` being the variant's
+    /// own `message` field, renamed so it does not clash with the top-level one).
+    /// Hand-written so it works without the `serde` feature.
+    pub fn to_json(&self) -> std::string::String {
+        let mut out = std::string::String::from("{");
+        write_json_field(&mut out, "error_type", self.error_type());
+        out.push_str(&std::format!(",\"code\":{}", self.error_code()));
+        out.push(',');
+        write_json_field(&mut out, "message", &self.to_string());
+        match self {
+            ZenpatchError::FileNotFound(path)
+            | ZenpatchError::DuplicatePath(path)
+            | ZenpatchError::MissingFile(path)
+            | ZenpatchError::FileExists(path) => {
+                out.push(',');
+                write_json_field(&mut out, "path", path);
+            }
+            ZenpatchError::InvalidLine(line) => {
+                out.push(',');
+                write_json_field(&mut out, "line", line);
+            }
+            ZenpatchError::InvalidContext(index, context) | ZenpatchError::InvalidEOFContext(index, context) => {
+                out.push_str(&std::format!(",\"index\":{},", index));
+                write_json_field(&mut out, "context", context);
+            }
+            ZenpatchError::PatchConflict { path, chunk_index, message }
+            | ZenpatchError::ContextNotFound { path, chunk_index, message }
+            | ZenpatchError::AmbiguousPatch { path, chunk_index, message } => {
+                out.push(',');
+                write_json_field(&mut out, "path", path);
+                out.push_str(&std::format!(",\"chunk_index\":{},", chunk_index));
+                write_json_field(&mut out, "detail", message);
+            }
+            _ => {}
+        }
+        out.push('}');
+        out
+    }
+}
+
+/// Appends `"key":"value"` to `out`, escaping `value` per RFC 8259.
+fn write_json_field(out: &mut std::string::String, key: &str, value: &str) {
+    out.push('"');
+    out.push_str(key);
+    out.push_str("\":\"");
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&std::format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Writes `"<label> in '<path>' chunk <i>: <message>"`, dropping the path part
+/// when the location has no path yet.
+fn write_location(
+    f: &mut std::fmt::Formatter<'_>,
+    label: &str,
+    path: &str,
+    chunk_index: usize,
+    message: &str,
+) -> std::fmt::Result {
+    if path.is_empty() {
+        write!(f, "{} in chunk {}: {}", label, chunk_index, message)
+    } else {
+        write!(f, "{} in '{}' chunk {}: {}", label, path, chunk_index, message)
+    }
 }
 
 impl std::fmt::Display for ZenpatchError {
@@ -56,17 +228,75 @@ impl std::fmt::Display for ZenpatchError {
             ZenpatchError::InvalidContext(idx, ctx) => write!(f, "Invalid context at index {}: {}", idx, ctx),
             ZenpatchError::InvalidEOFContext(idx, ctx) => write!(f, "Invalid end-of-file context at index {}: {}", idx, ctx),
             ZenpatchError::IndexOutOfBounds(msg) => write!(f, "Index out of bounds: {}", msg),
-            ZenpatchError::IoError(msg) => write!(f, "I/O error: {}", msg),
-            ZenpatchError::PatchConflict(msg) => write!(f, "Patch conflict: {}", msg),
-            ZenpatchError::ContextNotFound(msg) => write!(f, "Context not found: {}", msg),
-            ZenpatchError::AmbiguousPatch(msg) => write!(f, "Ambiguous patch: {}", msg),
-            ZenpatchError::AnyhowError(msg) =>write!(f, "Anyhow error: {}", msg),
+            ZenpatchError::IoError { message, .. } => write!(f, "I/O error: {}", message),
+            ZenpatchError::PatchConflict { path, chunk_index, message } => {
+                write_location(f, "Patch conflict", path, *chunk_index, message)
+            }
+            ZenpatchError::ContextNotFound { path, chunk_index, message } => {
+                write_location(f, "Context not found", path, *chunk_index, message)
+            }
+            ZenpatchError::AmbiguousPatch { path, chunk_index, message } => {
+                write_location(f, "Ambiguous patch", path, *chunk_index, message)
+            }
+            ZenpatchError::AnyhowError(err) => write!(f, "Anyhow error: {}", err),
             ZenpatchError::PatchApplicationFailed(msg) => write!(f, "Patch application: {}", msg),
         }
     }
 }
 
-impl std::error::Error for ZenpatchError {}
+/// Errors are equal when their variants and fields are; boxed sources,
+/// which cannot be compared, are equal when they display the same.
+impl std::cmp::PartialEq for ZenpatchError {
+    fn eq(&self, other: &Self) -> bool {
+        let same_source = |a: &BoxedSource, b: &BoxedSource| a.to_string() == b.to_string();
+        match (self, other) {
+            (ZenpatchError::InvalidPatchFormat(a), ZenpatchError::InvalidPatchFormat(b))
+            | (ZenpatchError::FileNotFound(a), ZenpatchError::FileNotFound(b))
+            | (ZenpatchError::DuplicatePath(a), ZenpatchError::DuplicatePath(b))
+            | (ZenpatchError::MissingFile(a), ZenpatchError::MissingFile(b))
+            | (ZenpatchError::FileExists(a), ZenpatchError::FileExists(b))
+            | (ZenpatchError::InvalidLine(a), ZenpatchError::InvalidLine(b))
+            | (ZenpatchError::IndexOutOfBounds(a), ZenpatchError::IndexOutOfBounds(b))
+            | (ZenpatchError::PatchApplicationFailed(a), ZenpatchError::PatchApplicationFailed(b)) => a == b,
+            (ZenpatchError::InvalidContext(i, a), ZenpatchError::InvalidContext(j, b))
+            | (ZenpatchError::InvalidEOFContext(i, a), ZenpatchError::InvalidEOFContext(j, b)) => i == j && a == b,
+            (
+                ZenpatchError::IoError { message: a, source: source_a },
+                ZenpatchError::IoError { message: b, source: source_b },
+            ) => {
+                a == b
+                    && match (source_a, source_b) {
+                        (std::option::Option::Some(x), std::option::Option::Some(y)) => same_source(x, y),
+                        (x, y) => x.is_none() && y.is_none(),
+                    }
+            }
+            (
+                ZenpatchError::PatchConflict { path: p, chunk_index: i, message: m },
+                ZenpatchError::PatchConflict { path: q, chunk_index: j, message: n },
+            )
+            | (
+                ZenpatchError::ContextNotFound { path: p, chunk_index: i, message: m },
+                ZenpatchError::ContextNotFound { path: q, chunk_index: j, message: n },
+            )
+            | (
+                ZenpatchError::AmbiguousPatch { path: p, chunk_index: i, message: m },
+                ZenpatchError::AmbiguousPatch { path: q, chunk_index: j, message: n },
+            ) => p == q && i == j && m == n,
+            (ZenpatchError::AnyhowError(a), ZenpatchError::AnyhowError(b)) => same_source(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl std::error::Error for ZenpatchError {
+    fn source(&self) -> std::option::Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ZenpatchError::IoError { source, .. } => source.as_deref().map(|e| e as &(dyn std::error::Error + 'static)),
+            ZenpatchError::AnyhowError(err) => std::option::Option::Some(err.as_ref()),
+            _ => std::option::Option::None,
+        }
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -128,26 +358,48 @@ mod tests {
 
     #[test]
     fn test_display_io_error() {
-        let e = ZenpatchError::IoError("disk full".into());
+        let e = ZenpatchError::IoError { message: "disk full".into(), source: None };
         assert_eq!(e.to_string(), "I/O error: disk full");
     }
 
     #[test]
     fn test_display_patch_conflict() {
-        let e = ZenpatchError::PatchConflict("mismatch".into());
-        assert_eq!(e.to_string(), "Patch conflict: mismatch");
+        let e = ZenpatchError::PatchConflict {
+            path: "foo.rs".into(),
+            chunk_index: 2,
+            message: "mismatch".into(),
+        };
+        assert_eq!(e.to_string(), "Patch conflict in 'foo.rs' chunk 2: mismatch");
+    }
+
+    #[test]
+    fn test_display_patch_conflict_without_path() {
+        let e = ZenpatchError::PatchConflict {
+            path: std::string::String::new(),
+            chunk_index: 0,
+            message: "mismatch".into(),
+        };
+        assert_eq!(e.to_string(), "Patch conflict in chunk 0: mismatch");
     }
 
     #[test]
     fn test_display_context_not_found() {
-        let e = ZenpatchError::ContextNotFound("missing ctx".into());
-        assert_eq!(e.to_string(), "Context not found: missing ctx");
+        let e = ZenpatchError::ContextNotFound {
+            path: "a.rs".into(),
+            chunk_index: 1,
+            message: "missing ctx".into(),
+        };
+        assert_eq!(e.to_string(), "Context not found in 'a.rs' chunk 1: missing ctx");
     }
 
     #[test]
     fn test_display_ambiguous_patch() {
-        let e = ZenpatchError::AmbiguousPatch("multi-match".into());
-        assert_eq!(e.to_string(), "Ambiguous patch: multi-match");
+        let e = ZenpatchError::AmbiguousPatch {
+            path: "a.rs".into(),
+            chunk_index: 0,
+            message: "multi-match".into(),
+        };
+        assert_eq!(e.to_string(), "Ambiguous patch in 'a.rs' chunk 0: multi-match");
     }
 
     #[test]
@@ -164,10 +416,38 @@ mod tests {
 
     #[test]
     fn test_with_path_tags_location_errors() {
-        let e = ZenpatchError::PatchConflict("nope".into()).with_path("src/a.rs");
-        assert_eq!(e, ZenpatchError::PatchConflict("in src/a.rs: nope".into()));
-        let e = ZenpatchError::AmbiguousPatch("two".into()).with_path("b.rs");
-        assert_eq!(e, ZenpatchError::AmbiguousPatch("in b.rs: two".into()));
+        let e = ZenpatchError::PatchConflict {
+            path: std::string::String::new(),
+            chunk_index: 3,
+            message: "nope".into(),
+        }
+        .with_path("src/a.rs");
+        assert_eq!(
+            e,
+            ZenpatchError::PatchConflict {
+                path: "src/a.rs".into(),
+                chunk_index: 3,
+                message: "nope".into(),
+            }
+        );
+        let e = ZenpatchError::AmbiguousPatch {
+            path: std::string::String::new(),
+            chunk_index: 0,
+            message: "two".into(),
+        }
+        .with_path("b.rs");
+        assert_eq!(e.to_string(), "Ambiguous patch in 'b.rs' chunk 0: two");
+    }
+
+    #[test]
+    fn test_with_chunk_index_replaces_index() {
+        let e = ZenpatchError::ContextNotFound {
+            path: "a.rs".into(),
+            chunk_index: 0,
+            message: "m".into(),
+        }
+        .with_chunk_index(4);
+        assert_eq!(e.to_string(), "Context not found in 'a.rs' chunk 4: m");
     }
 
     #[test]
@@ -175,4 +455,83 @@ mod tests {
         let e = ZenpatchError::FileExists("x.rs".into()).with_path("ignored");
         assert_eq!(e, ZenpatchError::FileExists("x.rs".into()));
     }
+
+    #[test]
+    fn test_io_error_source_is_the_io_error() {
+        use std::error::Error;
+        let io = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied");
+        let e = ZenpatchError::IoError { message: std::format!("a.txt: {}", io), source: Some(Box::new(io)) };
+
+        let source = e.source().expect("io source");
+        assert_eq!(source.to_string(), "denied");
+        let io = source.downcast_ref::<std::io::Error>().expect("an io::Error");
+        assert_eq!(io.kind(), std::io::ErrorKind::PermissionDenied);
+        assert!(ZenpatchError::IoError { message: "x".into(), source: None }.source().is_none());
+        assert!(ZenpatchError::FileNotFound("x".into()).source().is_none());
+    }
+
+    #[test]
+    fn test_anyhow_error_wraps_source() {
+        use std::error::Error;
+        let e = ZenpatchError::AnyhowError(Box::new(std::fmt::Error));
+        assert_eq!(e.source().unwrap().to_string(), std::fmt::Error.to_string());
+        assert_eq!(ZenpatchError::AnyhowError("m".into()), ZenpatchError::AnyhowError("m".into()));
+        assert_ne!(ZenpatchError::AnyhowError("m".into()), ZenpatchError::AnyhowError("n".into()));
+    }
+
+    #[test]
+    fn test_io_errors_compare_message_and_source() {
+        let with_source = |msg: &str| ZenpatchError::IoError {
+            message: "a.txt".into(),
+            source: Some(Box::new(std::io::Error::other(msg.to_string()))),
+        };
+        assert_eq!(with_source("gone"), with_source("gone"));
+        assert_ne!(with_source("gone"), with_source("busy"));
+        assert_ne!(with_source("gone"), ZenpatchError::IoError { message: "a.txt".into(), source: None });
+    }
+
+    #[test]
+    fn test_to_json_location_error_fields() {
+        let e = ZenpatchError::PatchConflict {
+            path: "src/lib.rs".into(),
+            chunk_index: 3,
+            message: "mismatch".into(),
+        };
+        let v: serde_json::Value = serde_json::from_str(&e.to_json()).expect("valid JSON");
+        assert_eq!(v["error_type"], "patch_conflict");
+        assert_eq!(v["code"], 1011);
+        assert_eq!(v["message"], "Patch conflict in 'src/lib.rs' chunk 3: mismatch");
+        assert_eq!(v["path"], "src/lib.rs");
+        assert_eq!(v["chunk_index"], 3);
+        assert_eq!(v["detail"], "mismatch");
+    }
+
+    #[test]
+    fn test_to_json_escapes_special_characters() {
+        let e = ZenpatchError::InvalidLine("say \"hi\"\\\n\t\u{1}".into());
+        let v: serde_json::Value = serde_json::from_str(&e.to_json()).expect("valid JSON");
+        assert_eq!(v["error_type"], "invalid_line");
+        assert_eq!(v["line"], "say \"hi\"\\\n\t\u{1}");
+    }
+
+    #[test]
+    fn test_to_json_context_and_plain_variants() {
+        let v: serde_json::Value =
+            serde_json::from_str(&ZenpatchError::InvalidEOFContext(7, "eof".into()).to_json()).unwrap();
+        assert_eq!(v["error_type"], "invalid_eof_context");
+        assert_eq!(v["index"], 7);
+        assert_eq!(v["context"], "eof");
+
+        let v: serde_json::Value = serde_json::from_str(&ZenpatchError::IoError { message: "disk".into(), source: None }.to_json()).unwrap();
+        assert_eq!(v["error_type"], "io_error");
+        assert_eq!(v["message"], "I/O error: disk");
+        assert!(v.get("path").is_none());
+    }
+
+    #[test]
+    fn test_error_codes_are_stable() {
+        assert_eq!(ZenpatchError::InvalidPatchFormat(std::string::String::new()).error_code(), 1001);
+        assert_eq!(ZenpatchError::FileNotFound(std::string::String::new()).error_code(), 1002);
+        assert_eq!(ZenpatchError::PatchApplicationFailed(std::string::String::new()).error_code(), 1015);
+    }
 }
@@ -0,0 +1,34 @@
+//! Defines the `FeatureSet` struct selecting which optional patch-format
+//! features the generated LLM instructions describe.
+//!
+//! The base format (Begin/End envelope, Update/Add/Delete, line prefixes) is
+//! always documented; each flag here adds one optional directive or marker.
+//! Conforms to rust coding guidelines (one item per file).
+
+/// Optional format features to include in [`crate::get_llm_instructions_for::get_llm_instructions_for`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FeatureSet {
+    /// `@@ <text>` hunk headers that anchor a hunk after a matching line.
+    pub change_context: bool,
+    /// The `*** End of File` marker anchoring a hunk to the file's tail.
+    pub end_of_file: bool,
+    /// The `*** Move to:` directive renaming a file while updating it.
+    pub move_to: bool,
+    /// The `*** Copy File:` / `*** To:` directive pair duplicating a file.
+    pub copy_file: bool,
+    /// The `*** Rename File:` / `*** To:` directive pair moving a file.
+    pub rename_file: bool,
+}
+
+impl FeatureSet {
+    /// Every optional feature the parser supports.
+    pub fn all() -> Self {
+        Self {
+            change_context: true,
+            end_of_file: true,
+            move_to: true,
+            copy_file: true,
+            rename_file: true,
+        }
+    }
+}
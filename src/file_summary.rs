@@ -0,0 +1,18 @@
+//! Defines the `FileSummary` struct: per-file counts within a `PatchSummary`.
+//!
+//! Conforms to rust coding guidelines (one item per file).
+
+/// What a single applied action did to one file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileSummary {
+    /// The VFS key the action applied to (for a rename, the destination path).
+    pub path: std::string::String,
+    /// The kind of action applied.
+    pub action: crate::data::action_type::ActionType,
+    /// Lines inserted into this file.
+    pub lines_inserted: usize,
+    /// Lines deleted from this file.
+    pub lines_deleted: usize,
+    /// Whitespace mode this file's hunks needed (`Strict` for Add/Delete).
+    pub whitespace_mode_used: crate::applier::whitespace_mode::WhitespaceMode,
+}
@@ -0,0 +1,46 @@
+//! Defines `AnsiColorFormatter`, which renders patches for terminals.
+//!
+//! Conforms to rust coding guidelines (one item per file).
+
+/// A [`crate::format::patch_formatter::PatchFormatter`] using ANSI escape
+/// codes: red deletions, green insertions, dim context and bold headers.
+/// Every coloured line ends with a reset code.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AnsiColorFormatter;
+
+const RESET: &str = "\x1b[0m";
+
+impl crate::format::patch_formatter::PatchFormatter for AnsiColorFormatter {
+    fn format_line(&self, line_type: crate::data::line_type::LineType, content: &str) -> std::string::String {
+        let color = match line_type {
+            crate::data::line_type::LineType::Deletion => "\x1b[31m",
+            crate::data::line_type::LineType::Insertion => "\x1b[32m",
+            crate::data::line_type::LineType::Context | crate::data::line_type::LineType::NoNewlineAtEOF => "\x1b[2m",
+        };
+        std::format!("{}{}{}{}", color, line_type.prefix(), content, RESET)
+    }
+
+    fn format_header(&self, header: &str) -> std::string::String {
+        std::format!("\x1b[1m{}{}", header, RESET)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::format::patch_formatter::PatchFormatter;
+
+    #[test]
+    fn test_ansi_formatter_colours_lines() {
+        let chunk = crate::parser::text_to_patch::text_to_patch(
+            "*** Begin Patch\n*** Update File: a.txt\n@@\n a\n-b\n+c\n*** End Patch",
+        )
+        .unwrap()
+        .remove(0)
+        .chunks
+        .remove(0);
+        assert_eq!(
+            super::AnsiColorFormatter.format_chunk(&chunk),
+            "\x1b[1m@@\x1b[0m\n\x1b[2m a\x1b[0m\n\x1b[31m-b\x1b[0m\n\x1b[32m+c\x1b[0m"
+        );
+    }
+}
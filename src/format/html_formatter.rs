@@ -0,0 +1,68 @@
+//! Defines `HtmlFormatter`, which renders patches as HTML fragments.
+//!
+//! Conforms to rust coding guidelines (one item per file).
+
+/// A [`crate::format::patch_formatter::PatchFormatter`] producing HTML.
+///
+/// Deleted lines are wrapped in `<del class="zp-del">`, inserted lines in
+/// `<ins class="zp-ins">`, context in `<span class="zp-ctx">` and headers in
+/// `<span class="zp-header">`; all text is HTML-escaped. Lines are separated
+/// by `\n`, so the output is meant to sit inside a `<pre>` element.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HtmlFormatter;
+
+impl crate::format::patch_formatter::PatchFormatter for HtmlFormatter {
+    fn format_line(&self, line_type: crate::data::line_type::LineType, content: &str) -> std::string::String {
+        let (tag, class) = match line_type {
+            crate::data::line_type::LineType::Deletion => ("del", "zp-del"),
+            crate::data::line_type::LineType::Insertion => ("ins", "zp-ins"),
+            crate::data::line_type::LineType::Context => ("span", "zp-ctx"),
+            crate::data::line_type::LineType::NoNewlineAtEOF => ("span", "zp-meta"),
+        };
+        std::format!(
+            "<{tag} class=\"{class}\">{}</{tag}>",
+            escape(&std::format!("{}{}", line_type.prefix(), content))
+        )
+    }
+
+    fn format_header(&self, header: &str) -> std::string::String {
+        std::format!("<span class=\"zp-header\">{}</span>", escape(header))
+    }
+}
+
+fn escape(text: &str) -> std::string::String {
+    let mut out = std::string::String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::format::patch_formatter::PatchFormatter;
+
+    #[test]
+    fn test_html_formatter_tags_and_escapes() {
+        let action = crate::parser::text_to_patch::text_to_patch(
+            "*** Begin Patch\n*** Update File: a.rs\n@@\n ok\n-if a < b {\n+if a <= b && c {\n*** End Patch",
+        )
+        .unwrap()
+        .remove(0);
+        assert_eq!(
+            super::HtmlFormatter.format_action(&action),
+            "<span class=\"zp-header\">*** Update File: a.rs</span>\n\
+<span class=\"zp-header\">@@</span>\n\
+<span class=\"zp-ctx\"> ok</span>\n\
+<del class=\"zp-del\">-if a &lt; b {</del>\n\
+<ins class=\"zp-ins\">+if a &lt;= b &amp;&amp; c {</ins>"
+        );
+    }
+}
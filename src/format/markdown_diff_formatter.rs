@@ -0,0 +1,120 @@
+//! Defines `MarkdownDiffFormatter`, which renders patches as Markdown
+//! ` ```diff ` code blocks.
+//!
+//! Conforms to rust coding guidelines (one item per file).
+
+/// A [`crate::format::patch_formatter::PatchFormatter`] producing fenced
+/// ` ```diff ` blocks that GitHub renders with red and green lines, e.g. for
+/// embedding a change in a PR description.
+///
+/// An action becomes one block opened by a `# Update: <path>` comment line
+/// (`Add`, `Delete`, `Copy` or `Rename` for the other actions, with
+/// `-> <new path>` for moves), followed by the prefixed lines of its chunks.
+/// `@@` headers and `*** End of File` markers are left out; chunks are
+/// separated by an empty line instead. The fence grows beyond three
+/// backticks when a line of the patch itself starts with a backtick run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MarkdownDiffFormatter;
+
+impl crate::format::patch_formatter::PatchFormatter for MarkdownDiffFormatter {
+    fn format_line(&self, line_type: crate::data::line_type::LineType, content: &str) -> std::string::String {
+        std::format!("{}{}", line_type.prefix(), content)
+    }
+
+    fn format_chunk(&self, chunk: &crate::data::chunk::Chunk) -> std::string::String {
+        fenced(&self.chunk_body(chunk))
+    }
+
+    fn format_action(&self, action: &crate::data::patch_action::PatchAction) -> std::string::String {
+        let verb = match action.type_ {
+            crate::data::action_type::ActionType::Add => "Add",
+            crate::data::action_type::ActionType::Delete => "Delete",
+            crate::data::action_type::ActionType::Update => "Update",
+            crate::data::action_type::ActionType::Copy => "Copy",
+            crate::data::action_type::ActionType::Rename => "Rename",
+        };
+        let mut header = std::format!("# {}: {}", verb, action.path);
+        if let std::option::Option::Some(new_path) = &action.new_path {
+            header.push_str(" -> ");
+            header.push_str(new_path);
+        }
+        let mut body = std::vec![header];
+        let chunks: std::vec::Vec<std::string::String> =
+            action.into_iter().map(|chunk| self.chunk_body(chunk)).collect();
+        if !chunks.is_empty() {
+            body.push(chunks.join("\n\n"));
+        }
+        fenced(&body.join("\n"))
+    }
+}
+
+impl MarkdownDiffFormatter {
+    fn chunk_body(&self, chunk: &crate::data::chunk::Chunk) -> std::string::String {
+        use crate::format::patch_formatter::PatchFormatter;
+        let lines: std::vec::Vec<std::string::String> =
+            chunk.into_iter().map(|(line_type, content)| self.format_line(line_type, content)).collect();
+        lines.join("\n")
+    }
+}
+
+/// Wraps `body` in a ` ```diff ` fence longer than any backtick run that
+/// starts one of its lines, so patch content cannot close the block early.
+fn fenced(body: &str) -> std::string::String {
+    let longest_run = body
+        .lines()
+        .map(|line| line.trim_start_matches([' ', '+', '-']).chars().take_while(|c| *c == '`').count())
+        .max()
+        .unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    std::format!("{fence}diff\n{body}\n{fence}")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::format::patch_formatter::PatchFormatter;
+
+    #[test]
+    fn test_markdown_diff_formatter_fences_prefixed_lines() {
+        let actions = crate::parser::text_to_patch::text_to_patch(
+            "*** Begin Patch\n*** Update File: src/lib.rs\n@@ fn main\n ok\n-old\n+new\n@@\n-gone\n\
+             *** Add File: notes.md\n+hello\n*** End Patch",
+        )
+        .unwrap();
+
+        assert_eq!(
+            super::MarkdownDiffFormatter.format_action(&actions[0]),
+            "```diff\n# Update: src/lib.rs\n ok\n-old\n+new\n\n-gone\n```"
+        );
+        assert_eq!(super::MarkdownDiffFormatter.format_action(&actions[1]), "```diff\n# Add: notes.md\n+hello\n```");
+        assert_eq!(super::MarkdownDiffFormatter.format_chunk(&actions[0].chunks[1]), "```diff\n-gone\n```");
+    }
+
+    #[test]
+    fn test_markdown_diff_formatter_headers_for_moves() {
+        let actions = crate::parser::text_to_patch::text_to_patch(
+            "*** Begin Patch\n*** Rename File: a.txt\n*** To: b.txt\n\
+             *** Update File: c.txt\n*** Move to: d.txt\n@@\n-x\n+y\n*** End Patch",
+        )
+        .unwrap();
+
+        assert_eq!(super::MarkdownDiffFormatter.format_action(&actions[0]), "```diff\n# Rename: a.txt -> b.txt\n```");
+        assert_eq!(
+            super::MarkdownDiffFormatter.format_action(&actions[1]),
+            "```diff\n# Update: c.txt -> d.txt\n-x\n+y\n```"
+        );
+    }
+
+    #[test]
+    fn test_markdown_diff_formatter_outgrows_backticks_in_content() {
+        let action = crate::parser::text_to_patch::text_to_patch(
+            "*** Begin Patch\n*** Update File: README.md\n@@\n-```rust\n+````rust\n*** End Patch",
+        )
+        .unwrap()
+        .remove(0);
+
+        let formatted = super::MarkdownDiffFormatter.format_action(&action);
+
+        assert!(formatted.starts_with("`````diff\n"), "{}", formatted);
+        assert!(formatted.ends_with("\n`````"), "{}", formatted);
+    }
+}
@@ -0,0 +1,11 @@
+//! Human-readable rendering of parsed patches.
+//!
+//! `PatchFormatter` turns a `PatchAction` or `Chunk` into text; the stock
+//! implementations produce plain patch text, HTML, ANSI-coloured output and
+//! Markdown diff blocks without any terminal or HTML dependency.
+
+pub mod ansi_color_formatter;
+pub mod html_formatter;
+pub mod markdown_diff_formatter;
+pub mod patch_formatter;
+pub mod plain_formatter;
@@ -0,0 +1,56 @@
+//! Defines the `PatchFormatter` trait for rendering patches.
+//!
+//! Conforms to rust coding guidelines (one item per file).
+
+/// Renders patch actions and chunks as text.
+///
+/// Only [`PatchFormatter::format_line`] is required: the other methods lay
+/// out the same structure as the patch's `Display` form, one line per entry
+/// joined with `\n`, and pass every directive or `@@` header through
+/// [`PatchFormatter::format_header`].
+pub trait PatchFormatter {
+    /// Renders one chunk line, including its prefix character.
+    fn format_line(&self, line_type: crate::data::line_type::LineType, content: &str) -> std::string::String;
+
+    /// Renders a `*** ...` directive or `@@` header line.
+    fn format_header(&self, header: &str) -> std::string::String {
+        header.to_string()
+    }
+
+    /// Renders a chunk: its `@@` header, its lines and any end-of-file marker.
+    fn format_chunk(&self, chunk: &crate::data::chunk::Chunk) -> std::string::String {
+        let header = match &chunk.change_context {
+            std::option::Option::Some(ctx) => std::format!("@@ {}", ctx),
+            std::option::Option::None => "@@".to_string(),
+        };
+        let mut out = std::vec![self.format_header(&header)];
+        out.extend(chunk.into_iter().map(|(line_type, content)| self.format_line(line_type, content)));
+        if chunk.is_end_of_file {
+            out.push(self.format_header("*** End of File"));
+        }
+        out.join("\n")
+    }
+
+    /// Renders an action: its directive lines, then its chunks (for updates)
+    /// or its bare lines (for adds and deletes).
+    fn format_action(&self, action: &crate::data::patch_action::PatchAction) -> std::string::String {
+        let text = action.to_string();
+        let mut out: std::vec::Vec<std::string::String> =
+            text.lines().take_while(|line| line.starts_with("*** ")).map(|line| self.format_header(line)).collect();
+        match action.type_ {
+            crate::data::action_type::ActionType::Update => {
+                out.extend(action.into_iter().map(|chunk| self.format_chunk(chunk)));
+            }
+            crate::data::action_type::ActionType::Add | crate::data::action_type::ActionType::Delete => {
+                out.extend(
+                    action
+                        .into_iter()
+                        .flat_map(|chunk| chunk.into_iter())
+                        .map(|(line_type, content)| self.format_line(line_type, content)),
+                );
+            }
+            crate::data::action_type::ActionType::Copy | crate::data::action_type::ActionType::Rename => {}
+        }
+        out.join("\n")
+    }
+}
@@ -0,0 +1,35 @@
+//! Defines `PlainFormatter`, which renders patches as plain patch text.
+//!
+//! Conforms to rust coding guidelines (one item per file).
+
+/// A [`crate::format::patch_formatter::PatchFormatter`] producing the same
+/// text as the `Display` implementations of `PatchAction` and `Chunk`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PlainFormatter;
+
+impl crate::format::patch_formatter::PatchFormatter for PlainFormatter {
+    fn format_line(&self, line_type: crate::data::line_type::LineType, content: &str) -> std::string::String {
+        std::format!("{}{}", line_type.prefix(), content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::format::patch_formatter::PatchFormatter;
+
+    #[test]
+    fn test_plain_formatter_matches_display() {
+        let actions = crate::parser::text_to_patch::text_to_patch(
+            "*** Begin Patch\n\
+*** Update File: a.txt\n*** Move to: b.txt\n@@ fn main\n a\n-b\n+B\n*** End of File\n\
+*** Add File: c.txt\n+one\n\
+*** Rename File: d.txt\n*** To: e.txt\n\
+*** End Patch",
+        )
+        .unwrap();
+        for action in &actions {
+            assert_eq!(super::PlainFormatter.format_action(action), action.to_string());
+        }
+        assert_eq!(super::PlainFormatter.format_chunk(&actions[0].chunks[0]), actions[0].chunks[0].to_string());
+    }
+}
@@ -0,0 +1,217 @@
+//! Filesystem-backed entry points: apply a patch directly to a directory tree.
+//!
+//! Only the files referenced by the patch are read into a temporary `Vfs`; the
+//! patch is applied in memory with the same engine as [`crate::apply::apply`],
+//! and only files whose content actually changed are written back (deleted
+//! files are removed). Paths are canonicalized against the root first, so
+//! `..` segments and symlinks cannot escape it.
+
+/// Applies `patch_text` to the files under `root`.
+///
+/// Nothing is written unless the whole patch applies. Filesystem failures and
+/// paths that resolve outside `root` are reported as `ZenpatchError::IoError`.
+pub fn apply_to_directory(
+    patch_text: &str,
+    root: &std::path::Path,
+) -> std::result::Result<(), crate::error::ZenpatchError> {
+    let root = std::fs::canonicalize(root).map_err(|e| io_error(root, e))?;
+    let mut actions = crate::parser::text_to_patch::text_to_patch(patch_text)?;
+
+    let mut vfs = crate::vfs::Vfs::new();
+    for action in &mut actions {
+        action.path = canonical_key(&root, &action.path)?;
+        load_if_present(&root, &action.path, &mut vfs)?;
+        if let std::option::Option::Some(new_path) = &action.new_path {
+            let key = canonical_key(&root, new_path)?;
+            load_if_present(&root, &key, &mut vfs)?;
+            action.new_path = std::option::Option::Some(key);
+        }
+    }
+
+    let (new_vfs, _) = crate::apply::apply_actions(
+        actions,
+        &vfs,
+        &crate::apply_options::ApplyOptions::default(),
+    )?;
+
+    for (key, content) in &new_vfs {
+        if vfs.get(key) == std::option::Option::Some(content) {
+            continue;
+        }
+        let path = root.join(key);
+        if let std::option::Option::Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| io_error(parent, e))?;
+        }
+        std::fs::write(&path, content).map_err(|e| io_error(&path, e))?;
+    }
+    for key in vfs.keys() {
+        if !new_vfs.contains_key(key) {
+            let path = root.join(key);
+            std::fs::remove_file(&path).map_err(|e| io_error(&path, e))?;
+        }
+    }
+
+    std::result::Result::Ok(())
+}
+
+/// Reads the patch at `patch_path` and applies it to the files under `root`
+/// (see [`apply_to_directory`]).
+pub fn apply_from_file(
+    patch_path: &std::path::Path,
+    root: &std::path::Path,
+) -> std::result::Result<(), crate::error::ZenpatchError> {
+    let patch_text = std::fs::read_to_string(patch_path).map_err(|e| io_error(patch_path, e))?;
+    apply_to_directory(&patch_text, root)
+}
+
+/// Resolves a patch path to a `/`-separated VFS key relative to the (already
+/// canonical) `root`. The longest existing ancestor is canonicalized (resolving
+/// symlinks and `..`); the not-yet-existing remainder is normalized lexically.
+fn canonical_key(
+    root: &std::path::Path,
+    patch_path: &str,
+) -> std::result::Result<std::string::String, crate::error::ZenpatchError> {
+    let joined = root.join(patch_path.trim_start_matches('/'));
+    let existing = joined
+        .ancestors()
+        .find(|a| a.exists())
+        .unwrap_or(root);
+    let mut resolved = std::fs::canonicalize(existing).map_err(|e| io_error(existing, e))?;
+    let remainder = joined.strip_prefix(existing).unwrap_or(std::path::Path::new(""));
+    for component in remainder.components() {
+        match component {
+            std::path::Component::Normal(part) => resolved.push(part),
+            std::path::Component::ParentDir => {
+                resolved.pop();
+            }
+            _ => {}
+        }
+    }
+
+    let relative = resolved.strip_prefix(root).map_err(|_| {
+        crate::error::ZenpatchError::IoError {
+            message: format!("{}: path resolves outside of {}", patch_path, root.display()),
+            source: std::option::Option::None,
+        }
+    })?;
+    let parts: std::vec::Vec<std::string::String> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    if parts.is_empty() {
+        return std::result::Result::Err(crate::error::ZenpatchError::IoError {
+            message: format!("{}: path does not name a file", patch_path),
+            source: std::option::Option::None,
+        });
+    }
+    std::result::Result::Ok(parts.join("/"))
+}
+
+/// Reads `root/key` into `vfs` when it is an existing file not loaded yet.
+fn load_if_present(
+    root: &std::path::Path,
+    key: &str,
+    vfs: &mut crate::vfs::Vfs,
+) -> std::result::Result<(), crate::error::ZenpatchError> {
+    let path = root.join(key);
+    if vfs.contains_key(key) || !path.is_file() {
+        return std::result::Result::Ok(());
+    }
+    let content = std::fs::read_to_string(&path).map_err(|e| io_error(&path, e))?;
+    vfs.insert(key.to_string(), content);
+    std::result::Result::Ok(())
+}
+
+/// `ZenpatchError::IoError` for `err` at `path`, keeping `err` as its source.
+/// Shared with [`crate::vfs_fs`].
+pub(crate) fn io_error(path: &std::path::Path, err: std::io::Error) -> crate::error::ZenpatchError {
+    crate::error::ZenpatchError::IoError {
+        message: format!("{}: {}", path.display(), err),
+        source: std::option::Option::Some(std::boxed::Box::new(err)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{apply_from_file, apply_to_directory};
+
+    #[test]
+    fn test_apply_to_directory_updates_adds_and_deletes() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "a\nz\n").unwrap();
+        std::fs::write(dir.path().join("old.txt"), "old").unwrap();
+        std::fs::write(dir.path().join("untouched.txt"), "keep").unwrap();
+        let patch = "*** Begin Patch\n\
+*** Update File: a.txt\n@@\n-a\n+A\n\
+*** Add File: sub/new.txt\n+new\n\
+*** Delete File: old.txt\n-old\n\
+*** End Patch";
+
+        apply_to_directory(patch, dir.path()).unwrap();
+
+        assert_eq!(std::fs::read_to_string(dir.path().join("a.txt")).unwrap(), "A\nz\n");
+        assert_eq!(std::fs::read_to_string(dir.path().join("sub/new.txt")).unwrap(), "new");
+        assert!(!dir.path().join("old.txt").exists());
+        assert_eq!(std::fs::read_to_string(dir.path().join("untouched.txt")).unwrap(), "keep");
+    }
+
+    #[test]
+    fn test_apply_to_directory_resolves_dot_dot_inside_root() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+        let patch = "*** Begin Patch\n*** Update File: sub/../a.txt\n@@\n-a\n+b\n*** End Patch";
+
+        apply_to_directory(patch, dir.path()).unwrap();
+
+        assert_eq!(std::fs::read_to_string(dir.path().join("a.txt")).unwrap(), "b");
+    }
+
+    #[test]
+    fn test_apply_to_directory_rejects_path_escaping_root() {
+        let outer = tempfile::tempdir().unwrap();
+        let root = outer.path().join("root");
+        std::fs::create_dir(&root).unwrap();
+        let patch = "*** Begin Patch\n*** Add File: ../escaped.txt\n+x\n*** End Patch";
+
+        match apply_to_directory(patch, &root).unwrap_err() {
+            crate::error::ZenpatchError::IoError { message: msg, .. } => assert!(msg.contains("outside"), "{msg}"),
+            other => panic!("Expected IoError, got {other:?}"),
+        }
+        assert!(!outer.path().join("escaped.txt").exists());
+    }
+
+    #[test]
+    fn test_apply_to_directory_failed_patch_writes_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+        let patch = "*** Begin Patch\n*** Add File: b.txt\n+b\n*** Update File: a.txt\n@@\n-ghost\n+x\n*** End Patch";
+
+        assert!(apply_to_directory(patch, dir.path()).is_err());
+        assert!(!dir.path().join("b.txt").exists());
+        assert_eq!(std::fs::read_to_string(dir.path().join("a.txt")).unwrap(), "a");
+    }
+
+    #[test]
+    fn test_apply_from_file_reads_patch_from_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+        let patch_path = dir.path().join("change.patch");
+        std::fs::write(
+            &patch_path,
+            "*** Begin Patch\n*** Update File: a.txt\n@@\n-a\n+b\n*** End Patch",
+        )
+        .unwrap();
+
+        apply_from_file(&patch_path, dir.path()).unwrap();
+
+        assert_eq!(std::fs::read_to_string(dir.path().join("a.txt")).unwrap(), "b");
+    }
+
+    #[test]
+    fn test_apply_from_file_missing_patch_is_io_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = apply_from_file(&dir.path().join("missing.patch"), dir.path());
+        assert!(matches!(result, Err(crate::error::ZenpatchError::IoError { .. })));
+    }
+}
@@ -0,0 +1,20 @@
+//! Provides `get_llm_instructions_brief`, a compact cheat sheet of the patch
+//! format.
+//!
+//! Returns the contents of `llms-brief.txt`, one annotated line per
+//! directive, for agents with a tight prompt budget.
+
+pub fn get_llm_instructions_brief() -> &'static str {
+    std::include_str!("../llms-brief.txt")
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_brief_instructions_name_the_format() {
+        let instructions = super::get_llm_instructions_brief();
+        std::assert!(!instructions.is_empty());
+        std::assert!(instructions.starts_with("Zenpatch Patch Format (brief)"));
+        std::assert!(instructions.len() < crate::get_llm_instructions::get_llm_instructions().len() / 2);
+    }
+}
@@ -0,0 +1,280 @@
+//! Provides `get_llm_instructions_for`, which assembles LLM-facing patch
+//! format documentation limited to a chosen `FeatureSet`.
+//!
+//! Unlike the static `llms.txt` returned by `get_llm_instructions`, the text
+//! is built from sections so an agent prompt never advertises a directive the
+//! caller does not want the model to use.
+
+const INTRO: &str = "Zenpatch Patch Format for LLMs
+
+This document describes the text-based patch format used by the Zenpatch library. It is designed to be simple for Large Language Models (LLMs) to generate.
+
+## Overall Structure
+
+A patch starts with `*** Begin Patch` and ends with `*** End Patch` on their own lines.
+Between these markers, one or more file operations (hunks) can be listed.
+
+```
+*** Begin Patch
+... file operations ...
+*** End Patch
+```
+
+## File Operations
+
+There are three types of file operations: updating, adding, and deleting.
+
+### 1. Updating a File
+
+To update an existing file, use the `*** Update File:` directive.
+
+- **Directive:** `*** Update File: path/to/your/file.ext`
+- **Hunks:** Each change within the file is specified in a hunk starting with `@@`.
+";
+
+const CHANGE_CONTEXT_ITEM: &str = "- **Hunk Headers:** A bare `@@` starts a hunk. To disambiguate repeated patterns in large files, add a context string after `@@`: `@@ class MyClass:` or `@@ def my_function():`. The patcher uses this text to locate the nearest matching line and constrains the search to positions after it.
+";
+
+const END_OF_FILE_ITEM: &str = "- **End of File Marker:** Use `*** End of File` after the last line of a hunk to indicate the change must apply at the end of the file. This is useful when context lines appear multiple times and you want to anchor to the final occurrence.
+";
+
+const MOVE_TO_ITEM: &str = "- **Rename:** To rename the file while updating it, put `*** Move to: new/path.ext` on the line right after the `*** Update File:` directive.
+";
+
+const UPDATE_ITEMS: &str = "- **Hunk Order:** List hunks in the order they appear in the file. The patcher tries file order first; when several identical regions are edited, hunk order determines which occurrence each hunk targets.
+- **Line Prefixes:**
+    - ` ` (space): A context line, which must match the existing content in the file.
+    - `-`: A line to be deleted.
+    - `+`: A line to be added.
+
+**Example (basic):**
+
+```
+*** Update File: src/main.rs
+@@
+ // some context
+-let old_variable = 1;
++let new_variable = 2;
+ // more context
+```
+";
+
+const CHANGE_CONTEXT_EXAMPLE: &str = "
+**Example (with @@ context for disambiguation):**
+
+```
+*** Update File: src/app.py
+@@ class UserService:
+ def update(self, user):
+-    self.db.save(user)
++    self.db.upsert(user)
+```
+";
+
+const END_OF_FILE_EXAMPLE: &str = "
+**Example (with End of File marker):**
+
+```
+*** Update File: src/config.rs
+@@
+ // last config entry
++pub const NEW_SETTING: bool = true;
+*** End of File
+```
+";
+
+const MOVE_TO_EXAMPLE: &str = "
+**Example (rename while updating):**
+
+```
+*** Update File: src/old_name.rs
+*** Move to: src/new_name.rs
+@@
+-pub fn old() {}
++pub fn new() {}
+```
+";
+
+const ADD_AND_DELETE: &str = "
+### 2. Adding a New File
+
+To create a new file, use the `*** Add File:` directive.
+
+- **Directive:** `*** Add File: path/to/new/file.ext`
+- **Content:** Every line of content for the new file must start with a `+`.
+
+**Example:**
+
+```
+*** Add File: new_feature.rs
++fn new_function() {
++    // implementation
++}
+```
+
+### 3. Deleting a File
+
+To delete an existing file, use the `*** Delete File:` directive.
+
+- **Directive:** `*** Delete File: path/to/be/deleted.txt`
+- **Content:** The content to be deleted can optionally be included, prefixed with `-`. The patcher primarily uses the filename for deletion.
+
+**Example:**
+
+```
+*** Delete File: old_config.toml
+-some_setting = \"old_value\"
+```
+";
+
+const COPY_FILE: &str = "
+### 4. Copying a File
+
+To duplicate an existing file verbatim, use the `*** Copy File:` directive followed by a `*** To:` line naming the destination. No content lines are needed.
+
+- **Directive:** `*** Copy File: path/to/source.ext`
+- **Destination:** `*** To: path/to/copy.ext` (must not exist yet)
+
+**Example:**
+
+```
+*** Copy File: templates/base.html
+*** To: templates/landing.html
+```
+";
+
+const RENAME_FILE: &str = "
+### 5. Renaming a File
+
+To move a file without changing its content, use the `*** Rename File:` directive followed by a `*** To:` line naming the new path. No content lines are needed.
+
+- **Directive:** `*** Rename File: path/to/old_name.ext`
+- **Destination:** `*** To: path/to/new_name.ext` (must not exist yet)
+
+**Example:**
+
+```
+*** Rename File: src/util.rs
+*** To: src/helpers.rs
+```
+";
+
+const PRINCIPLES_HEADING: &str = "
+## Key Principles for Robust Patches
+
+";
+
+const PRINCIPLE_CONTEXT: &str = "**Context is Key:** For `Update File` operations, provide at least 3 surrounding context lines (lines with a leading space) to uniquely identify the location of the change. This is more robust than relying on line numbers.";
+
+const PRINCIPLE_CHANGE_CONTEXT: &str = "**Use `@@` Headers for Disambiguation:** When a file has repeated patterns (e.g., multiple methods with the same signature), add the enclosing class or function name to the `@@` header: `@@ class Foo:` or `@@ def bar():`. The patcher will only match positions after the first occurrence of that text.";
+
+const PRINCIPLE_END_OF_FILE: &str = "**Use `*** End of File` for Tail Anchoring:** When appending to the end of a file or modifying the last occurrence of a pattern, add `*** End of File` after the hunk to constrain matching to the file's tail.";
+
+const PRINCIPLE_ONE_CHANGE: &str = "**One Change at a Time:** While a patch file can contain multiple hunks (`@@`) for a single file, it's often clearer and safer to have each hunk represent a single, contiguous change.";
+
+const PRINCIPLE_EXACT: &str = "**Exact Match:** Context and deleted lines must match the content in the file exactly, including whitespace.";
+
+/// Builds the patch-format instructions, documenting only the optional
+/// features enabled in `features` on top of the always-available base format.
+pub fn get_llm_instructions_for(features: &crate::feature_set::FeatureSet) -> std::string::String {
+    let mut text = std::string::String::from(INTRO);
+    if features.change_context {
+        text.push_str(CHANGE_CONTEXT_ITEM);
+    }
+    if features.end_of_file {
+        text.push_str(END_OF_FILE_ITEM);
+    }
+    if features.move_to {
+        text.push_str(MOVE_TO_ITEM);
+    }
+    text.push_str(UPDATE_ITEMS);
+    if features.change_context {
+        text.push_str(CHANGE_CONTEXT_EXAMPLE);
+    }
+    if features.end_of_file {
+        text.push_str(END_OF_FILE_EXAMPLE);
+    }
+    if features.move_to {
+        text.push_str(MOVE_TO_EXAMPLE);
+    }
+    text.push_str(ADD_AND_DELETE);
+    if features.copy_file {
+        text.push_str(COPY_FILE);
+    }
+    if features.rename_file {
+        text.push_str(RENAME_FILE);
+    }
+    text.push_str(PRINCIPLES_HEADING);
+
+    let mut principles = std::vec![PRINCIPLE_CONTEXT];
+    if features.change_context {
+        principles.push(PRINCIPLE_CHANGE_CONTEXT);
+    }
+    if features.end_of_file {
+        principles.push(PRINCIPLE_END_OF_FILE);
+    }
+    principles.push(PRINCIPLE_ONE_CHANGE);
+    principles.push(PRINCIPLE_EXACT);
+    for (i, principle) in principles.iter().enumerate() {
+        text.push_str(&std::format!("{}.  {}\n", i + 1, principle));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::feature_set::FeatureSet;
+
+    #[test]
+    fn test_base_instructions_omit_optional_directives() {
+        let text = super::get_llm_instructions_for(&FeatureSet::default());
+        std::assert!(text.contains("Zenpatch Patch Format for LLMs"));
+        std::assert!(text.contains("*** Update File:"));
+        std::assert!(!text.contains("*** Move to:"));
+        std::assert!(!text.contains("*** End of File"));
+        std::assert!(!text.contains("@@ class"));
+        std::assert!(!text.contains("*** Copy File:"));
+        std::assert!(!text.contains("*** Rename File:"));
+    }
+
+    #[test]
+    fn test_rename_feature_documents_rename_directive() {
+        let features = FeatureSet { rename_file: true, ..FeatureSet::default() };
+        let text = super::get_llm_instructions_for(&features);
+        std::assert!(text.contains("*** Rename File:"));
+        std::assert!(text.contains("*** To:"));
+    }
+
+    #[test]
+    fn test_copy_feature_documents_copy_directive() {
+        let features = FeatureSet { copy_file: true, ..FeatureSet::default() };
+        let text = super::get_llm_instructions_for(&features);
+        std::assert!(text.contains("*** Copy File:"));
+        std::assert!(text.contains("*** To:"));
+    }
+
+    #[test]
+    fn test_enabled_feature_is_documented() {
+        let features = FeatureSet { move_to: true, ..FeatureSet::default() };
+        let text = super::get_llm_instructions_for(&features);
+        std::assert!(text.contains("*** Move to:"));
+        std::assert!(!text.contains("*** End of File"));
+    }
+
+    /// The static `llms.txt` documents exactly these features; the assembled
+    /// text must not drift from it.
+    #[test]
+    fn test_matches_static_instructions_for_same_features() {
+        let features = FeatureSet { move_to: false, copy_file: false, rename_file: false, ..FeatureSet::all() };
+        let text = super::get_llm_instructions_for(&features);
+        std::assert_eq!(text.trim_end(), crate::get_llm_instructions::get_llm_instructions().trim_end());
+    }
+
+    #[test]
+    fn test_principles_are_numbered_consecutively() {
+        let text = super::get_llm_instructions_for(&FeatureSet::default());
+        std::assert!(text.contains("1.  **Context is Key:**"));
+        std::assert!(text.contains("2.  **One Change at a Time:**"));
+        std::assert!(text.contains("3.  **Exact Match:**"));
+    }
+}
@@ -0,0 +1,41 @@
+//! Provides `get_llm_instructions_json_schema`, a JSON Schema for patches
+//! expressed as data.
+//!
+//! Returns the contents of `llms-schema.json`, which describes the JSON form
+//! of a `Vec<PatchAction>` (as produced with the `serde` feature), for agents
+//! that emit structured output through function-calling APIs.
+
+pub fn get_llm_instructions_json_schema() -> &'static str {
+    std::include_str!("../llms-schema.json")
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_json_schema_is_valid_json_naming_the_format() {
+        let schema: serde_json::Value = serde_json::from_str(super::get_llm_instructions_json_schema()).unwrap();
+        std::assert_eq!(schema["title"], "Zenpatch Patch Format");
+        std::assert!(schema["$defs"]["PatchAction"].is_object());
+        std::assert!(schema["$defs"]["Chunk"].is_object());
+    }
+
+    /// The schema's properties must be exactly the fields serde writes.
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json_schema_matches_serialized_actions() {
+        let schema: serde_json::Value = serde_json::from_str(super::get_llm_instructions_json_schema()).unwrap();
+        let actions = crate::parser::text_to_patch::text_to_patch(
+            "*** Begin Patch\n*** Update File: a\n*** Move to: b\n@@ fn main\n ctx\n-x\n+y\n*** End Patch",
+        )
+        .unwrap();
+        let json = serde_json::to_value(&actions).unwrap();
+
+        let keys = |value: &serde_json::Value| {
+            let mut keys: std::vec::Vec<std::string::String> = value.as_object().unwrap().keys().cloned().collect();
+            keys.sort();
+            keys
+        };
+        std::assert_eq!(keys(&json[0]), keys(&schema["$defs"]["PatchAction"]["properties"]));
+        std::assert_eq!(keys(&json[0]["chunks"][0]), keys(&schema["$defs"]["Chunk"]["properties"]));
+    }
+}
@@ -0,0 +1,31 @@
+//! Provides `get_llm_instructions_markdown`, a Markdown rendition of the
+//! patch format documentation.
+//!
+//! Returns the contents of `llms-markdown.md`: directive and prefix tables,
+//! one combined example and the matching rules, for agents whose system
+//! prompts are written in Markdown.
+
+pub fn get_llm_instructions_markdown() -> &'static str {
+    std::include_str!("../llms-markdown.md")
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_markdown_instructions_name_the_format() {
+        let instructions = super::get_llm_instructions_markdown();
+        std::assert!(!instructions.is_empty());
+        std::assert!(instructions.starts_with("# Zenpatch Patch Format"));
+        std::assert!(instructions.contains("| `*** Update File: <path>` |"));
+    }
+
+    /// The example must stay a patch the library actually accepts.
+    #[test]
+    fn test_markdown_example_parses() {
+        let instructions = super::get_llm_instructions_markdown();
+        let start = instructions.find("```\n*** Begin Patch").unwrap() + "```\n".len();
+        let end = instructions.find("*** End Patch\n```").unwrap() + "*** End Patch".len();
+        let actions = crate::parser::text_to_patch::text_to_patch(&instructions[start..end]).unwrap();
+        std::assert_eq!(actions.len(), 3);
+    }
+}
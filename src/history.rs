@@ -0,0 +1,171 @@
+//! Defines `PatchHistory`, an undo/redo stack of patches applied to a VFS.
+//!
+//! Conforms to rust coding guidelines (one item per file).
+
+/// A [`crate::vfs::Vfs`] together with the patches applied to it, so they can
+/// be undone and redone.
+///
+/// Each entry pairs the forward actions with the actions that undo them. The
+/// backward actions come from [`crate::patch_ops::reverse_patch`]; when that
+/// cannot restore the previous state exactly (a `Copy`, or a patch that only
+/// applied with lenient matching) they are computed with
+/// [`crate::diff::diff_vfs`] instead. At most `max_history` entries are kept;
+/// the oldest is dropped first. Applying a new patch clears the redo stack.
+#[derive(Debug, Clone)]
+pub struct PatchHistory {
+    vfs: crate::vfs::Vfs,
+    undo_stack: std::vec::Vec<(std::vec::Vec<crate::data::patch_action::PatchAction>, std::vec::Vec<crate::data::patch_action::PatchAction>)>,
+    redo_stack: std::vec::Vec<(std::vec::Vec<crate::data::patch_action::PatchAction>, std::vec::Vec<crate::data::patch_action::PatchAction>)>,
+    /// The number of patches that can be undone.
+    pub max_history: usize,
+}
+
+impl PatchHistory {
+    /// Starts an empty history over `vfs`.
+    pub fn new(vfs: crate::vfs::Vfs, max_history: usize) -> Self {
+        Self {
+            vfs,
+            undo_stack: std::vec::Vec::new(),
+            redo_stack: std::vec::Vec::new(),
+            max_history,
+        }
+    }
+
+    /// The current state of the VFS.
+    pub fn vfs(&self) -> &crate::vfs::Vfs {
+        &self.vfs
+    }
+
+    /// True when [`PatchHistory::undo`] has a patch to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    /// True when [`PatchHistory::redo`] has a patch to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    /// Applies `patch_text` and records it for undo. A failing patch leaves
+    /// the VFS and both stacks untouched.
+    pub fn apply(&mut self, patch_text: &str) -> std::result::Result<(), crate::error::ZenpatchError> {
+        let forward = crate::parser::text_to_patch::text_to_patch(patch_text)?;
+        let options = crate::apply_options::ApplyOptions::default();
+        let (new_vfs, _) = crate::apply::apply_actions(forward.clone(), &self.vfs, &options)?;
+
+        let backward = crate::patch_ops::reverse_patch(&forward)
+            .ok()
+            .filter(|reversed| {
+                crate::apply::apply_actions(reversed.clone(), &new_vfs, &options)
+                    .is_ok_and(|(restored, _)| restored == self.vfs)
+            })
+            .unwrap_or_else(|| crate::diff::diff_vfs(&new_vfs, &self.vfs));
+
+        self.vfs = new_vfs;
+        self.redo_stack.clear();
+        self.undo_stack.push((forward, backward));
+        if self.undo_stack.len() > self.max_history {
+            let excess = self.undo_stack.len() - self.max_history;
+            self.undo_stack.drain(..excess);
+        }
+        std::result::Result::Ok(())
+    }
+
+    /// Undoes the most recent patch; does nothing when there is none.
+    pub fn undo(&mut self) -> std::result::Result<(), crate::error::ZenpatchError> {
+        let std::option::Option::Some((forward, backward)) = self.undo_stack.pop() else {
+            return std::result::Result::Ok(());
+        };
+        match crate::apply::apply_actions(backward.clone(), &self.vfs, &crate::apply_options::ApplyOptions::default()) {
+            std::result::Result::Ok((vfs, _)) => {
+                self.vfs = vfs;
+                self.redo_stack.push((forward, backward));
+                std::result::Result::Ok(())
+            }
+            std::result::Result::Err(e) => {
+                self.undo_stack.push((forward, backward));
+                std::result::Result::Err(e)
+            }
+        }
+    }
+
+    /// Re-applies the most recently undone patch; does nothing when there is none.
+    pub fn redo(&mut self) -> std::result::Result<(), crate::error::ZenpatchError> {
+        let std::option::Option::Some((forward, backward)) = self.redo_stack.pop() else {
+            return std::result::Result::Ok(());
+        };
+        match crate::apply::apply_actions(forward.clone(), &self.vfs, &crate::apply_options::ApplyOptions::default()) {
+            std::result::Result::Ok((vfs, _)) => {
+                self.vfs = vfs;
+                self.undo_stack.push((forward, backward));
+                std::result::Result::Ok(())
+            }
+            std::result::Result::Err(e) => {
+                self.redo_stack.push((forward, backward));
+                std::result::Result::Err(e)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PatchHistory;
+
+    fn history(max_history: usize) -> PatchHistory {
+        let mut vfs = crate::vfs::Vfs::new();
+        vfs.insert("a.txt".to_string(), "one\n".to_string());
+        PatchHistory::new(vfs, max_history)
+    }
+
+    const TO_TWO: &str = "*** Begin Patch\n*** Update File: a.txt\n@@\n-one\n+two\n*** End Patch";
+    const TO_THREE: &str = "*** Begin Patch\n*** Update File: a.txt\n@@\n-two\n+three\n*** End Patch";
+
+    #[test]
+    fn test_undo_and_redo() {
+        let mut h = history(10);
+        h.apply(TO_TWO).unwrap();
+        h.apply(TO_THREE).unwrap();
+
+        h.undo().unwrap();
+        assert_eq!(h.vfs().get("a.txt").unwrap(), "two\n");
+        h.undo().unwrap();
+        assert_eq!(h.vfs().get("a.txt").unwrap(), "one\n");
+        assert!(!h.can_undo());
+        h.undo().unwrap();
+
+        h.redo().unwrap();
+        h.redo().unwrap();
+        assert_eq!(h.vfs().get("a.txt").unwrap(), "three\n");
+        assert!(!h.can_redo());
+    }
+
+    #[test]
+    fn test_apply_clears_redo_stack() {
+        let mut h = history(10);
+        h.apply(TO_TWO).unwrap();
+        h.undo().unwrap();
+        assert!(h.can_redo());
+        h.apply(TO_TWO).unwrap();
+        assert!(!h.can_redo());
+    }
+
+    #[test]
+    fn test_max_history_drops_oldest() {
+        let mut h = history(1);
+        h.apply(TO_TWO).unwrap();
+        h.apply(TO_THREE).unwrap();
+        h.undo().unwrap();
+        assert!(!h.can_undo());
+        assert_eq!(h.vfs().get("a.txt").unwrap(), "two\n");
+    }
+
+    #[test]
+    fn test_copy_is_undone_through_diff() {
+        let mut h = history(10);
+        h.apply("*** Begin Patch\n*** Copy File: a.txt\n*** To: b.txt\n*** End Patch").unwrap();
+        h.undo().unwrap();
+        assert!(!h.vfs().contains_key("b.txt"));
+        assert_eq!(h.vfs().len(), 1);
+    }
+}
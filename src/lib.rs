@@ -5,18 +5,109 @@
 //! It is designed for simplicity and robustness, especially for use by AI agents.
 
 pub mod apply;
+pub mod apply_options;
 pub mod applier;
+pub mod apply_multiple_patches;
+#[cfg(feature = "async")]
+pub mod async_apply;
+pub mod conflict;
 pub mod data;
+pub mod detect_line_endings;
+pub mod diagnostics;
+pub mod diff;
+#[cfg(feature = "encoding")]
+pub mod encoding;
 pub mod error;
+pub mod feature_set;
+pub mod file_summary;
+pub mod format;
+pub mod fs_apply;
+pub mod history;
+pub mod line_ending;
+pub mod merge;
+pub mod observer;
+#[cfg(feature = "rayon")]
+pub mod parallel_apply;
 pub mod parser;
+pub mod patch_ops;
+pub mod split_lines;
+pub mod summary;
+pub mod text_utils;
 pub mod vfs;
+pub mod vfs_ext;
+pub mod vfs_fs;
+pub mod vfs_transaction;
+pub mod writer;
 pub mod get_llm_instructions;
+pub mod get_llm_instructions_brief;
+pub mod get_llm_instructions_for;
+pub mod get_llm_instructions_json_schema;
+pub mod get_llm_instructions_markdown;
 
 pub use apply::apply;
-pub use apply::{apply_partial, PartialReport};
+pub use apply::{
+    apply_filtered, apply_filtered_with_observer, apply_ordered, apply_partial, apply_partial_hunks, apply_subset,
+    apply_to_snapshot, apply_with_observer, apply_with_options, apply_with_path_prefix_map, apply_with_retry,
+    apply_with_summary, apply_with_summary_and_options, PartialReport,
+};
+pub use applier::ambiguity_policy::AmbiguityPolicy;
+pub use applier::backtracking_applier::BacktrackingApplier;
+pub use applier::chunk_applier::ChunkApplier;
+pub use applier::complexity::estimate_patch_complexity;
+pub use applier::greedy_applier::GreedyApplier;
+pub use applier::line_mapping::LineMapping;
+pub use applier::patch_complexity::PatchComplexity;
+pub use applier::retry_strategy::RetryStrategy;
+pub use applier::simulate_apply::simulate_apply;
+pub use apply_multiple_patches::{apply_multiple_patches, apply_multiple_patches_partial, apply_patch_sequence};
+pub use apply_options::ApplyOptions;
+pub use conflict::{apply_best_effort, apply_with_conflict_policy, BestEffortResult, ConflictReport, ConflictResolution};
+#[cfg(feature = "async")]
+pub use async_apply::{apply_async, apply_to_directory_async};
+#[cfg(feature = "encoding")]
+pub use encoding::{apply_to_binary_vfs, BinaryVfs};
+#[cfg(feature = "rayon")]
+pub use parallel_apply::parallel_apply;
+pub use data::patch::Patch;
+pub use data::patch_metadata::PatchMetadata;
+pub use parser::parse_multiple_patches::parse_multiple_patches;
+pub use parser::text_to_patch_lenient::text_to_patch_lenient;
+pub use parser::text_to_patch_strict::text_to_patch_strict;
+pub use parser::text_to_patch_with_metadata::text_to_patch_with_metadata;
+pub use patch_ops::{
+    compress_patch, deduplicate_actions, expand_context, is_empty_patch, merge_adjacent_chunks, merge_patches,
+    minimize_context, normalize_patch, reverse_patch, sort_by_path,
+};
+pub use detect_line_endings::detect_line_endings;
+pub use diagnostics::explain_conflict;
+pub use diff::{diff_vfs, find_unchanged_regions, lcs_diff, DiffOp};
+pub use merge::{three_way_merge, MergeConflict, MergeResult};
 pub use error::ZenpatchError;
+pub use fs_apply::{apply_from_file, apply_to_directory};
+pub use file_summary::FileSummary;
+pub use format::{
+    ansi_color_formatter::AnsiColorFormatter, html_formatter::HtmlFormatter,
+    markdown_diff_formatter::MarkdownDiffFormatter, patch_formatter::PatchFormatter, plain_formatter::PlainFormatter,
+};
+pub use history::PatchHistory;
+pub use summary::PatchSummary;
+pub use text_utils::{levenshtein, match_lines, normalize, super_normalise};
 pub use vfs::Vfs;
+pub use vfs_ext::VfsExt;
+pub use vfs_fs::{vfs_from_directory, vfs_to_directory};
+pub use vfs_transaction::VfsTransaction;
+pub use writer::{
+    generate_patch_text::generate_patch_text, patch_to_text::patch_to_text,
+    patch_to_text_with_metadata::patch_to_text_with_metadata,
+};
+pub use feature_set::FeatureSet;
 pub use get_llm_instructions::get_llm_instructions;
+pub use get_llm_instructions_brief::get_llm_instructions_brief;
+pub use get_llm_instructions_for::get_llm_instructions_for;
+pub use get_llm_instructions_json_schema::get_llm_instructions_json_schema;
+pub use get_llm_instructions_markdown::get_llm_instructions_markdown;
+pub use line_ending::LineEnding;
+pub use observer::{logging_observer::LoggingObserver, nop_observer::NopObserver, patch_observer::PatchObserver};
 
 #[cfg(test)]
 pub mod tests;
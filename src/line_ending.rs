@@ -0,0 +1,29 @@
+//! Defines the `LineEnding` enum describing a file's line terminators.
+//!
+//! Returned by [`crate::detect_line_endings::detect_line_endings`].
+//! Conforms to rust coding guidelines (one item per file).
+
+/// The line-terminator convention used by a piece of text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LineEnding {
+    /// Windows `\r\n`.
+    Crlf,
+    /// Unix `\n` (also reported for text without any terminator).
+    Lf,
+    /// Classic Mac OS lone `\r`.
+    Cr,
+    /// More than one convention occurs in the same text.
+    Mixed,
+}
+
+impl LineEnding {
+    /// The terminator itself, or `None` for `Mixed`.
+    pub fn as_str(&self) -> std::option::Option<&'static str> {
+        match self {
+            LineEnding::Crlf => std::option::Option::Some("\r\n"),
+            LineEnding::Lf => std::option::Option::Some("\n"),
+            LineEnding::Cr => std::option::Option::Some("\r"),
+            LineEnding::Mixed => std::option::Option::None,
+        }
+    }
+}
@@ -0,0 +1,158 @@
+//! Three-way merging of two `Vfs`s that diverged from a common ancestor.
+//!
+//! `three_way_merge` diffs each side against the base with `diff_vfs` and
+//! combines the results file by file, recording a `MergeConflict` wherever
+//! both sides changed the same file incompatibly.
+
+/// A file both sides changed in ways that could not be combined.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeConflict {
+    /// Path of the file.
+    pub path: std::string::String,
+    /// The file in the common ancestor (empty when it did not exist there).
+    pub base_content: std::string::String,
+    /// The file on our side (empty when we deleted it).
+    pub ours_content: std::string::String,
+    /// The file on their side (empty when they deleted it).
+    pub theirs_content: std::string::String,
+}
+
+/// Outcome of [`three_way_merge`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeResult {
+    /// The merged files. A conflicting file keeps our version.
+    pub merged: crate::vfs::Vfs,
+    /// Every file that could not be merged, ordered by path.
+    pub conflicts: std::vec::Vec<MergeConflict>,
+}
+
+/// Merges `ours` and `theirs`, which both descend from `base`.
+///
+/// A file changed (added, updated or deleted) on one side only takes that
+/// side's version, and a file changed identically on both sides is kept. A
+/// file updated differently on both sides is merged by applying their
+/// `Update` from [`crate::diff::diff_vfs`]`(base, theirs)` to our version:
+/// the applier matches hunks by content, so their changes land around ours
+/// as long as each hunk's context and deleted lines are still intact. When
+/// that fails, or when one side deleted a file the other changed, or both
+/// added different files at one path, the file is a [`MergeConflict`].
+pub fn three_way_merge(base: &crate::vfs::Vfs, ours: &crate::vfs::Vfs, theirs: &crate::vfs::Vfs) -> MergeResult {
+    let our_changes = crate::diff::diff_vfs(base, ours);
+    let their_changes = crate::diff::diff_vfs(base, theirs);
+
+    let mut merged = base.clone();
+    let mut conflicts = std::vec::Vec::new();
+    let mut paths: std::vec::Vec<&str> =
+        our_changes.iter().chain(&their_changes).map(|action| action.path.as_str()).collect();
+    paths.sort_unstable();
+    paths.dedup();
+
+    for path in paths {
+        let (b, o, t) = (base.get(path), ours.get(path), theirs.get(path));
+        let resolved = if o == t || t == b {
+            std::option::Option::Some(o.cloned())
+        } else if o == b {
+            std::option::Option::Some(t.cloned())
+        } else {
+            merge_file(path, b, o, &their_changes)
+        };
+        match resolved {
+            std::option::Option::Some(std::option::Option::Some(content)) => {
+                merged.insert(path.to_string(), content);
+            }
+            std::option::Option::Some(std::option::Option::None) => {
+                merged.remove(path);
+            }
+            std::option::Option::None => {
+                match o {
+                    std::option::Option::Some(content) => merged.insert(path.to_string(), content.clone()),
+                    std::option::Option::None => merged.remove(path),
+                };
+                conflicts.push(MergeConflict {
+                    path: path.to_string(),
+                    base_content: b.cloned().unwrap_or_default(),
+                    ours_content: o.cloned().unwrap_or_default(),
+                    theirs_content: t.cloned().unwrap_or_default(),
+                });
+            }
+        }
+    }
+
+    MergeResult { merged, conflicts }
+}
+
+/// Our version of `path` with their `Update` applied, or `None` when the
+/// sides cannot be combined.
+fn merge_file(
+    path: &str,
+    base: std::option::Option<&std::string::String>,
+    ours: std::option::Option<&std::string::String>,
+    their_changes: &[crate::data::patch_action::PatchAction],
+) -> std::option::Option<std::option::Option<std::string::String>> {
+    base?;
+    let ours = ours?;
+    let theirs: std::vec::Vec<crate::data::patch_action::PatchAction> =
+        their_changes.iter().filter(|action| action.path == path).cloned().collect();
+    if theirs.len() != 1 || theirs[0].type_ != crate::data::action_type::ActionType::Update {
+        return std::option::Option::None;
+    }
+    let vfs: crate::vfs::Vfs = std::iter::once((path.to_string(), ours.clone())).collect();
+    let (mut applied, _) =
+        crate::apply::apply_actions(theirs, &vfs, &crate::apply_options::ApplyOptions::default()).ok()?;
+    std::option::Option::Some(applied.remove(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{three_way_merge, MergeConflict};
+
+    fn vfs(files: &[(&str, &str)]) -> crate::vfs::Vfs {
+        files.iter().map(|(p, c)| (p.to_string(), c.to_string())).collect()
+    }
+
+    const BASE: &str = "one\ntwo\nthree\nfour\nfive\nsix\nseven\neight\nnine\nten\n";
+
+    #[test]
+    fn test_changes_to_different_parts_merge() {
+        let base = vfs(&[("a.txt", BASE), ("b.txt", "b\n")]);
+        let ours = vfs(&[("a.txt", &BASE.replace("two", "TWO")), ("b.txt", "b\n"), ("new.txt", "n\n")]);
+        let theirs = vfs(&[("a.txt", &BASE.replace("nine", "NINE"))]);
+
+        let result = three_way_merge(&base, &ours, &theirs);
+        assert!(result.conflicts.is_empty(), "{:?}", result.conflicts);
+        assert_eq!(result.merged["a.txt"], BASE.replace("two", "TWO").replace("nine", "NINE"));
+        assert_eq!(result.merged["new.txt"], "n\n");
+        assert!(!result.merged.contains_key("b.txt"));
+    }
+
+    #[test]
+    fn test_overlapping_changes_conflict() {
+        let base = vfs(&[("a.txt", BASE)]);
+        let ours = vfs(&[("a.txt", &BASE.replace("five", "ours"))]);
+        let theirs = vfs(&[("a.txt", &BASE.replace("five", "theirs"))]);
+
+        let result = three_way_merge(&base, &ours, &theirs);
+        assert_eq!(result.merged["a.txt"], ours["a.txt"]);
+        assert_eq!(
+            result.conflicts,
+            vec![MergeConflict {
+                path: "a.txt".to_string(),
+                base_content: BASE.to_string(),
+                ours_content: ours["a.txt"].clone(),
+                theirs_content: theirs["a.txt"].clone(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_delete_against_update_conflicts() {
+        let base = vfs(&[("a.txt", BASE)]);
+        let ours = vfs(&[]);
+        let theirs = vfs(&[("a.txt", &BASE.replace("five", "5"))]);
+
+        let result = three_way_merge(&base, &ours, &theirs);
+        assert_eq!(result.conflicts.len(), 1);
+        assert!(result.conflicts[0].ours_content.is_empty());
+        assert!(!result.merged.contains_key("a.txt"));
+    }
+}
@@ -0,0 +1,37 @@
+//! Defines `LoggingObserver`, which reports patch progress on stderr.
+//!
+//! Conforms to rust coding guidelines (one item per file).
+
+/// A [`crate::observer::patch_observer::PatchObserver`] that prints one line
+/// per event to stderr, prefixed with `zenpatch:`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LoggingObserver;
+
+impl crate::observer::patch_observer::PatchObserver for LoggingObserver {
+    fn on_action_start(&mut self, index: usize, action: &crate::data::patch_action::PatchAction) {
+        std::eprintln!("zenpatch: action {}: {:?} {}", index, action.type_, action.path);
+    }
+
+    fn on_action_complete(
+        &mut self,
+        index: usize,
+        result: &std::result::Result<(), crate::error::ZenpatchError>,
+    ) {
+        match result {
+            std::result::Result::Ok(()) => std::eprintln!("zenpatch: action {}: done", index),
+            std::result::Result::Err(e) => std::eprintln!("zenpatch: action {}: failed: {}", index, e),
+        }
+    }
+
+    fn on_chunk_applied(&mut self, action_index: usize, chunk_index: usize) {
+        std::eprintln!("zenpatch: action {}: chunk {} applied", action_index, chunk_index);
+    }
+
+    fn on_action_skipped(&mut self, index: usize, action: &crate::data::patch_action::PatchAction) {
+        std::eprintln!("zenpatch: action {}: skipped {:?} {}", index, action.type_, action.path);
+    }
+
+    fn on_warning(&mut self, message: &str) {
+        std::eprintln!("zenpatch: warning: {}", message);
+    }
+}
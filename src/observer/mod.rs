@@ -0,0 +1,8 @@
+//! Progress reporting hooks for patch application.
+//!
+//! `apply_with_observer` drives a `PatchObserver` as it works through a
+//! patch; `NopObserver` and `LoggingObserver` are the stock implementations.
+
+pub mod logging_observer;
+pub mod nop_observer;
+pub mod patch_observer;
@@ -0,0 +1,10 @@
+//! Defines `NopObserver`, the observer that ignores every event.
+//!
+//! Conforms to rust coding guidelines (one item per file).
+
+/// A [`crate::observer::patch_observer::PatchObserver`] that does nothing;
+/// what plain `apply` uses internally.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NopObserver;
+
+impl crate::observer::patch_observer::PatchObserver for NopObserver {}
@@ -0,0 +1,35 @@
+//! Defines the `PatchObserver` trait for progress reporting.
+//!
+//! Every method has an empty default body, so implementors override only the
+//! events they care about.
+//! Conforms to rust coding guidelines (one item per file).
+
+/// Receives progress events from [`crate::apply::apply_with_observer`].
+///
+/// Events for an action arrive in order: `on_action_start`, then one
+/// `on_chunk_applied` per chunk if an `Update` succeeded, then
+/// `on_action_complete` with the action's outcome. Application stops after
+/// the first failing action.
+pub trait PatchObserver {
+    /// Called before the action at `index` is applied.
+    fn on_action_start(&mut self, _index: usize, _action: &crate::data::patch_action::PatchAction) {}
+
+    /// Called once the action at `index` has succeeded or failed.
+    fn on_action_complete(
+        &mut self,
+        _index: usize,
+        _result: &std::result::Result<(), crate::error::ZenpatchError>,
+    ) {
+    }
+
+    /// Called for each chunk of a successfully applied `Update` action.
+    fn on_chunk_applied(&mut self, _action_index: usize, _chunk_index: usize) {}
+
+    /// Called instead of the other events for an action that
+    /// [`crate::apply::apply_filtered_with_observer`] leaves out.
+    fn on_action_skipped(&mut self, _index: usize, _action: &crate::data::patch_action::PatchAction) {}
+
+    /// Called for conditions worth reporting that do not stop application,
+    /// such as a patch that contains no changes.
+    fn on_warning(&mut self, _message: &str) {}
+}
@@ -0,0 +1,156 @@
+//! Applies the independent parts of a multi-file patch concurrently (behind
+//! the `rayon` feature).
+//!
+//! Actions are grouped so that no two groups can see each other's files;
+//! each group then runs the ordinary sequential applier on rayon's thread
+//! pool against its own slice of the VFS.
+
+/// Variant of [`crate::apply::apply`] that applies groups of actions touching
+/// unrelated files in parallel, for large patches spread over many files.
+///
+/// Two actions share a group when their [`crate::data::patch_action::PatchAction::affected_paths`]
+/// have a file name in common. That is stricter than sharing a path on
+/// purpose: a path may resolve to a longer VFS key by suffix (see
+/// [`crate::apply::resolve_vfs_path`]), and such a key always keeps the file
+/// name. Within a group, actions apply in document order, so the result is
+/// the same as [`crate::apply::apply`]'s, including which error is reported
+/// when several actions fail (the earliest in the patch).
+pub fn parallel_apply(
+    patch_text: &str,
+    vfs: &crate::vfs::Vfs,
+) -> std::result::Result<crate::vfs::Vfs, crate::error::ZenpatchError> {
+    use rayon::iter::IntoParallelIterator;
+    use rayon::iter::ParallelIterator;
+
+    let actions = crate::parser::text_to_patch::text_to_patch(patch_text)?;
+    let groups = independent_groups(&actions);
+    if groups.len() < 2 || crate::patch_ops::is_empty_patch(&actions) {
+        return crate::apply::apply_actions(actions, vfs, &crate::apply_options::ApplyOptions::default())
+            .map(|(new_vfs, _)| new_vfs);
+    }
+
+    let results: std::vec::Vec<_> = groups
+        .into_par_iter()
+        .map(|group| {
+            let names: std::collections::HashSet<&str> =
+                group.iter().flat_map(|&index| actions[index].affected_paths()).map(file_name).collect();
+            let mut group_vfs: crate::vfs::Vfs = vfs
+                .iter()
+                .filter(|(key, _)| names.contains(file_name(key)))
+                .map(|(key, content)| (key.clone(), content.clone()))
+                .collect();
+            let replaced: std::vec::Vec<std::string::String> = group_vfs.keys().cloned().collect();
+            let options = crate::apply_options::ApplyOptions::default();
+            let backtracking_options = options.backtracking_options();
+            let mut summary = crate::summary::PatchSummary::new();
+            for &index in &group {
+                let action = &actions[index];
+                crate::apply::apply_action(action, &mut group_vfs, &mut summary, &options, &backtracking_options)
+                    .map_err(|e| (index, e))?;
+            }
+            std::result::Result::Ok((replaced, group_vfs))
+        })
+        .collect();
+
+    let mut new_vfs = vfs.clone();
+    let mut first_error: std::option::Option<(usize, crate::error::ZenpatchError)> = std::option::Option::None;
+    for result in results {
+        match result {
+            std::result::Result::Ok((replaced, group_vfs)) => {
+                for key in replaced {
+                    new_vfs.remove(&key);
+                }
+                new_vfs.extend(group_vfs);
+            }
+            std::result::Result::Err((index, e)) => {
+                if first_error.as_ref().is_none_or(|(first, _)| index < *first) {
+                    first_error = std::option::Option::Some((index, e));
+                }
+            }
+        }
+    }
+    match first_error {
+        std::option::Option::Some((_, e)) => std::result::Result::Err(e),
+        std::option::Option::None => std::result::Result::Ok(new_vfs),
+    }
+}
+
+/// Partitions action indices into groups whose affected paths share no file
+/// name, each group in document order.
+fn independent_groups(actions: &[crate::data::patch_action::PatchAction]) -> std::vec::Vec<std::vec::Vec<usize>> {
+    // Union-find over action indices, joined through the file names they touch.
+    let mut parent: std::vec::Vec<usize> = (0..actions.len()).collect();
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+    let mut owner: std::collections::HashMap<&str, usize> = std::collections::HashMap::new();
+    for (index, action) in actions.iter().enumerate() {
+        for name in action.affected_paths().into_iter().map(file_name) {
+            let other = *owner.entry(name).or_insert(index);
+            let (a, b) = (root(&mut parent, index), root(&mut parent, other));
+            parent[a.max(b)] = a.min(b);
+        }
+    }
+
+    let mut groups: std::collections::BTreeMap<usize, std::vec::Vec<usize>> = std::collections::BTreeMap::new();
+    for index in 0..actions.len() {
+        groups.entry(root(&mut parent, index)).or_default().push(index);
+    }
+    groups.into_values().collect()
+}
+
+/// The last path component, which suffix resolution never changes.
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+#[cfg(test)]
+mod tests {
+    fn vfs_with(files: &[(&str, &str)]) -> crate::vfs::Vfs {
+        files.iter().map(|(path, content)| (path.to_string(), content.to_string())).collect()
+    }
+
+    #[test]
+    fn test_matches_sequential_apply() {
+        let vfs = vfs_with(&[("a.txt", "a\n"), ("src/b.txt", "b\n"), ("c.txt", "c\n"), ("keep.txt", "k\n")]);
+        let patch = "*** Begin Patch\n*** Update File: a.txt\n@@\n-a\n+A\n\
+                     *** Update File: b.txt\n@@\n-b\n+B\n\
+                     *** Delete File: c.txt\n-c\n*** Add File: d.txt\n+d\n\
+                     *** Rename File: a.txt\n*** To: e.txt\n*** End Patch";
+
+        let parallel = super::parallel_apply(patch, &vfs).unwrap();
+
+        std::assert_eq!(parallel, crate::apply::apply(patch, &vfs).unwrap());
+        std::assert_eq!(parallel.get("e.txt").map(std::string::String::as_str), std::option::Option::Some("A\n"));
+        std::assert_eq!(parallel.get("src/b.txt").map(std::string::String::as_str), std::option::Option::Some("B\n"));
+    }
+
+    #[test]
+    fn test_groups_join_actions_through_shared_file_names() {
+        let actions = crate::parser::text_to_patch::text_to_patch(
+            "*** Begin Patch\n*** Update File: a.txt\n@@\n-a\n+A\n*** Add File: x/b.txt\n+b\n\
+             *** Update File: a.txt\n*** Move to: c.txt\n@@\n-A\n+C\n*** Update File: b.txt\n@@\n-b\n+B\n\
+             *** Add File: d.txt\n+d\n*** End Patch",
+        )
+        .unwrap();
+
+        let groups = super::independent_groups(&actions);
+
+        std::assert_eq!(groups, std::vec![std::vec![0, 2], std::vec![1, 3], std::vec![4]]);
+    }
+
+    #[test]
+    fn test_reports_the_earliest_failing_action() {
+        let vfs = vfs_with(&[("a.txt", "a\n"), ("b.txt", "b\n")]);
+        let patch =
+            "*** Begin Patch\n*** Update File: a.txt\n@@\n-missing\n+A\n*** Delete File: gone.txt\n*** End Patch";
+
+        let parallel = super::parallel_apply(patch, &vfs).unwrap_err();
+
+        std::assert_eq!(parallel, crate::apply::apply(patch, &vfs).unwrap_err());
+    }
+}
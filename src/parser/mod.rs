@@ -1,2 +1,8 @@
+pub mod parse_multiple_patches;
+pub(crate) mod parse_patch_text;
+#[allow(clippy::module_inception)]
 pub mod parser;
 pub mod text_to_patch;
+pub mod text_to_patch_lenient;
+pub mod text_to_patch_strict;
+pub mod text_to_patch_with_metadata;
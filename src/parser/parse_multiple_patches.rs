@@ -0,0 +1,93 @@
+//! Provides `parse_multiple_patches` for text holding several patch envelopes.
+//!
+//! Some tools emit multiple `*** Begin Patch` / `*** End Patch` blocks back to
+//! back in one string; `text_to_patch` only accepts a single block.
+//! Adheres to the one-item-per-file rule and uses fully qualified paths.
+
+/// Splits `text` at every `*** Begin Patch` line and parses each block with
+/// `text_to_patch`, returning one action list per block, in order.
+///
+/// Text without any `*** Begin Patch` marker is parsed as a single patch (so
+/// the implicit envelope still works). Anything other than whitespace before
+/// the first marker, or a block with no file directive, is an
+/// `InvalidPatchFormat` error naming the block's index.
+pub fn parse_multiple_patches(
+    text: &str,
+) -> std::result::Result<
+    std::vec::Vec<std::vec::Vec<crate::data::patch_action::PatchAction>>,
+    crate::error::ZenpatchError,
+> {
+    let lines: std::vec::Vec<&str> = text.lines().collect();
+    let starts: std::vec::Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| l.trim() == "*** Begin Patch")
+        .map(|(i, _)| i)
+        .collect();
+
+    let first = match starts.first() {
+        std::option::Option::Some(&first) => first,
+        std::option::Option::None => {
+            return crate::parser::text_to_patch::text_to_patch(text).map(|actions| std::vec![actions]);
+        }
+    };
+    if lines[..first].iter().any(|l| !l.trim().is_empty()) {
+        return std::result::Result::Err(crate::error::ZenpatchError::InvalidPatchFormat(
+            "unexpected text before the first '*** Begin Patch'".to_string(),
+        ));
+    }
+
+    let mut patches = std::vec::Vec::with_capacity(starts.len());
+    for (index, &start) in starts.iter().enumerate() {
+        let end = starts.get(index + 1).copied().unwrap_or(lines.len());
+        let segment = lines[start..end].join("\n");
+        let actions = crate::parser::text_to_patch::text_to_patch(&segment).map_err(|e| match e {
+            crate::error::ZenpatchError::InvalidPatchFormat(msg) => {
+                crate::error::ZenpatchError::InvalidPatchFormat(std::format!("patch {}: {}", index, msg))
+            }
+            other => other,
+        })?;
+        patches.push(actions);
+    }
+    std::result::Result::Ok(patches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_multiple_patches;
+
+    #[test]
+    fn test_parse_multiple_patches_splits_blocks() {
+        let text = "*** Begin Patch\n*** Add File: a.txt\n+a\n*** End Patch\n\n\
+*** Begin Patch\n*** Update File: a.txt\n@@\n-a\n+b\n*** Delete File: c.txt\n*** End Patch\n";
+        let patches = parse_multiple_patches(text).unwrap();
+        assert_eq!(patches.len(), 2);
+        assert_eq!(patches[0].len(), 1);
+        assert_eq!(patches[1].len(), 2);
+        assert_eq!(patches[1][1].path, "c.txt");
+    }
+
+    #[test]
+    fn test_parse_multiple_patches_single_block_and_bare_directive() {
+        assert_eq!(parse_multiple_patches("*** Begin Patch\n*** Add File: a\n+a\n*** End Patch").unwrap().len(), 1);
+        assert_eq!(parse_multiple_patches("*** Add File: a\n+a").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_parse_multiple_patches_empty_block_is_an_error() {
+        let text = "*** Begin Patch\n*** Add File: a.txt\n+a\n*** End Patch\n*** Begin Patch\n*** End Patch";
+        match parse_multiple_patches(text) {
+            Err(crate::error::ZenpatchError::InvalidPatchFormat(msg)) => assert!(msg.starts_with("patch 1: "), "{msg}"),
+            other => panic!("expected InvalidPatchFormat, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_multiple_patches_rejects_leading_text() {
+        let text = "Here is the patch:\n*** Begin Patch\n*** Add File: a\n+a\n*** End Patch";
+        assert!(matches!(
+            parse_multiple_patches(text),
+            Err(crate::error::ZenpatchError::InvalidPatchFormat(_))
+        ));
+    }
+}
@@ -0,0 +1,98 @@
+//! Provides `parse_patch_text`, the shared core of the strict and lenient
+//! patch-text entry points.
+//!
+//! Normalizes the envelope (markdown fences, implicit Begin/End markers),
+//! parses the actions, and returns them alongside the patch's annotations.
+//! Adheres to the one-item-per-file rule and uses fully qualified paths.
+
+/// Parses patch text into its actions plus the `PatchMetadata` found along the
+/// way, with [`crate::parser::parser::Parser::parse_lenient`] instead of
+/// [`crate::parser::parser::Parser::parse`] when `lenient` is set.
+pub(crate) fn parse_patch_text(
+    text: &str,
+    lenient: bool,
+) -> std::result::Result<
+    (std::vec::Vec<crate::data::patch_action::PatchAction>, crate::data::patch_metadata::PatchMetadata),
+    crate::error::ZenpatchError,
+> {
+    // Some Windows editors save a UTF-8 BOM, which would otherwise make the
+    // first line '\u{FEFF}*** Begin Patch'.
+    let mut normalized = text.trim_start_matches('\u{FEFF}').trim().to_string();
+
+    // LLMs routinely wrap the whole patch in a markdown code fence
+    // (```/```diff/```patch). Strip a leading fence line and, if present,
+    // the matching trailing fence line.
+    if normalized.starts_with("```") {
+        let mut lines: std::vec::Vec<&str> = normalized.lines().collect();
+        lines.remove(0);
+        if lines
+            .last()
+            .is_some_and(|l| l.trim() == "```")
+        {
+            lines.pop();
+        }
+        normalized = lines.join("\n").trim().to_string();
+    }
+
+    // Lenient parsing rewrites `* Begin Patch`-style directives before the
+    // envelope is checked, so the markers themselves may be abbreviated.
+    if lenient {
+        normalized = normalized
+            .lines()
+            .filter_map(crate::parser::parser::Parser::lenient_line)
+            .collect::<std::vec::Vec<_>>()
+            .join("\n");
+    }
+
+    // LLMs routinely omit the Begin/End envelope and start straight with a
+    // file directive. When NEITHER marker is present and the text begins
+    // with a directive, the intent is unambiguous — wrap it implicitly.
+    // Deliberately narrow: if exactly one marker is present the patch is
+    // malformed or truncated (a missing '*** End Patch' after a present
+    // '*** Begin Patch' usually means the generation was cut off), and
+    // auto-repairing it could apply half a patch — keep failing loudly.
+    if (normalized.starts_with("*** Update File:")
+        || normalized.starts_with("*** Add File:")
+        || normalized.starts_with("*** Delete File:")
+        || normalized.starts_with("*** Copy File:")
+        || normalized.starts_with("*** Rename File:"))
+        && !normalized.contains("*** Begin Patch")
+        && !normalized.contains("*** End Patch")
+    {
+        normalized = std::format!("*** Begin Patch\n{normalized}\n*** End Patch");
+    }
+
+    let trimmed_text = normalized.as_str();
+
+    let lines: std::vec::Vec<&str> = trimmed_text.lines().collect();
+
+    if lines.len() < 2 {
+        return std::result::Result::Err(crate::error::ZenpatchError::InvalidPatchFormat(
+            "Patch text is too short (must include start and end markers).".to_string(),
+        ));
+    }
+    if lines[0] != "*** Begin Patch" {
+        return std::result::Result::Err(crate::error::ZenpatchError::InvalidPatchFormat(
+            "Patch must start with '*** Begin Patch'".to_string(),
+        ));
+    }
+    if lines[lines.len() - 1] != "*** End Patch" {
+        return std::result::Result::Err(crate::error::ZenpatchError::InvalidPatchFormat(
+            "Patch must end with '*** End Patch'".to_string(),
+        ));
+    }
+
+    let mut parser = crate::parser::parser::Parser::new(trimmed_text);
+    let mut actions = if lenient { parser.parse_lenient()? } else { parser.parse()? };
+
+    // Post-process chunks to populate del_lines and ins_lines
+    for action in &mut actions {
+        for chunk in &mut action.chunks {
+            chunk.recompute_del_ins_lines();
+        }
+        action.validate()?;
+    }
+
+    std::result::Result::Ok((actions, parser.metadata))
+}
+
@@ -9,6 +9,9 @@
 pub struct Parser {
     pub lines: std::vec::Vec<std::string::String>,
     pub index: usize,
+    /// Comments and `*** Author:`-style headers collected by [`Parser::parse`];
+    /// they never become actions.
+    pub metadata: crate::data::patch_metadata::PatchMetadata,
 }
 
 impl Parser {
@@ -41,14 +44,61 @@ impl Parser {
     }
 
     /// Creates a new parser for the given patch content.
+    /// A leading UTF-8 byte order mark is ignored.
     pub fn new(patch_content: &str) -> Self {
+        let patch_content = patch_content.strip_prefix('\u{FEFF}').unwrap_or(patch_content);
         let lines = if patch_content.trim().is_empty() {
             std::vec::Vec::new()
         } else {
             patch_content.lines().map(std::string::String::from).collect()
         };
 
-        Self { lines, index: 0 }
+        Self {
+            lines,
+            index: 0,
+            metadata: crate::data::patch_metadata::PatchMetadata::default(),
+        }
+    }
+
+    /// Returns the text of an annotation line: `*** Comment: <text>`, or a
+    /// `# <text>` line where no hunk content is expected.
+    fn comment_text(line: &str) -> std::option::Option<&str> {
+        line.strip_prefix("*** Comment:")
+            .or_else(|| line.strip_prefix('#'))
+            .map(str::trim)
+    }
+
+    /// Records `line` in the metadata's `comments` if it is an annotation line.
+    fn take_comment(&mut self, line: &str) {
+        if let std::option::Option::Some(text) = Self::comment_text(line) {
+            self.metadata.comments.push(text.to_string());
+        }
+    }
+
+    /// Records `line` in the metadata if it is an `*** Author:`, `*** Date:`,
+    /// `*** Subject:` or `*** Description:` header; a repeated header
+    /// overwrites the earlier one.
+    fn take_header(&mut self, line: &str) -> bool {
+        let metadata = &mut self.metadata;
+        let fields: [(&str, &mut std::option::Option<std::string::String>); 4] = [
+            ("*** Author:", &mut metadata.author),
+            ("*** Date:", &mut metadata.date),
+            ("*** Subject:", &mut metadata.subject),
+            ("*** Description:", &mut metadata.description),
+        ];
+        for (prefix, field) in fields {
+            if let std::option::Option::Some(value) = line.strip_prefix(prefix) {
+                *field = std::option::Option::Some(value.trim().to_string());
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Whether a section body (Add/Delete) continues at `line`: it runs until
+    /// the next `*** ` directive, but comments may be interleaved.
+    fn in_section_body(line: &str) -> bool {
+        !line.starts_with("*** ") || line.starts_with("*** Comment:")
     }
 
     /// Parses the patch text into a single `PatchAction`.
@@ -70,7 +120,18 @@ impl Parser {
                 actions.push(self.parse_update_file()?);
             } else if line.starts_with("*** Delete File: ") {
                 actions.push(self.parse_delete_file()?);
+            } else if line.starts_with("*** Copy File: ") {
+                actions.push(self.parse_with_destination("*** Copy File: ", crate::data::action_type::ActionType::Copy)?);
+            } else if line.starts_with("*** Rename File: ") {
+                actions.push(
+                    self.parse_with_destination("*** Rename File: ", crate::data::action_type::ActionType::Rename)?,
+                );
             } else {
+                let line = line.to_string();
+                // Headers are only recognised before the first file directive.
+                if !(actions.is_empty() && self.take_header(&line)) {
+                    self.take_comment(&line);
+                }
                 self.index += 1;
             }
         }
@@ -84,6 +145,95 @@ impl Parser {
         std::result::Result::Ok(actions)
     }
 
+    /// Directives [`Parser::parse`] understands; lenient parsing drops any
+    /// other `*** ` line.
+    const KNOWN_DIRECTIVES: [&'static str; 15] = [
+        "*** Begin Patch",
+        "*** End Patch",
+        "*** Add File:",
+        "*** Update File:",
+        "*** Delete File:",
+        "*** Copy File:",
+        "*** Rename File:",
+        "*** Move to:",
+        "*** To:",
+        "*** End of File",
+        "*** Comment:",
+        "*** Author:",
+        "*** Date:",
+        "*** Subject:",
+        "*** Description:",
+    ];
+
+    /// Rewrites one patch line into the strict format, or returns `None` to
+    /// drop it. See [`Parser::parse_lenient`] for the rules.
+    pub(crate) fn lenient_line(line: &str) -> std::option::Option<std::string::String> {
+        if let std::option::Option::Some(rest) = line.strip_prefix("@@") {
+            let rest = rest.trim();
+            if Self::parse_hunk_range(rest).is_some() {
+                return std::option::Option::Some(line.to_string());
+            }
+            let ctx = rest.strip_suffix("@@").unwrap_or(rest).trim();
+            return std::option::Option::Some(if ctx.is_empty() {
+                "@@".to_string()
+            } else {
+                std::format!("@@ {ctx}")
+            });
+        }
+        if let std::option::Option::Some(rest) = line.strip_prefix('=') {
+            return std::option::Option::Some(std::format!(" {rest}"));
+        }
+        let directive = match line.strip_prefix("* ") {
+            std::option::Option::Some(rest) => std::format!("*** {rest}"),
+            std::option::Option::None if line.starts_with("*** ") => line.to_string(),
+            std::option::Option::None => return std::option::Option::Some(line.to_string()),
+        };
+        Self::KNOWN_DIRECTIVES
+            .iter()
+            .any(|known| directive.trim_end().starts_with(known))
+            .then_some(directive)
+    }
+
+    /// Parses like [`Parser::parse`] after recovering from common format
+    /// deviations:
+    ///
+    /// - an `@@` line is a chunk separator whatever follows it; a stray
+    ///   closing `@@` (`@@ fn main @@`) is dropped from the change context;
+    /// - `* ` is accepted in place of `*** ` for directives;
+    /// - a line starting with `=` is a context line. The file is not known
+    ///   at parse time, so its content is checked when the chunk is applied;
+    /// - unrecognized `*** ` directives are ignored instead of ending the
+    ///   current section.
+    pub fn parse_lenient(
+        &mut self,
+    ) -> std::result::Result<std::vec::Vec<crate::data::patch_action::PatchAction>, crate::error::ZenpatchError>
+    {
+        self.lines = self.lines.iter().filter_map(|line| Self::lenient_line(line)).collect();
+        self.parse()
+    }
+
+    /// Parses like [`Parser::parse`], then swaps the `+` and `-` lines of
+    /// every `Update` chunk: the explicit recovery for a patch written
+    /// backwards, e.g. after an `InvalidPatchFormat` reporting swapped markers
+    /// (see `ApplyOptions::detect_swapped_markers`). `Add` and `Delete`
+    /// content is left alone, since its marker is fixed by the directive.
+    pub fn parse_with_swapped_markers(
+        &mut self,
+    ) -> std::result::Result<std::vec::Vec<crate::data::patch_action::PatchAction>, crate::error::ZenpatchError>
+    {
+        let mut actions = self.parse()?;
+        for action in &mut actions {
+            if action.type_ == crate::data::action_type::ActionType::Update {
+                for chunk in &mut action.chunks {
+                    *chunk = crate::patch_ops::reverse_chunk(chunk);
+                    // `parse` leaves the derived lists to its callers.
+                    chunk.recompute_del_ins_lines();
+                }
+            }
+        }
+        std::result::Result::Ok(actions)
+    }
+
     fn parse_add_file(
         &mut self,
     ) -> std::result::Result<crate::data::patch_action::PatchAction, crate::error::ZenpatchError> {
@@ -100,10 +250,12 @@ impl Parser {
        // omitted — dropping them would corrupt the added file. Only a trailing
        // run of them (a separator before the next directive) is not content.
        let mut trailing_bare_empty: usize = 0;
-       while self.index < self.lines.len() && !self.lines[self.index].starts_with("*** ") {
-           let line_content = &self.lines[self.index];
-           if line_content.starts_with('+') {
-               let content = line_content[1..].to_string();
+       while self.index < self.lines.len() && Self::in_section_body(&self.lines[self.index]) {
+           let line_content = self.lines[self.index].clone();
+           if let std::option::Option::Some(stripped) = line_content
+               .strip_prefix(crate::data::line_type::LineType::Insertion.prefix())
+           {
+               let content = stripped.to_string();
                lines.push((
                    crate::data::line_type::LineType::Insertion,
                    content.clone(),
@@ -117,6 +269,8 @@ impl Parser {
                ));
                ins_lines.push(std::string::String::new());
                trailing_bare_empty += 1;
+           } else {
+               self.take_comment(&line_content);
            }
            self.index += 1;
        }
@@ -132,6 +286,8 @@ impl Parser {
            ins_lines,
            change_context: std::option::Option::None,
            is_end_of_file: false,
+           forced_position: std::option::Option::None,
+           context_hint: std::option::Option::None,
        };
 
        std::result::Result::Ok(crate::data::patch_action::PatchAction {
@@ -163,6 +319,8 @@ impl Parser {
             if line.starts_with("*** Add File:")
                 || line.starts_with("*** Update File:")
                 || line.starts_with("*** Delete File:")
+                || line.starts_with("*** Copy File:")
+                || line.starts_with("*** Rename File:")
             {
                 break; // Stop before next file directive
             }
@@ -186,16 +344,21 @@ impl Parser {
                 continue;
             }
 
-            if line.starts_with("@@") {
+            if let std::option::Option::Some(trimmed) = line.strip_prefix("@@") {
                 Self::push_chunk(&mut chunks, current_chunk);
                 current_chunk = crate::data::chunk::Chunk::new();
-                // Extract change_context from "@@ <text>" header
-                let trimmed = &line[2..];
-                if !trimmed.is_empty() {
-                    let ctx = trimmed.trim_start();
-                    if !ctx.is_empty() {
-                        current_chunk.change_context = std::option::Option::Some(ctx.to_string());
+                // A unified-diff range header ("@@ -n,m +n,m @@ <text>") seeds
+                // orig_index, and its text (git's possibly truncated function
+                // name) is only a display hint. A bare "@@ <text>" is the
+                // change_context, which must match a file line.
+                let ctx = trimmed.trim_start();
+                if let std::option::Option::Some((orig_index, rest)) = Self::parse_hunk_range(ctx) {
+                    current_chunk.orig_index = orig_index;
+                    if !rest.is_empty() {
+                        current_chunk.context_hint = std::option::Option::Some(rest.to_string());
                     }
+                } else if !ctx.is_empty() {
+                    current_chunk.change_context = std::option::Option::Some(ctx.to_string());
                 }
                 self.index += 1;
                 continue;
@@ -210,22 +373,14 @@ impl Parser {
                     crate::data::line_type::LineType::Context,
                     std::string::String::new(),
                 )
-            } else if line.starts_with(' ') {
-                (
-                    crate::data::line_type::LineType::Context,
-                    line[1..].to_string(),
-                )
-            } else if line.starts_with('+') {
-                (
-                    crate::data::line_type::LineType::Insertion,
-                    line[1..].to_string(),
-                )
-            } else if line.starts_with('-') {
-                (
-                    crate::data::line_type::LineType::Deletion,
-                    line[1..].to_string(),
-                )
+            } else if let std::option::Option::Some(line_type) = line
+                .chars()
+                .next()
+                .and_then(crate::data::line_type::LineType::from_prefix)
+            {
+                (line_type, line[1..].to_string())
             } else {
+                self.take_comment(&line);
                 self.index += 1;
                 continue;
             };
@@ -244,6 +399,64 @@ impl Parser {
         })
     }
 
+    /// Parses the `-n,m +n,m @@` part of a unified-diff hunk header, returning
+    /// the 0-based original index and the trimmed text after the closing `@@`.
+    ///
+    /// A zero-length old range (`-n,0`) means "insert after line n", so its
+    /// index is `n` rather than `n - 1`. Returns `None` when `rest` is not a
+    /// range header, leaving the caller to treat it as plain context text.
+    fn parse_hunk_range(rest: &str) -> std::option::Option<(usize, &str)> {
+        fn parse_range(range: &str) -> std::option::Option<(usize, usize)> {
+            let (start, count) = match range.split_once(',') {
+                std::option::Option::Some((start, count)) => (start, count.parse().ok()?),
+                std::option::Option::None => (range, 1),
+            };
+            std::option::Option::Some((start.parse().ok()?, count))
+        }
+
+        let mut tokens = rest.splitn(3, ' ');
+        let (start, count) = parse_range(tokens.next()?.strip_prefix('-')?)?;
+        parse_range(tokens.next()?.strip_prefix('+')?)?;
+        let tail = tokens.next().unwrap_or("").strip_prefix("@@")?;
+
+        let orig_index = if count == 0 { start } else { start.saturating_sub(1) };
+        std::option::Option::Some((orig_index, tail.trim()))
+    }
+
+    /// Parses a `<directive><source>` line followed by `*** To: <destination>`
+    /// (the Copy and Rename directives) into a chunk-less action.
+    fn parse_with_destination(
+        &mut self,
+        directive: &str,
+        action_type: crate::data::action_type::ActionType,
+    ) -> std::result::Result<crate::data::patch_action::PatchAction, crate::error::ZenpatchError> {
+        let line = &self.lines[self.index];
+        let filename = line.trim().trim_start_matches(directive).trim().to_string();
+        self.index += 1;
+
+        let destination = self
+            .lines
+            .get(self.index)
+            .and_then(|l| l.trim().strip_prefix("*** To: "))
+            .map(|d| d.trim().to_string())
+            .filter(|d| !d.is_empty())
+            .ok_or_else(|| {
+                crate::error::ZenpatchError::InvalidPatchFormat(std::format!(
+                    "'{}{}' must be followed by '*** To: <destination>'",
+                    directive,
+                    filename
+                ))
+            })?;
+        self.index += 1;
+
+        std::result::Result::Ok(crate::data::patch_action::PatchAction {
+            type_: action_type,
+            path: filename,
+            new_path: std::option::Option::Some(destination),
+            chunks: std::vec::Vec::new(),
+        })
+    }
+
     fn parse_delete_file(
         &mut self,
     ) -> std::result::Result<crate::data::patch_action::PatchAction, crate::error::ZenpatchError> {
@@ -255,11 +468,15 @@ impl Parser {
         self.index += 1;
 
         let mut lines = std::vec::Vec::new();
-        while self.index < self.lines.len() && !self.lines[self.index].starts_with("*** ") {
-            let line_content = &self.lines[self.index];
-            if line_content.starts_with('-') {
-                let content = line_content[1..].to_string();
+        while self.index < self.lines.len() && Self::in_section_body(&self.lines[self.index]) {
+            let line_content = self.lines[self.index].clone();
+            if let std::option::Option::Some(stripped) = line_content
+                .strip_prefix(crate::data::line_type::LineType::Deletion.prefix())
+            {
+                let content = stripped.to_string();
                 lines.push((crate::data::line_type::LineType::Deletion, content));
+            } else {
+                self.take_comment(&line_content);
             }
             self.index += 1;
         }
@@ -274,6 +491,8 @@ impl Parser {
                 ins_lines: std::vec::Vec::new(),
                 change_context: std::option::Option::None,
                 is_end_of_file: false,
+                forced_position: std::option::Option::None,
+                context_hint: std::option::Option::None,
             }]
         };
 
@@ -291,6 +510,19 @@ mod tests {
     use super::*;
     use crate::data::{action_type::ActionType, line_type::LineType};
 
+    #[test]
+    fn test_parse_with_swapped_markers_only_swaps_update_chunks() {
+        let content = "*** Begin Patch\n*** Update File: a.txt\n@@\n ctx\n-new\n+old\n*** Add File: b.txt\n+b\n*** End Patch";
+        let actions = Parser::new(content).parse_with_swapped_markers().unwrap();
+
+        let chunk = &actions[0].chunks[0];
+        assert_eq!(chunk.lines[1], (LineType::Insertion, "new".to_string()));
+        assert_eq!(chunk.lines[2], (LineType::Deletion, "old".to_string()));
+        assert_eq!(chunk.del_lines, vec!["old"]);
+        assert_eq!(chunk.ins_lines, vec!["new"]);
+        assert_eq!(actions[1].chunks[0].lines, vec![(LineType::Insertion, "b".to_string())]);
+    }
+
     #[test]
     fn test_parse_add_file() {
         let content = "*** Begin Patch\n*** Add File: new.txt\n+hello\n+world\n*** End Patch";
@@ -423,6 +655,63 @@ mod tests {
         assert_eq!(action.chunks[1].lines[0], (LineType::Context, "line3".to_string()));
     }
 
+    #[test]
+    fn test_copy_file_with_destination() {
+        let content = "*** Begin Patch\n*** Copy File: a.txt\n*** To: b.txt\n*** End Patch";
+        let mut parser = Parser::new(content);
+        let actions = parser.parse().unwrap();
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].type_, ActionType::Copy);
+        assert_eq!(actions[0].path, "a.txt");
+        assert_eq!(actions[0].new_path, Some("b.txt".to_string()));
+        assert!(actions[0].chunks.is_empty());
+    }
+
+    #[test]
+    fn test_copy_file_without_destination_fails() {
+        let content = "*** Begin Patch\n*** Copy File: a.txt\n*** End Patch";
+        let mut parser = Parser::new(content);
+        match parser.parse().unwrap_err() {
+            crate::error::ZenpatchError::InvalidPatchFormat(msg) => assert!(msg.contains("*** To:")),
+            other => panic!("Expected InvalidPatchFormat error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_update_stops_before_copy_directive() {
+        let content = "*** Begin Patch\n*** Update File: a.txt\n@@\n-a\n+b\n*** Copy File: a.txt\n*** To: c.txt\n*** End Patch";
+        let mut parser = Parser::new(content);
+        let actions = parser.parse().unwrap();
+        assert_eq!(actions.len(), 2);
+        assert_eq!(actions[1].type_, ActionType::Copy);
+    }
+
+    #[test]
+    fn test_no_newline_marker_is_parsed() {
+        let content = "*** Begin Patch\n*** Update File: a.txt\n@@\n-a\n\\ No newline at end of file\n+b\n*** End Patch";
+        let actions = Parser::new(content).parse().unwrap();
+        let chunk = &actions[0].chunks[0];
+        assert_eq!(chunk.lines[1], (LineType::NoNewlineAtEOF, " No newline at end of file".to_string()));
+        assert_eq!(chunk.lines.len(), 3);
+    }
+
+    #[test]
+    fn test_rename_file_with_destination() {
+        let content = "*** Begin Patch\n*** Update File: a.txt\n@@\n-a\n+b\n*** Rename File: a.txt\n*** To: b.txt\n*** End Patch";
+        let actions = Parser::new(content).parse().unwrap();
+        assert_eq!(actions.len(), 2);
+        assert_eq!(actions[1].type_, ActionType::Rename);
+        assert_eq!(actions[1].path, "a.txt");
+        assert_eq!(actions[1].new_path, Some("b.txt".to_string()));
+        assert!(actions[1].chunks.is_empty());
+
+        let missing = "*** Begin Patch\n*** Rename File: a.txt\n*** End Patch";
+        match Parser::new(missing).parse().unwrap_err() {
+            crate::error::ZenpatchError::InvalidPatchFormat(msg) => assert!(msg.contains("*** Rename File: a.txt")),
+            other => panic!("Expected InvalidPatchFormat error, got {other:?}"),
+        }
+    }
+
     #[test]
     fn test_invalid_lines_inside_chunk_are_skipped() {
         // Lines that don't start with ' ', '+', '-', or '@@' are skipped
@@ -537,4 +826,33 @@ mod tests {
         let actions = parser.parse().unwrap();
         assert!(!actions[0].chunks[0].is_end_of_file);
     }
+
+    #[test]
+    fn test_hunk_range_header_sets_orig_index() {
+        let content = "*** Begin Patch\n*** Update File: a.txt\n@@ -42,7 +42,9 @@\n ctx\n+new\n@@ -50,2 +52,2 @@ fn tail\n-x\n+y\n*** End Patch";
+        let actions = Parser::new(content).parse().unwrap();
+        let chunks = &actions[0].chunks;
+
+        assert_eq!(chunks[0].orig_index, 41);
+        assert_eq!(chunks[0].change_context, None);
+        assert_eq!(chunks[1].orig_index, 49);
+        assert_eq!(chunks[1].change_context, None);
+        assert_eq!(chunks[1].context_hint.as_deref(), Some("fn tail"));
+    }
+
+    #[test]
+    fn test_hunk_range_header_variants() {
+        assert_eq!(Parser::parse_hunk_range("-3 +3 @@"), Some((2, "")));
+        assert_eq!(Parser::parse_hunk_range("-10,0 +11,2 @@"), Some((10, "")));
+        assert_eq!(Parser::parse_hunk_range("-0,0 +1 @@"), Some((0, "")));
+        assert_eq!(Parser::parse_hunk_range("fn main"), None);
+        assert_eq!(Parser::parse_hunk_range("-x,1 +1 @@"), None);
+        assert_eq!(Parser::parse_hunk_range("-1,1 +1,1"), None);
+    }
+
+    #[test]
+    fn test_parser_new_strips_bom() {
+        let parser = Parser::new("\u{FEFF}*** Begin Patch\n*** End Patch");
+        assert_eq!(parser.lines[0], "*** Begin Patch");
+    }
 }
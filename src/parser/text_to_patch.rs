@@ -7,7 +7,9 @@
 /// Parses patch text into a structured `PatchAction` object.
 ///
 /// Validates the patch format (start/end markers) and delegates the core parsing
-/// logic to the `Parser`. It expects the patch to contain exactly one file operation.
+/// logic to the `Parser`. Annotations are discarded; use
+/// [`crate::parser::text_to_patch_with_metadata::text_to_patch_with_metadata`]
+/// to keep them.
 ///
 /// # Arguments
 ///
@@ -21,92 +23,7 @@ pub fn text_to_patch(
     text: &str,
 ) -> std::result::Result<std::vec::Vec<crate::data::patch_action::PatchAction>, crate::error::ZenpatchError>
 {
-    let mut normalized = text.trim().to_string();
-
-    // LLMs routinely wrap the whole patch in a markdown code fence
-    // (```/```diff/```patch). Strip a leading fence line and, if present,
-    // the matching trailing fence line.
-    if normalized.starts_with("```") {
-        let mut lines: std::vec::Vec<&str> = normalized.lines().collect();
-        lines.remove(0);
-        if lines
-            .last()
-            .is_some_and(|l| l.trim() == "```")
-        {
-            lines.pop();
-        }
-        normalized = lines.join("\n").trim().to_string();
-    }
-
-    // LLMs routinely omit the Begin/End envelope and start straight with a
-    // file directive. When NEITHER marker is present and the text begins
-    // with a directive, the intent is unambiguous — wrap it implicitly.
-    // Deliberately narrow: if exactly one marker is present the patch is
-    // malformed or truncated (a missing '*** End Patch' after a present
-    // '*** Begin Patch' usually means the generation was cut off), and
-    // auto-repairing it could apply half a patch — keep failing loudly.
-    if (normalized.starts_with("*** Update File:")
-        || normalized.starts_with("*** Add File:")
-        || normalized.starts_with("*** Delete File:"))
-        && !normalized.contains("*** Begin Patch")
-        && !normalized.contains("*** End Patch")
-    {
-        normalized = std::format!("*** Begin Patch\n{normalized}\n*** End Patch");
-    }
-
-    let trimmed_text = normalized.as_str();
-
-    let lines: std::vec::Vec<&str> = trimmed_text.lines().collect();
-
-    if lines.len() < 2 {
-        return std::result::Result::Err(crate::error::ZenpatchError::InvalidPatchFormat(
-            "Patch text is too short (must include start and end markers).".to_string(),
-        ));
-    }
-    if lines[0] != "*** Begin Patch" {
-        return std::result::Result::Err(crate::error::ZenpatchError::InvalidPatchFormat(
-            "Patch must start with '*** Begin Patch'".to_string(),
-        ));
-    }
-    if lines[lines.len() - 1] != "*** End Patch" {
-        return std::result::Result::Err(crate::error::ZenpatchError::InvalidPatchFormat(
-            "Patch must end with '*** End Patch'".to_string(),
-        ));
-    }
-
-    let mut parser = crate::parser::parser::Parser::new(trimmed_text);
-    let mut actions = parser.parse()?;
-
-    // Post-process chunks to populate del_lines and ins_lines
-    for action in &mut actions {
-        for chunk in &mut action.chunks {
-            chunk.del_lines = chunk
-                .lines
-                .iter()
-                .filter_map(|(lt, content)| {
-                    if *lt == crate::data::line_type::LineType::Deletion {
-                        std::option::Option::Some(content.clone())
-                    } else {
-                        std::option::Option::None
-                    }
-                })
-                .collect();
-
-            chunk.ins_lines = chunk
-                .lines
-                .iter()
-                .filter_map(|(lt, content)| {
-                    if *lt == crate::data::line_type::LineType::Insertion {
-                        std::option::Option::Some(content.clone())
-                    } else {
-                        std::option::Option::None
-                    }
-                })
-                .collect();
-        }
-    }
-
-    std::result::Result::Ok(actions)
+    crate::parser::text_to_patch_with_metadata::text_to_patch_with_metadata(text).map(|(actions, _)| actions)
 }
 
 #[cfg(test)]
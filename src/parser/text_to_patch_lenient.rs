@@ -0,0 +1,66 @@
+//! Provides the `text_to_patch_lenient` function for parsing sloppy patch text.
+//!
+//! Same envelope handling as `text_to_patch`, but the body goes through
+//! `Parser::parse_lenient`, which recovers from common format deviations.
+//! Adheres to the one-item-per-file rule and uses fully qualified paths.
+
+/// Parses patch text like [`crate::parser::text_to_patch::text_to_patch`],
+/// applying the recovery rules of
+/// [`crate::parser::parser::Parser::parse_lenient`] even where the strict
+/// parser would succeed. Annotations are discarded.
+pub fn text_to_patch_lenient(
+    text: &str,
+) -> std::result::Result<std::vec::Vec<crate::data::patch_action::PatchAction>, crate::error::ZenpatchError>
+{
+    crate::parser::parse_patch_text::parse_patch_text(text, true).map(|(actions, _)| actions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::text_to_patch_lenient;
+    use crate::data::action_type::ActionType;
+    use crate::data::line_type::LineType;
+
+    #[test]
+    fn test_short_directives_are_accepted() {
+        let patch_text = "* Begin Patch\n* Update File: a.txt\n@@\n-old\n+new\n* End Patch";
+        let actions = text_to_patch_lenient(patch_text).unwrap();
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].type_, ActionType::Update);
+        assert_eq!(actions[0].path, "a.txt");
+    }
+
+    #[test]
+    fn test_equals_lines_become_context() {
+        let patch_text = "*** Begin Patch\n*** Update File: a.txt\n@@\n=ctx\n-old\n+new\n*** End Patch";
+        let actions = text_to_patch_lenient(patch_text).unwrap();
+        assert_eq!(actions[0].chunks[0].lines[0], (LineType::Context, "ctx".to_string()));
+    }
+
+    #[test]
+    fn test_unknown_directives_do_not_end_add_section() {
+        let patch_text = "*** Begin Patch\n*** Add File: a.txt\n+one\n*** Reviewed-by: someone\n+two\n*** End Patch";
+        let actions = text_to_patch_lenient(patch_text).unwrap();
+        assert_eq!(actions[0].chunks[0].ins_lines, vec!["one", "two"]);
+    }
+
+    #[test]
+    fn test_closing_hunk_marker_is_dropped_from_context() {
+        let patch_text = "*** Begin Patch\n*** Update File: a.txt\n@@ fn main @@\n-old\n+new\n*** End Patch";
+        let actions = text_to_patch_lenient(patch_text).unwrap();
+        assert_eq!(actions[0].chunks[0].change_context.as_deref(), Some("fn main"));
+    }
+
+    #[test]
+    fn test_text_to_patch_falls_back_to_lenient() {
+        let patch_text = "*** Begin Patch\n* Update File: a.txt\n@@\n-old\n+new\n*** End Patch";
+        let actions = crate::parser::text_to_patch::text_to_patch(patch_text).unwrap();
+        assert_eq!(actions, text_to_patch_lenient(patch_text).unwrap());
+    }
+
+    #[test]
+    fn test_missing_end_marker_still_fails() {
+        let patch_text = "* Begin Patch\n* Update File: a.txt\n@@\n-old\n+new";
+        assert!(text_to_patch_lenient(patch_text).is_err());
+    }
+}
@@ -0,0 +1,110 @@
+//! Provides the `text_to_patch_strict` function for validating patches.
+//!
+//! The opposite of lenient parsing: the patch must parse without recovery
+//! and every hunk must match its file exactly. Meant for linting patches
+//! (e.g. in CI) before they are stored.
+//! Adheres to the one-item-per-file rule and uses fully qualified paths.
+
+/// Parses `text` and checks it against `vfs`, rejecting anything the normal
+/// entry points only accept by being forgiving.
+///
+/// Fails when the patch needs the lenient parser (see
+/// [`crate::parser::text_to_patch_lenient::text_to_patch_lenient`]), when an
+/// `Update` section has hunk lines before its first `@@` header, or when the
+/// actions do not apply to `vfs` with [`crate::applier::whitespace_mode::WhitespaceMode::Strict`]
+/// matching. A hunk that only matches with a looser whitespace mode is a
+/// `PatchConflict` saying so; other application errors, ambiguity included,
+/// are returned as they are.
+pub fn text_to_patch_strict(
+    text: &str,
+    vfs: &crate::vfs::Vfs,
+) -> std::result::Result<std::vec::Vec<crate::data::patch_action::PatchAction>, crate::error::ZenpatchError> {
+    let (actions, _) = crate::parser::parse_patch_text::parse_patch_text(text, false)?;
+
+    let mut awaiting_header: std::option::Option<&str> = std::option::Option::None;
+    for line in text.lines() {
+        if let std::option::Option::Some(path) = line.strip_prefix("*** Update File:") {
+            awaiting_header = std::option::Option::Some(path.trim());
+        } else if line.starts_with("@@") || (line.starts_with("*** ") && !line.starts_with("*** Move to:")) {
+            awaiting_header = std::option::Option::None;
+        } else if let std::option::Option::Some(path) = awaiting_header {
+            if line.starts_with([' ', '-', '+']) {
+                return std::result::Result::Err(crate::error::ZenpatchError::InvalidPatchFormat(std::format!(
+                    "hunk lines in '*** Update File: {}' must follow an '@@' header",
+                    path
+                )));
+            }
+        }
+    }
+
+    let strict = crate::apply_options::ApplyOptions {
+        whitespace_mode: std::option::Option::Some(crate::applier::whitespace_mode::WhitespaceMode::Strict),
+        ..std::default::Default::default()
+    };
+    if let std::result::Result::Err(strict_err) = crate::apply::apply_actions(actions.clone(), vfs, &strict) {
+        let default = crate::apply_options::ApplyOptions::default();
+        if crate::apply::apply_actions(actions.clone(), vfs, &default).is_err() {
+            return std::result::Result::Err(strict_err);
+        }
+        let (path, chunk_index) = match &strict_err {
+            crate::error::ZenpatchError::PatchConflict { path, chunk_index, .. }
+            | crate::error::ZenpatchError::ContextNotFound { path, chunk_index, .. }
+            | crate::error::ZenpatchError::AmbiguousPatch { path, chunk_index, .. } => (path.clone(), *chunk_index),
+            _ => (std::string::String::new(), 0),
+        };
+        return std::result::Result::Err(crate::error::ZenpatchError::PatchConflict {
+            path,
+            chunk_index,
+            message: std::format!("the patch would require lenient mode to apply ({})", strict_err),
+        });
+    }
+
+    std::result::Result::Ok(actions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::text_to_patch_strict;
+
+    fn vfs() -> crate::vfs::Vfs {
+        let mut vfs = crate::vfs::Vfs::new();
+        vfs.insert("a.txt".to_string(), "fn main() {\n    old();\n}\n".to_string());
+        vfs
+    }
+
+    #[test]
+    fn test_exact_patch_is_accepted() {
+        let patch = "*** Begin Patch\n*** Update File: a.txt\n@@\n fn main() {\n-    old();\n+    new();\n*** End Patch";
+        assert_eq!(text_to_patch_strict(patch, &vfs()).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_whitespace_deviation_is_rejected() {
+        let patch = "*** Begin Patch\n*** Update File: a.txt\n@@\n fn main()  {\n-  old();\n+    new();\n*** End Patch";
+        assert!(crate::apply::apply(patch, &vfs()).is_ok());
+        match text_to_patch_strict(patch, &vfs()) {
+            Err(crate::error::ZenpatchError::PatchConflict { path, message, .. }) => {
+                assert_eq!(path, "a.txt");
+                assert!(message.contains("lenient mode"), "{}", message);
+            }
+            other => panic!("expected a conflict, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_missing_hunk_header_is_rejected() {
+        let patch = "*** Begin Patch\n*** Update File: a.txt\n fn main() {\n-    old();\n+    new();\n*** End Patch";
+        assert!(crate::apply::apply(patch, &vfs()).is_ok());
+        assert!(matches!(
+            text_to_patch_strict(patch, &vfs()),
+            Err(crate::error::ZenpatchError::InvalidPatchFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_lenient_parsing_is_not_used() {
+        let patch = "*** Begin Patch\n* Update File: a.txt\n@@\n-    old();\n+    new();\n*** End Patch";
+        assert!(crate::parser::text_to_patch::text_to_patch(patch).is_ok());
+        assert!(text_to_patch_strict(patch, &vfs()).is_err());
+    }
+}
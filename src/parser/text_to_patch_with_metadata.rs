@@ -0,0 +1,129 @@
+//! Provides `text_to_patch_with_metadata`, the core of patch-text parsing.
+//!
+//! Parses strictly, falling back to lenient parsing on a format error, and
+//! returns the actions alongside the patch's annotations.
+//! Adheres to the one-item-per-file rule and uses fully qualified paths.
+
+/// Parses patch text into its actions plus the `PatchMetadata` (comments and
+/// the `*** Author:` / `*** Date:` / `*** Subject:` / `*** Description:`
+/// headers before the first file directive) found along the way.
+///
+/// Accepts exactly what [`crate::parser::text_to_patch::text_to_patch`]
+/// accepts; `*** Comment: <text>` and `# <text>` lines never produce actions.
+/// Text the strict parser rejects with `InvalidPatchFormat` is retried with
+/// [`crate::parser::text_to_patch_lenient::text_to_patch_lenient`]'s rules.
+pub fn text_to_patch_with_metadata(
+    text: &str,
+) -> std::result::Result<
+    (std::vec::Vec<crate::data::patch_action::PatchAction>, crate::data::patch_metadata::PatchMetadata),
+    crate::error::ZenpatchError,
+> {
+    match crate::parser::parse_patch_text::parse_patch_text(text, false) {
+        // A malformed patch gets a second chance through the lenient parser;
+        // if that fails too, the strict parser's error is the useful one.
+        std::result::Result::Err(err @ crate::error::ZenpatchError::InvalidPatchFormat(_)) => {
+            crate::parser::parse_patch_text::parse_patch_text(text, true).map_err(|_| err)
+        }
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::text_to_patch_with_metadata;
+
+    #[test]
+    fn test_headers_are_parsed_before_first_directive() {
+        let patch_text = "*** Begin Patch\n\
+*** Author: Ada Lovelace <ada@example.com>\n\
+*** Date: 2024-03-01T12:00:00Z\n\
+*** Subject: Rename the helper\n\
+*** Description: Keeps call sites unchanged.\n\
+*** Update File: a.txt\n\
+@@\n\
+-old\n\
++new\n\
+*** Add File: b.txt\n\
++b\n\
+*** End Patch";
+        let (actions, metadata) = text_to_patch_with_metadata(patch_text).unwrap();
+        assert_eq!(actions.len(), 2);
+        assert_eq!(metadata.author.as_deref(), Some("Ada Lovelace <ada@example.com>"));
+        assert_eq!(metadata.date.as_deref(), Some("2024-03-01T12:00:00Z"));
+        assert_eq!(metadata.subject.as_deref(), Some("Rename the helper"));
+        assert_eq!(metadata.description.as_deref(), Some("Keeps call sites unchanged."));
+    }
+
+    #[test]
+    fn test_comments_are_collected_in_order() {
+        let patch_text = "*** Begin Patch\n\
+# generated for TICKET-12\n\
+*** Comment: rename the helper\n\
+*** Update File: a.txt\n\
+@@\n\
+-old\n\
+*** Comment: mid-hunk note\n\
++new\n\
+*** End Patch";
+        let (actions, metadata) = text_to_patch_with_metadata(patch_text).unwrap();
+        assert_eq!(actions.len(), 1);
+        assert_eq!(
+            metadata.comments,
+            vec!["generated for TICKET-12", "rename the helper", "mid-hunk note"]
+        );
+    }
+
+    #[test]
+    fn test_interleaved_comments_parse_identically() {
+        let plain = "*** Begin Patch\n\
+*** Add File: new.txt\n\
++one\n\
++two\n\
+*** Update File: a.txt\n\
+@@ fn main\n\
+ ctx\n\
+-old\n\
++new\n\
+*** Delete File: gone.txt\n\
+-bye\n\
+*** End Patch";
+        let commented = "*** Begin Patch\n\
+*** Comment: start\n\
+*** Add File: new.txt\n\
++one\n\
+*** Comment: inside add\n\
++two\n\
+# between sections\n\
+*** Update File: a.txt\n\
+*** Comment: before hunk\n\
+@@ fn main\n\
+ ctx\n\
+-old\n\
++new\n\
+*** Comment: before delete\n\
+*** Delete File: gone.txt\n\
+# inside delete\n\
+-bye\n\
+*** End Patch";
+
+        let (plain_actions, plain_metadata) = text_to_patch_with_metadata(plain).unwrap();
+        let (actions, metadata) = text_to_patch_with_metadata(commented).unwrap();
+
+        assert_eq!(actions, plain_actions);
+        assert!(plain_metadata.comments.is_empty());
+        assert_eq!(metadata.comments.len(), 6);
+    }
+
+    #[test]
+    fn test_comment_only_patch_is_rejected() {
+        let patch_text = "*** Begin Patch\n*** Comment: nothing to do\n*** End Patch";
+        assert!(text_to_patch_with_metadata(patch_text).is_err());
+    }
+
+    #[test]
+    fn test_leading_bom_is_stripped() {
+        let patch_text = "\u{FEFF}*** Begin Patch\n*** Add File: a.txt\n+a\n*** End Patch";
+        let (actions, _) = text_to_patch_with_metadata(patch_text).unwrap();
+        assert_eq!(actions[0].path, "a.txt");
+    }
+}
@@ -0,0 +1,878 @@
+//! Operations that transform parsed patches without applying them.
+//!
+//! These work on `PatchAction` lists and never modify a `Vfs`; the context
+//! rewriting passes read one to check that their output still applies.
+
+/// True when applying `actions` cannot change anything: every action is an
+/// in-place `Update` whose chunks are all context-only (see
+/// [`crate::data::chunk::Chunk::is_context_only`]). Adds, deletes, copies,
+/// renames and moves always count as changes.
+pub fn is_empty_patch(actions: &[crate::data::patch_action::PatchAction]) -> bool {
+    actions
+        .iter()
+        .all(|action| is_plain_update(action) && action.chunks.iter().all(crate::data::chunk::Chunk::is_context_only))
+}
+
+/// Sorts `actions` into a canonical order: by `path`, then by type
+/// (`Delete`, `Add`, `Update`, `Copy`, `Rename`), then by the `orig_index` of
+/// the first chunk. The sort is stable, so otherwise equal actions keep
+/// their relative order.
+///
+/// Reordering can change what a patch does when several actions touch the
+/// same file; use it to compare or merge independent action sets.
+pub fn sort_by_path(actions: &mut [crate::data::patch_action::PatchAction]) {
+    let rank = |action: &crate::data::patch_action::PatchAction| match action.type_ {
+        crate::data::action_type::ActionType::Delete => 0,
+        crate::data::action_type::ActionType::Add => 1,
+        crate::data::action_type::ActionType::Update => 2,
+        crate::data::action_type::ActionType::Copy => 3,
+        crate::data::action_type::ActionType::Rename => 4,
+    };
+    actions.sort_by(|a, b| {
+        a.path
+            .cmp(&b.path)
+            .then_with(|| rank(a).cmp(&rank(b)))
+            .then_with(|| first_orig_index(a).cmp(&first_orig_index(b)))
+    });
+}
+
+/// Removes every action equal to an earlier one, keeping the first of each,
+/// and returns how many were removed. Actions are compared in full only when
+/// their [`crate::data::patch_action::PatchAction::fingerprint`]s match.
+pub fn deduplicate_actions(actions: &mut std::vec::Vec<crate::data::patch_action::PatchAction>) -> usize {
+    let before = actions.len();
+    let mut seen: std::collections::HashMap<u64, std::vec::Vec<usize>> = std::collections::HashMap::new();
+    let mut kept: std::vec::Vec<crate::data::patch_action::PatchAction> = std::vec::Vec::with_capacity(before);
+    for action in actions.drain(..) {
+        let same_fingerprint = seen.entry(action.fingerprint()).or_default();
+        if !same_fingerprint.iter().any(|&i| kept[i] == action) {
+            same_fingerprint.push(kept.len());
+            kept.push(action);
+        }
+    }
+    *actions = kept;
+    before - actions.len()
+}
+
+fn first_orig_index(action: &crate::data::patch_action::PatchAction) -> usize {
+    action.chunks.first().map_or(0, |c| c.orig_index)
+}
+
+/// Removes every context-only chunk (see
+/// [`crate::data::chunk::Chunk::is_context_only`]) from `Update` and `Add`
+/// actions, then drops the in-place `Update`s this leaves without chunks.
+///
+/// An `Add` left without chunks creates an empty file; deletes, copies,
+/// renames and moves are always kept. Unlike [`normalize_patch`] this needs no
+/// VFS: a context-only chunk changes nothing wherever it applies.
+pub fn compress_patch(
+    actions: std::vec::Vec<crate::data::patch_action::PatchAction>,
+) -> std::vec::Vec<crate::data::patch_action::PatchAction> {
+    actions
+        .into_iter()
+        .filter_map(|mut action| {
+            if !std::matches!(
+                action.type_,
+                crate::data::action_type::ActionType::Update | crate::data::action_type::ActionType::Add
+            ) {
+                return std::option::Option::Some(action);
+            }
+            let had_chunks = !action.chunks.is_empty();
+            action.chunks.retain(|c| !c.is_context_only());
+            let emptied = had_chunks && action.chunks.is_empty() && is_plain_update(&action);
+            (!emptied).then_some(action)
+        })
+        .collect()
+}
+
+/// Drops leading and trailing context lines from every `Update` chunk as long
+/// as the chunk still matches exactly one position in its file (via the
+/// applier's own strict position search) and the action still produces the
+/// same file. Interior context and at least one context or deleted line per
+/// chunk are always kept, so no chunk loses its anchor.
+///
+/// `actions` are applied to a copy of `vfs` one after another, each chunk
+/// being minimised against the file as the preceding actions left it. Fails
+/// with the applier's error if `actions` do not apply to `vfs`.
+pub fn minimize_context(
+    actions: &[crate::data::patch_action::PatchAction],
+    vfs: &crate::vfs::Vfs,
+) -> std::result::Result<std::vec::Vec<crate::data::patch_action::PatchAction>, crate::error::ZenpatchError> {
+    rewrite_updates(actions, vfs, |action, lines, reproduces| {
+        for chunk_index in 0..action.chunks.len() {
+            for from_front in [true, false] {
+                while let std::option::Option::Some(candidate) = drop_context_line(action, chunk_index, from_front) {
+                    // Judged without the line-number hint, so the chunk stays
+                    // unique in text form, which does not carry it.
+                    let unique = crate::applier::backtracking_patcher::valid_positions_for_chunk(
+                        lines,
+                        &crate::data::chunk::Chunk { orig_index: 0, ..candidate.chunks[chunk_index].clone() },
+                        crate::applier::whitespace_mode::WhitespaceMode::Strict,
+                    )
+                    .len()
+                        == 1;
+                    if !unique || !reproduces(&candidate) {
+                        break;
+                    }
+                    *action = candidate;
+                }
+            }
+        }
+    })
+}
+
+/// Adds context lines read from `vfs` around every `Update` chunk until it
+/// has `target_context` lines before and after its changes (fewer at the
+/// start or end of the file). Chunks that do not match exactly one position
+/// in strict mode are left alone, as is any chunk whose widened form would
+/// change what the action produces, e.g. by reaching into a neighbouring
+/// chunk's deletions.
+///
+/// Like [`minimize_context`], `actions` are applied to a copy of `vfs` in
+/// order; fails with the applier's error if they do not apply.
+pub fn expand_context(
+    actions: &[crate::data::patch_action::PatchAction],
+    vfs: &crate::vfs::Vfs,
+    target_context: usize,
+) -> std::result::Result<std::vec::Vec<crate::data::patch_action::PatchAction>, crate::error::ZenpatchError> {
+    rewrite_updates(actions, vfs, |action, lines, reproduces| {
+        for chunk_index in 0..action.chunks.len() {
+            let chunk = &action.chunks[chunk_index];
+            let positions = crate::applier::backtracking_patcher::valid_positions_for_chunk(
+                lines,
+                chunk,
+                crate::applier::whitespace_mode::WhitespaceMode::Strict,
+            );
+            let [start] = positions[..] else { continue };
+            let leading = chunk.leading_context_count();
+            let trailing = chunk.trailing_context_count().min(chunk.lines.len() - leading);
+            let span = chunk
+                .lines
+                .iter()
+                .filter(|(lt, _)| {
+                    matches!(lt, crate::data::line_type::LineType::Context | crate::data::line_type::LineType::Deletion)
+                })
+                .count();
+            let before_start = start.saturating_sub(target_context.saturating_sub(leading));
+            let after_end = (start + span + target_context.saturating_sub(trailing)).min(lines.len());
+            let after_start = (start + span).min(after_end);
+
+            let mut candidate = action.clone();
+            let widened = &mut candidate.chunks[chunk_index];
+            let context = |line: &std::string::String| (crate::data::line_type::LineType::Context, line.clone());
+            widened.lines.splice(0..0, lines[before_start..start].iter().map(context));
+            widened.lines.extend(lines[after_start..after_end].iter().map(context));
+            widened.orig_index = widened.orig_index.saturating_sub(start - before_start);
+            if widened.lines.len() != action.chunks[chunk_index].lines.len() && reproduces(&candidate) {
+                *action = candidate;
+            }
+        }
+    })
+}
+
+/// Rewrites `actions` into a canonical form that applies to `vfs` exactly
+/// like the original, so equivalent patches can be compared or deduplicated.
+///
+/// In order, each `Update`: has its chunks sorted by `orig_index`, has its
+/// context minimised with [`minimize_context`], has trailing whitespace
+/// stripped from its context lines, has adjacent chunks merged with
+/// [`merge_adjacent_chunks`], loses chunks that change nothing, and finally
+/// has each chunk's `orig_index` set to the one line where it matches. A step
+/// that would change what the action produces is skipped for that action.
+/// Fails with the applier's error if `actions` do not apply to `vfs`.
+pub fn normalize_patch(
+    actions: std::vec::Vec<crate::data::patch_action::PatchAction>,
+    vfs: &crate::vfs::Vfs,
+) -> std::result::Result<std::vec::Vec<crate::data::patch_action::PatchAction>, crate::error::ZenpatchError> {
+    let try_step = |action: &mut crate::data::patch_action::PatchAction,
+                    reproduces: &dyn Fn(&crate::data::patch_action::PatchAction) -> bool,
+                    step: &dyn Fn(&mut crate::data::patch_action::PatchAction)| {
+        let mut candidate = action.clone();
+        step(&mut candidate);
+        if candidate != *action && reproduces(&candidate) {
+            *action = candidate;
+        }
+    };
+    let sorted = rewrite_updates(&actions, vfs, |action, _, reproduces| {
+        try_step(action, reproduces, &|candidate| candidate.chunks.sort_by_key(|c| c.orig_index));
+    })?;
+    let minimized = minimize_context(&sorted, vfs)?;
+    rewrite_updates(&minimized, vfs, |action, lines, reproduces| {
+        try_step(action, reproduces, &|candidate| {
+            for chunk in &mut candidate.chunks {
+                for (lt, content) in &mut chunk.lines {
+                    if *lt == crate::data::line_type::LineType::Context {
+                        content.truncate(content.trim_end().len());
+                    }
+                }
+            }
+        });
+        try_step(action, reproduces, &merge_adjacent_chunks);
+        try_step(action, reproduces, &|candidate| {
+            candidate.chunks.retain(|c| !c.is_context_only());
+        });
+        try_step(action, reproduces, &|candidate| {
+            // A chunk without context or deletions is placed by its hint alone.
+            let anchored = |chunk: &&mut crate::data::chunk::Chunk| {
+                chunk.lines.iter().any(|(lt, _)| {
+                    matches!(lt, crate::data::line_type::LineType::Context | crate::data::line_type::LineType::Deletion)
+                })
+            };
+            for chunk in candidate.chunks.iter_mut().filter(anchored) {
+                let unhinted = crate::data::chunk::Chunk { orig_index: 0, ..chunk.clone() };
+                let positions = crate::applier::backtracking_patcher::valid_positions_for_chunk(
+                    lines,
+                    &unhinted,
+                    crate::applier::whitespace_mode::WhitespaceMode::Strict,
+                );
+                if let [pos] = positions[..] {
+                    chunk.orig_index = pos;
+                }
+            }
+        });
+    })
+}
+
+/// Walks `actions` over a copy of `vfs`, letting `rewrite` edit each `Update`
+/// given the target file's current lines and a check that a candidate
+/// rewrite of the action produces the same VFS as the original action.
+fn rewrite_updates(
+    actions: &[crate::data::patch_action::PatchAction],
+    vfs: &crate::vfs::Vfs,
+    mut rewrite: impl FnMut(
+        &mut crate::data::patch_action::PatchAction,
+        &[std::string::String],
+        &dyn Fn(&crate::data::patch_action::PatchAction) -> bool,
+    ),
+) -> std::result::Result<std::vec::Vec<crate::data::patch_action::PatchAction>, crate::error::ZenpatchError> {
+    let options = crate::apply_options::ApplyOptions::default();
+    let mut current = vfs.clone();
+    let mut rewritten = std::vec::Vec::with_capacity(actions.len());
+
+    for action in actions {
+        let (expected, _) = crate::apply::apply_actions(std::vec![action.clone()], &current, &options)?;
+        let mut action = action.clone();
+        if action.type_ == crate::data::action_type::ActionType::Update {
+            if let std::option::Option::Some(content) = current.get(&action.path) {
+                let lines = crate::split_lines::split_lines(content);
+                let reproduces = |candidate: &crate::data::patch_action::PatchAction| {
+                    crate::apply::apply_actions(std::vec![candidate.clone()], &current, &options)
+                        .is_ok_and(|(vfs, _)| vfs == expected)
+                };
+                rewrite(&mut action, &lines, &reproduces);
+            }
+        }
+        current = expected;
+        rewritten.push(action);
+    }
+
+    std::result::Result::Ok(rewritten)
+}
+
+/// A copy of `action` with the first (or last) line of chunk `chunk_index`
+/// removed, if that line is context and the chunk keeps another context or
+/// deleted line to anchor it.
+fn drop_context_line(
+    action: &crate::data::patch_action::PatchAction,
+    chunk_index: usize,
+    from_front: bool,
+) -> std::option::Option<crate::data::patch_action::PatchAction> {
+    let lines = &action.chunks[chunk_index].lines;
+    let index = if from_front { 0 } else { lines.len().checked_sub(1)? };
+    if lines.get(index)?.0 != crate::data::line_type::LineType::Context {
+        return std::option::Option::None;
+    }
+    let anchors = lines
+        .iter()
+        .filter(|(lt, _)| {
+            matches!(lt, crate::data::line_type::LineType::Context | crate::data::line_type::LineType::Deletion)
+        })
+        .count();
+    if anchors < 2 {
+        return std::option::Option::None;
+    }
+    let mut candidate = action.clone();
+    let chunk = &mut candidate.chunks[chunk_index];
+    chunk.lines.remove(index);
+    if from_front {
+        chunk.orig_index += 1;
+    }
+    std::option::Option::Some(candidate)
+}
+
+/// Merges two patches into one action list equivalent to applying `a` and
+/// then `b`.
+///
+/// Actions are concatenated in order, except that an `Update` in `b` on a path
+/// that `a` also updates (neither renaming it) is folded into `a`'s action,
+/// provided that update is the last action touching the path. An `Update`
+/// whose path an earlier action deletes or renames away is a `PatchConflict`;
+/// one on a path recreated after `a`'s update is appended.
+///
+/// Folding needs line numbers: every chunk of both updates must have an
+/// `orig_index`, as populated from `@@ -n,m +n,m @@` headers (`0` means no
+/// line number was given). Otherwise `b`'s update is appended as its own
+/// action. `b`'s positions refer to the file after `a` and are mapped back
+/// onto the original before the chunks are interleaved by `orig_index`. A `b`
+/// chunk that [`crate::data::chunk::Chunk::overlaps_with`] one of `a`'s
+/// chunks, as that chunk lands in `a`'s output, is a `PatchConflict`.
+pub fn merge_patches(
+    a: &[crate::data::patch_action::PatchAction],
+    b: &[crate::data::patch_action::PatchAction],
+) -> std::result::Result<std::vec::Vec<crate::data::patch_action::PatchAction>, crate::error::ZenpatchError> {
+    let mut merged: std::vec::Vec<crate::data::patch_action::PatchAction> = a.to_vec();
+
+    for action in b {
+        if action.type_ == crate::data::action_type::ActionType::Update {
+            // The state of the path `action` updates is set by the last action touching it.
+            let last = merged.iter().rposition(|existing| {
+                existing.path == action.path || existing.new_path.as_deref() == std::option::Option::Some(&action.path)
+            });
+            if let std::option::Option::Some(index) = last {
+                if removes_path(&merged[index], &action.path) {
+                    return std::result::Result::Err(crate::error::ZenpatchError::PatchConflict {
+                        path: action.path.clone(),
+                        chunk_index: 0,
+                        message: "the file is deleted or renamed before this update; the patches cannot be merged"
+                            .to_string(),
+                    });
+                }
+                if index < a.len()
+                    && is_plain_update(&merged[index])
+                    && is_plain_update(action)
+                    && has_line_numbers(&merged[index])
+                    && has_line_numbers(action)
+                {
+                    let chunks = merge_chunks(&merged[index].chunks, &action.chunks, &action.path)?;
+                    merged[index].chunks = chunks;
+                    continue;
+                }
+            }
+        }
+        merged.push(action.clone());
+    }
+
+    std::result::Result::Ok(merged)
+}
+
+/// Merges consecutive chunks of `action` that describe adjacent or
+/// overlapping parts of the file into single chunks, so the backtracking
+/// search has fewer chunks to place.
+///
+/// When both chunks carry a line-number hint (`orig_index > 0`), the hints
+/// decide: the second must start inside or right after the first's span, and
+/// the overlapping lines must be identical context in both. Otherwise the
+/// first chunk's trailing context must be a prefix of the second's leading
+/// context, and those lines are taken to be the same file lines. A chunk with
+/// a `change_context` header or a `forced_position`, or following an
+/// end-of-file chunk, is never merged. `del_lines`/`ins_lines` are recomputed for merged chunks.
+pub fn merge_adjacent_chunks(action: &mut crate::data::patch_action::PatchAction) {
+    let mut merged: std::vec::Vec<crate::data::chunk::Chunk> = std::vec::Vec::with_capacity(action.chunks.len());
+    for chunk in std::mem::take(&mut action.chunks) {
+        match merged.last().and_then(|last| merge_two_chunks(last, &chunk)) {
+            std::option::Option::Some(combined) => *merged.last_mut().unwrap() = combined,
+            std::option::Option::None => merged.push(chunk),
+        }
+    }
+    action.chunks = merged;
+}
+
+/// The single chunk equivalent to `first` followed by `second`, if
+/// [`merge_adjacent_chunks`] considers them adjacent.
+pub(crate) fn merge_two_chunks(
+    first: &crate::data::chunk::Chunk,
+    second: &crate::data::chunk::Chunk,
+) -> std::option::Option<crate::data::chunk::Chunk> {
+    if first.is_end_of_file || second.change_context.is_some() || second.forced_position.is_some() {
+        return std::option::Option::None;
+    }
+    let trailing = first.trailing_context_count();
+    let leading = second.leading_context_count();
+
+    let overlap = if first.orig_index > 0 && second.orig_index > 0 {
+        let first_span = span(first, crate::data::line_type::LineType::Insertion);
+        let offset = second.orig_index.checked_sub(first.orig_index)?;
+        first_span.checked_sub(offset)?
+    } else {
+        if trailing == 0 {
+            return std::option::Option::None;
+        }
+        trailing
+    };
+    if overlap > trailing.min(leading)
+        || first.lines[first.lines.len() - overlap..] != second.lines[..overlap]
+    {
+        return std::option::Option::None;
+    }
+
+    let mut combined = crate::data::chunk::Chunk {
+        lines: first.lines.iter().chain(&second.lines[overlap..]).cloned().collect(),
+        is_end_of_file: second.is_end_of_file,
+        ..first.clone()
+    };
+    combined.recompute_del_ins_lines();
+    std::option::Option::Some(combined)
+}
+
+fn is_plain_update(action: &crate::data::patch_action::PatchAction) -> bool {
+    action.type_ == crate::data::action_type::ActionType::Update && action.new_path.is_none()
+}
+
+/// Whether every chunk of `action` carries a line number (`orig_index > 0`).
+fn has_line_numbers(action: &crate::data::patch_action::PatchAction) -> bool {
+    action.chunks.iter().all(|chunk| chunk.orig_index > 0)
+}
+
+/// Whether `path` no longer exists once `action` has applied.
+fn removes_path(action: &crate::data::patch_action::PatchAction, path: &str) -> bool {
+    action.path == path
+        && match action.type_ {
+            crate::data::action_type::ActionType::Delete => true,
+            crate::data::action_type::ActionType::Update | crate::data::action_type::ActionType::Rename => {
+                action.new_path.as_deref().is_some_and(|new_path| new_path != path)
+            }
+            _ => false,
+        }
+}
+
+/// Number of `lines` entries that are not of type `excluded`: the chunk's
+/// span in the original (`excluded = Insertion`) or new (`Deletion`) file.
+fn span(chunk: &crate::data::chunk::Chunk, excluded: crate::data::line_type::LineType) -> usize {
+    chunk.lines.iter().filter(|(lt, _)| *lt != excluded).count()
+}
+
+fn merge_chunks(
+    first: &[crate::data::chunk::Chunk],
+    second: &[crate::data::chunk::Chunk],
+    path: &str,
+) -> std::result::Result<std::vec::Vec<crate::data::chunk::Chunk>, crate::error::ZenpatchError> {
+    // Each of `first`'s chunks as it lands in the file it produces: reversed,
+    // so its range covers its leading context and inserted lines there.
+    let mut ordered_first: std::vec::Vec<&crate::data::chunk::Chunk> = first.iter().collect();
+    ordered_first.sort_by_key(|c| c.orig_index);
+    let mut delta: isize = 0;
+    let mut landed: std::vec::Vec<(crate::data::chunk::Chunk, isize)> = std::vec::Vec::new();
+    for chunk in ordered_first {
+        let mut reversed = reverse_chunk(chunk);
+        reversed.orig_index = (chunk.orig_index as isize + delta).max(0) as usize;
+        landed.push((reversed, chunk.net_line_delta()));
+        delta += chunk.net_line_delta();
+    }
+
+    let mut result = first.to_vec();
+    for (chunk_index, chunk) in second.iter().enumerate() {
+        let mut shift: isize = 0;
+        for (landed_chunk, chunk_delta) in &landed {
+            if chunk.overlaps_with(landed_chunk) {
+                let range = chunk.original_range();
+                return std::result::Result::Err(crate::error::ZenpatchError::PatchConflict {
+                    path: path.to_string(),
+                    chunk_index,
+                    message: std::format!(
+                        "lines {}..{} are also changed by the first patch; the patches cannot be merged",
+                        range.start + 1,
+                        range.end
+                    ),
+                });
+            }
+            if landed_chunk.original_range().end <= chunk.orig_index {
+                shift += chunk_delta;
+            }
+        }
+        let mut mapped = chunk.clone();
+        mapped.orig_index = (chunk.orig_index as isize - shift).max(0) as usize;
+        result.push(mapped);
+    }
+
+    // Stable: chunks sharing an index keep first-patch-then-second order.
+    result.sort_by_key(|c| c.orig_index);
+    std::result::Result::Ok(result)
+}
+
+/// Builds the patch that undoes `actions`: the actions in reverse order, each
+/// inverted. Update chunks swap their deleted and inserted lines (and a move
+/// goes back to its source), an `Add` becomes a `Delete` of the same lines and
+/// vice versa, and a `Rename` swaps its paths.
+///
+/// A `Copy` cannot be reversed because the copied content is not part of the
+/// patch; it is an `InvalidPatchFormat` error. The reverse of a patch that
+/// applied leniently re-inserts the patch's lines, not the file's, so it may
+/// not restore whitespace exactly.
+pub fn reverse_patch(
+    actions: &[crate::data::patch_action::PatchAction],
+) -> std::result::Result<std::vec::Vec<crate::data::patch_action::PatchAction>, crate::error::ZenpatchError> {
+    actions
+        .iter()
+        .rev()
+        .map(|action| {
+            let mut reversed = action.clone();
+            reversed.chunks = action.chunks.iter().map(reverse_chunk).collect();
+            match action.type_ {
+                crate::data::action_type::ActionType::Add => {
+                    reversed.type_ = crate::data::action_type::ActionType::Delete;
+                }
+                crate::data::action_type::ActionType::Delete => {
+                    reversed.type_ = crate::data::action_type::ActionType::Add;
+                }
+                crate::data::action_type::ActionType::Update | crate::data::action_type::ActionType::Rename => {
+                    if let std::option::Option::Some(new_path) = &action.new_path {
+                        reversed.path = new_path.clone();
+                        reversed.new_path = std::option::Option::Some(action.path.clone());
+                    }
+                }
+                crate::data::action_type::ActionType::Copy => {
+                    return std::result::Result::Err(crate::error::ZenpatchError::InvalidPatchFormat(format!(
+                        "cannot reverse the copy of {}: the copied content is not part of the patch",
+                        action.path
+                    )));
+                }
+            }
+            std::result::Result::Ok(reversed)
+        })
+        .collect()
+}
+
+/// `chunk` with its deletions and insertions swapped.
+pub(crate) fn reverse_chunk(chunk: &crate::data::chunk::Chunk) -> crate::data::chunk::Chunk {
+    let swap = |line_type: crate::data::line_type::LineType| match line_type {
+        crate::data::line_type::LineType::Deletion => crate::data::line_type::LineType::Insertion,
+        crate::data::line_type::LineType::Insertion => crate::data::line_type::LineType::Deletion,
+        other => other,
+    };
+    crate::data::chunk::Chunk {
+        lines: chunk.lines.iter().map(|(lt, content)| (swap(*lt), content.clone())).collect(),
+        del_lines: chunk.ins_lines.clone(),
+        ins_lines: chunk.del_lines.clone(),
+        ..chunk.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::merge_patches;
+
+    fn parse(text: &str) -> std::vec::Vec<crate::data::patch_action::PatchAction> {
+        crate::parser::text_to_patch::text_to_patch(text).unwrap()
+    }
+
+    fn vfs_with(path: &str, content: &str) -> crate::vfs::Vfs {
+        let mut vfs = crate::vfs::Vfs::new();
+        vfs.insert(path.to_string(), content.to_string());
+        vfs
+    }
+
+    #[test]
+    fn test_merge_patches_on_different_paths_concatenates() {
+        let a = parse("*** Begin Patch\n*** Add File: a.txt\n+a\n*** End Patch");
+        let b = parse("*** Begin Patch\n*** Add File: b.txt\n+b\n*** End Patch");
+        let merged = merge_patches(&a, &b).unwrap();
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].path, "a.txt");
+        assert_eq!(merged[1].path, "b.txt");
+    }
+
+    #[test]
+    fn test_merge_patches_same_file_matches_sequential_apply() {
+        let original = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n";
+        let a_text = "*** Begin Patch\n*** Update File: f.txt\n@@ -2,2 +2,3 @@\n 2\n+2.5\n 3\n*** End Patch";
+        // Line 8 of the original is line 9 after `a` inserts one line.
+        let b_text = "*** Begin Patch\n*** Update File: f.txt\n@@ -9,1 +9,1 @@\n-8\n+eight\n*** End Patch";
+
+        let merged = merge_patches(&parse(a_text), &parse(b_text)).unwrap();
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].chunks.len(), 2);
+        assert_eq!(merged[0].chunks[1].orig_index, 7);
+
+        let vfs = vfs_with("f.txt", original);
+        let sequential = crate::apply::apply(b_text, &crate::apply::apply(a_text, &vfs).unwrap()).unwrap();
+        let merged_vfs = crate::data::patch::Patch(merged).apply(&vfs).unwrap();
+        assert_eq!(merged_vfs, sequential);
+    }
+
+    #[test]
+    fn test_merge_patches_overlapping_ranges_conflict() {
+        let a = parse("*** Begin Patch\n*** Update File: f.txt\n@@ -3,2 +3,2 @@\n 3\n-4\n+four\n*** End Patch");
+        let b = parse("*** Begin Patch\n*** Update File: f.txt\n@@ -4,1 +4,1 @@\n-four\n+FOUR\n*** End Patch");
+        assert!(matches!(
+            merge_patches(&a, &b),
+            Err(crate::error::ZenpatchError::PatchConflict { chunk_index: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_merge_patches_change_in_trailing_context_matches_sequential_apply() {
+        let a_text = "*** Begin Patch\n*** Update File: f.txt\n@@ -2,3 +2,4 @@\n 2\n+2.5\n 3\n 4\n*** End Patch";
+        // Line 4 of the original, part of `a`'s trailing context, is line 5 after `a`.
+        let b_text = "*** Begin Patch\n*** Update File: f.txt\n@@ -5,1 +5,1 @@\n-4\n+four\n*** End Patch";
+
+        let merged = merge_patches(&parse(a_text), &parse(b_text)).unwrap();
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].chunks[1].orig_index, 3);
+
+        let vfs = vfs_with("f.txt", "1\n2\n3\n4\n5\n");
+        let sequential = crate::apply::apply(b_text, &crate::apply::apply(a_text, &vfs).unwrap()).unwrap();
+        assert_eq!(crate::data::patch::Patch(merged).apply(&vfs).unwrap(), sequential);
+    }
+
+    #[test]
+    fn test_merge_patches_without_line_numbers_appends_update() {
+        let a_text = "*** Begin Patch\n*** Update File: f.txt\n@@\n l1\n-l2\n+L2\n*** End Patch";
+        let b_text = "*** Begin Patch\n*** Update File: f.txt\n@@\n l8\n-l9\n+L9\n*** End Patch";
+
+        let merged = merge_patches(&parse(a_text), &parse(b_text)).unwrap();
+        assert_eq!(merged.len(), 2);
+
+        let vfs = vfs_with("f.txt", "l1\nl2\nl3\nl4\nl5\nl6\nl7\nl8\nl9\nl10\n");
+        let sequential = crate::apply::apply(b_text, &crate::apply::apply(a_text, &vfs).unwrap()).unwrap();
+        assert_eq!(crate::data::patch::Patch(merged).apply(&vfs).unwrap(), sequential);
+    }
+
+    #[test]
+    fn test_merge_patches_update_after_delete_or_rename_conflicts() {
+        let update = "*** Update File: f.txt\n@@ -1,1 +1,1 @@\n-1\n+one\n";
+        let b = parse("*** Begin Patch\n*** Update File: f.txt\n@@ -2,1 +2,1 @@\n-2\n+two\n*** End Patch");
+        for later in ["*** Delete File: f.txt\n-one\n-2\n", "*** Rename File: f.txt\n*** To: g.txt\n"] {
+            let a = parse(&format!("*** Begin Patch\n{}{}*** End Patch", update, later));
+            match merge_patches(&a, &b) {
+                Err(crate::error::ZenpatchError::PatchConflict { path, .. }) => assert_eq!(path, "f.txt"),
+                other => panic!("expected a PatchConflict after {:?}, got {:?}", later, other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_merge_patches_update_of_recreated_file_is_appended() {
+        let a_text = "*** Begin Patch\n*** Update File: f.txt\n@@ -1,1 +1,1 @@\n-1\n+one\n\
+                      *** Delete File: f.txt\n-one\n-2\n*** Add File: f.txt\n+new\n*** End Patch";
+        let b_text = "*** Begin Patch\n*** Update File: f.txt\n@@ -1,1 +1,1 @@\n-new\n+NEW\n*** End Patch";
+
+        let merged = merge_patches(&parse(a_text), &parse(b_text)).unwrap();
+        assert_eq!(merged.len(), 4);
+        assert_eq!(merged[0].chunks.len(), 1);
+
+        let vfs = vfs_with("f.txt", "1\n2\n");
+        let sequential = crate::apply::apply(b_text, &crate::apply::apply(a_text, &vfs).unwrap()).unwrap();
+        assert_eq!(crate::data::patch::Patch(merged).apply(&vfs).unwrap(), sequential);
+        assert_eq!(sequential["f.txt"], "NEW");
+    }
+
+    #[test]
+    fn test_is_empty_patch() {
+        let context_only = crate::parser::text_to_patch::text_to_patch(
+            "*** Begin Patch\n*** Update File: a.txt\n@@\n one\n two\n*** End Patch",
+        )
+        .unwrap();
+        assert!(super::is_empty_patch(&context_only));
+
+        let rename =
+            crate::parser::text_to_patch::text_to_patch("*** Begin Patch\n*** Rename File: a.txt\n*** To: b.txt\n*** End Patch")
+                .unwrap();
+        assert!(!super::is_empty_patch(&rename));
+
+        let change =
+            crate::parser::text_to_patch::text_to_patch("*** Begin Patch\n*** Update File: a.txt\n@@\n one\n+two\n*** End Patch")
+                .unwrap();
+        assert!(!super::is_empty_patch(&change));
+    }
+
+    #[test]
+    fn test_minimize_context_drops_redundant_lines() {
+        let vfs: crate::vfs::Vfs =
+            [("a.txt".to_string(), "one\ntwo\nthree\nfour\nfive\nsix\n".to_string())].into_iter().collect();
+        let actions = crate::parser::text_to_patch::text_to_patch(
+            "*** Begin Patch\n*** Update File: a.txt\n@@\n one\n two\n three\n-four\n+FOUR\n five\n six\n*** End Patch",
+        )
+        .unwrap();
+
+        let minimized = super::minimize_context(&actions, &vfs).unwrap();
+
+        let chunk = &minimized[0].chunks[0];
+        assert_eq!(chunk.lines.len(), 2, "only the changed lines are needed: {:?}", chunk.lines);
+        assert_eq!(
+            crate::apply::apply_actions(minimized.clone(), &vfs, &Default::default()).unwrap().0,
+            crate::apply::apply_actions(actions, &vfs, &Default::default()).unwrap().0
+        );
+    }
+
+    #[test]
+    fn test_minimize_context_keeps_context_needed_for_uniqueness() {
+        let vfs: crate::vfs::Vfs =
+            [("a.txt".to_string(), "a\nx\nb\nx\n".to_string())].into_iter().collect();
+        let actions = crate::parser::text_to_patch::text_to_patch(
+            "*** Begin Patch\n*** Update File: a.txt\n@@\n a\n b\n-x\n+y\n*** End Patch",
+        )
+        .unwrap();
+
+        let minimized = super::minimize_context(&actions, &vfs).unwrap();
+
+        let chunk = &minimized[0].chunks[0];
+        assert_eq!(chunk.lines[0], (crate::data::line_type::LineType::Context, "b".to_string()));
+        assert_eq!(
+            crate::apply::apply_actions(minimized.clone(), &vfs, &Default::default()).unwrap().0.get("a.txt").unwrap(),
+            "a\nx\nb\ny\n"
+        );
+    }
+
+    #[test]
+    fn test_sort_by_path_and_deduplicate_actions() {
+        let mut actions = parse(
+            "*** Begin Patch\n*** Update File: b.txt\n@@ -5,1 +5,1 @@\n-x\n+y\n*** Update File: b.txt\n@@ -2,1 +2,1 @@\n-p\n+q\n*** Add File: b.txt\n+b\n*** Delete File: a.txt\n-a\n*** Update File: b.txt\n@@ -5,1 +5,1 @@\n-x\n+y\n*** End Patch",
+        );
+        assert_eq!(super::deduplicate_actions(&mut actions), 1);
+        assert_eq!(actions.len(), 4);
+
+        super::sort_by_path(&mut actions);
+        let order: Vec<(&str, usize)> =
+            actions.iter().map(|a| (a.path.as_str(), a.chunks.first().map_or(0, |c| c.orig_index))).collect();
+        assert_eq!(order, vec![("a.txt", 0), ("b.txt", 0), ("b.txt", 1), ("b.txt", 4)]);
+        assert_eq!(actions[1].type_, crate::data::action_type::ActionType::Add);
+    }
+
+    #[test]
+    fn test_compress_patch_drops_context_only_chunks_and_actions() {
+        let vfs: crate::vfs::Vfs = [
+            ("a.txt".to_string(), "one\ntwo\nthree\n".to_string()),
+            ("b.txt".to_string(), "b\n".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        let actions = parse(
+            "*** Begin Patch\n*** Update File: a.txt\n@@\n one\n@@\n-two\n+TWO\n*** Update File: b.txt\n@@\n b\n*** Delete File: b.txt\n-b\n*** End Patch",
+        );
+
+        let compressed = super::compress_patch(actions.clone());
+        assert_eq!(compressed.len(), 2);
+        assert_eq!(compressed[0].chunks.len(), 1);
+        assert_eq!(compressed[1].type_, crate::data::action_type::ActionType::Delete);
+        assert_eq!(
+            crate::apply::apply_actions(compressed, &vfs, &Default::default()).unwrap().0,
+            crate::apply::apply_actions(actions, &vfs, &Default::default()).unwrap().0
+        );
+    }
+
+    #[test]
+    fn test_normalize_patch_gives_equivalent_patches_one_form() {
+        let vfs: crate::vfs::Vfs =
+            [("a.txt".to_string(), "one\ntwo\nthree\nfour\nfive\nsix\n".to_string())].into_iter().collect();
+        let wide = parse(
+            "*** Begin Patch\n*** Update File: a.txt\n@@\n one  \n two\n-three\n+THREE\n four\n@@\n five\n six\n*** End Patch",
+        );
+        let narrow = parse("*** Begin Patch\n*** Update File: a.txt\n@@\n two\n-three\n+THREE\n*** End Patch");
+
+        let normalized = super::normalize_patch(wide.clone(), &vfs).unwrap();
+        assert_eq!(normalized, super::normalize_patch(narrow, &vfs).unwrap());
+        assert_eq!(normalized[0].chunks.len(), 1);
+        assert_eq!(
+            crate::apply::apply_actions(normalized, &vfs, &Default::default()).unwrap().0,
+            crate::apply::apply_actions(wide, &vfs, &Default::default()).unwrap().0
+        );
+    }
+
+    #[test]
+    fn test_expand_context_adds_surrounding_lines() {
+        let vfs: crate::vfs::Vfs =
+            [("a.txt".to_string(), "one\ntwo\nthree\nfour\nfive\nsix\n".to_string())].into_iter().collect();
+        let actions = crate::parser::text_to_patch::text_to_patch(
+            "*** Begin Patch\n*** Update File: a.txt\n@@\n-four\n+FOUR\n*** End Patch",
+        )
+        .unwrap();
+
+        let expanded = super::expand_context(&actions, &vfs, 2).unwrap();
+
+        let chunk = &expanded[0].chunks[0];
+        let texts: Vec<&str> = chunk.into_iter().map(|(_, l)| l).collect();
+        assert_eq!(texts, vec!["two", "three", "four", "FOUR", "five", "six"]);
+        assert_eq!(chunk.del_lines, vec!["four"]);
+        assert_eq!(chunk.ins_lines, vec!["FOUR"]);
+        chunk.validate().unwrap();
+        assert_eq!(
+            crate::apply::apply_actions(expanded.clone(), &vfs, &Default::default()).unwrap().0,
+            crate::apply::apply_actions(actions, &vfs, &Default::default()).unwrap().0
+        );
+    }
+
+    #[test]
+    fn test_expand_context_clamps_at_file_edges() {
+        let vfs: crate::vfs::Vfs = [("a.txt".to_string(), "one\ntwo\n".to_string())].into_iter().collect();
+        let actions = crate::parser::text_to_patch::text_to_patch(
+            "*** Begin Patch\n*** Update File: a.txt\n@@\n-one\n+ONE\n*** End Patch",
+        )
+        .unwrap();
+
+        let expanded = super::expand_context(&actions, &vfs, 3).unwrap();
+
+        let texts: Vec<&str> = (&expanded[0].chunks[0]).into_iter().map(|(_, l)| l).collect();
+        assert_eq!(texts, vec!["one", "ONE", "two"]);
+    }
+
+    #[test]
+    fn test_reverse_patch_undoes_update_add_delete_and_rename() {
+        let mut vfs = crate::vfs::Vfs::new();
+        vfs.insert("a.txt".to_string(), "one\ntwo\nthree\n".to_string());
+        vfs.insert("old.txt".to_string(), "x\ny".to_string());
+        vfs.insert("r.txt".to_string(), "r\n".to_string());
+        let actions = parse(
+            "*** Begin Patch\n*** Update File: a.txt\n*** Move to: b.txt\n@@\n one\n-two\n+TWO\n+2\n\
+             *** Add File: new.txt\n+n\n*** Delete File: old.txt\n-x\n-y\n\
+             *** Rename File: r.txt\n*** To: s.txt\n*** End Patch",
+        );
+
+        let (patched, _) = crate::apply::apply_actions(actions.clone(), &vfs, &Default::default()).unwrap();
+        let reversed = super::reverse_patch(&actions).unwrap();
+        let (restored, _) = crate::apply::apply_actions(reversed, &patched, &Default::default()).unwrap();
+
+        assert_eq!(restored, vfs);
+    }
+
+    #[test]
+    fn test_reverse_patch_rejects_copy() {
+        let actions = parse("*** Begin Patch\n*** Copy File: a.txt\n*** To: b.txt\n*** End Patch");
+        assert!(matches!(
+            super::reverse_patch(&actions),
+            Err(crate::error::ZenpatchError::InvalidPatchFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_merge_adjacent_chunks_by_shared_context() {
+        let mut action = parse(
+            "*** Begin Patch\n*** Update File: a.txt\n@@\n a\n-b\n+B\n c\n@@\n c\n d\n-e\n+E\n*** End Patch",
+        )
+        .remove(0);
+        super::merge_adjacent_chunks(&mut action);
+
+        assert_eq!(action.chunks.len(), 1);
+        let texts: Vec<&str> = (&action.chunks[0]).into_iter().map(|(_, l)| l).collect();
+        assert_eq!(texts, vec!["a", "b", "B", "c", "d", "e", "E"]);
+        assert_eq!(action.chunks[0].del_lines, vec!["b", "e"]);
+        assert_eq!(action.chunks[0].ins_lines, vec!["B", "E"]);
+    }
+
+    #[test]
+    fn test_merge_adjacent_chunks_by_line_hints() {
+        let mut action = parse(
+            "*** Begin Patch\n*** Update File: a.txt\n@@ -2,2 +2,2 @@\n a\n-b\n+B\n@@ -4,2 +4,2 @@\n c\n-d\n+D\n*** End Patch",
+        )
+        .remove(0);
+        super::merge_adjacent_chunks(&mut action);
+        assert_eq!(action.chunks.len(), 1);
+        assert_eq!(action.chunks[0].orig_index, 1);
+
+        let mut apart = parse(
+            "*** Begin Patch\n*** Update File: a.txt\n@@ -2,2 +2,2 @@\n a\n-b\n+B\n@@ -9,2 +9,2 @@\n c\n-d\n+D\n*** End Patch",
+        )
+        .remove(0);
+        super::merge_adjacent_chunks(&mut apart);
+        assert_eq!(apart.chunks.len(), 2);
+    }
+
+    #[test]
+    fn test_merge_adjacent_chunks_leaves_unrelated_chunks() {
+        let mut action = parse(
+            "*** Begin Patch\n*** Update File: a.txt\n@@\n a\n-b\n+B\n@@\n x\n-y\n+Y\n*** End Patch",
+        )
+        .remove(0);
+        super::merge_adjacent_chunks(&mut action);
+        assert_eq!(action.chunks.len(), 2);
+    }
+}
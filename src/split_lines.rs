@@ -0,0 +1,52 @@
+//! Provides the `split_lines` function: line splitting for file content.
+//!
+//! Unlike `str::lines`, this recognizes all three line terminators — `\n`,
+//! `\r\n`, and a lone `\r` (classic Mac OS) — so legacy `\r`-only files are
+//! split into lines instead of being treated as one long line.
+//! Conforms to rust coding guidelines (one item per file).
+
+/// Splits `content` into lines on `\n`, `\r\n`, or a lone `\r`.
+///
+/// Terminators are not included in the returned lines, and a final terminator
+/// does not produce a trailing empty line (matching `str::lines`).
+pub fn split_lines(content: &str) -> std::vec::Vec<std::string::String> {
+    let mut lines = std::vec::Vec::new();
+    let mut rest = content;
+    while !rest.is_empty() {
+        match rest.find(['\n', '\r']) {
+            std::option::Option::Some(idx) => {
+                lines.push(rest[..idx].to_string());
+                let terminator_len = if rest[idx..].starts_with("\r\n") { 2 } else { 1 };
+                rest = &rest[idx + terminator_len..];
+            }
+            std::option::Option::None => {
+                lines.push(rest.to_string());
+                rest = "";
+            }
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::split_lines;
+
+    #[test]
+    fn test_split_lines_all_terminators() {
+        assert_eq!(split_lines("a\nb\r\nc\rd"), vec!["a", "b", "c", "d"]);
+    }
+
+    #[test]
+    fn test_split_lines_cr_only() {
+        assert_eq!(split_lines("a\rb\r"), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_split_lines_matches_str_lines_for_lf_and_crlf() {
+        for text in ["", "\n", "a", "a\n", "a\n\nb", "a\r\n\r\nb\r\n"] {
+            let expected: std::vec::Vec<&str> = text.lines().collect();
+            assert_eq!(split_lines(text), expected, "input {text:?}");
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! Defines the `PatchSummary` struct describing what a successful apply changed.
+//!
+//! Returned by [`crate::apply::apply_with_summary`] alongside the patched VFS so
+//! callers (typically AI agent pipelines) get feedback without re-diffing the
+//! result. Conforms to rust coding guidelines (one item per file).
+
+/// Aggregate counts for a successfully applied patch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchSummary {
+    /// Number of files created by `Add File` and `Copy File` actions.
+    pub files_added: usize,
+    /// Number of files removed by `Delete File` actions.
+    pub files_deleted: usize,
+    /// Number of files changed by `Update File` actions (including renames)
+    /// and moved by `Rename File` actions.
+    pub files_updated: usize,
+    /// Total lines inserted across all files.
+    pub lines_inserted: usize,
+    /// Total lines deleted across all files.
+    pub lines_deleted: usize,
+    /// The most lenient whitespace mode any file needed to apply; `Strict`
+    /// when every hunk matched exactly (or nothing was updated).
+    pub whitespace_mode_used: crate::applier::whitespace_mode::WhitespaceMode,
+    /// One entry per applied action, in patch order.
+    pub per_file: std::vec::Vec<crate::file_summary::FileSummary>,
+    /// Non-fatal issues met while applying, each prefixed with its file path
+    /// (e.g. an ambiguity accepted under `AmbiguityPolicy::AcceptFirst`).
+    pub warnings: std::vec::Vec<std::string::String>,
+}
+
+impl PatchSummary {
+    /// An empty summary: nothing changed, strict matching.
+    pub fn new() -> Self {
+        Self {
+            files_added: 0,
+            files_deleted: 0,
+            files_updated: 0,
+            lines_inserted: 0,
+            lines_deleted: 0,
+            whitespace_mode_used: crate::applier::whitespace_mode::WhitespaceMode::Strict,
+            per_file: std::vec::Vec::new(),
+            warnings: std::vec::Vec::new(),
+        }
+    }
+
+    /// Records one applied action, updating the aggregate counts.
+    pub fn record(&mut self, file: crate::file_summary::FileSummary) {
+        match file.action {
+            crate::data::action_type::ActionType::Add | crate::data::action_type::ActionType::Copy => {
+                self.files_added += 1
+            }
+            crate::data::action_type::ActionType::Delete => self.files_deleted += 1,
+            crate::data::action_type::ActionType::Update | crate::data::action_type::ActionType::Rename => {
+                self.files_updated += 1
+            }
+        }
+        self.lines_inserted += file.lines_inserted;
+        self.lines_deleted += file.lines_deleted;
+        self.whitespace_mode_used = self.whitespace_mode_used.max(file.whitespace_mode_used);
+        self.per_file.push(file);
+    }
+}
+
+impl std::default::Default for PatchSummary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::applier::whitespace_mode::WhitespaceMode;
+    use crate::data::action_type::ActionType;
+    use crate::file_summary::FileSummary;
+
+    #[test]
+    fn test_record_aggregates_counts_and_most_lenient_mode() {
+        let mut summary = super::PatchSummary::new();
+        summary.record(FileSummary {
+            path: "a.txt".into(),
+            action: ActionType::Update,
+            lines_inserted: 2,
+            lines_deleted: 1,
+            whitespace_mode_used: WhitespaceMode::Lenient,
+        });
+        summary.record(FileSummary {
+            path: "b.txt".into(),
+            action: ActionType::Add,
+            lines_inserted: 3,
+            lines_deleted: 0,
+            whitespace_mode_used: WhitespaceMode::Strict,
+        });
+        assert_eq!(summary.files_updated, 1);
+        assert_eq!(summary.files_added, 1);
+        assert_eq!(summary.files_deleted, 0);
+        assert_eq!(summary.lines_inserted, 5);
+        assert_eq!(summary.lines_deleted, 1);
+        assert_eq!(summary.whitespace_mode_used, WhitespaceMode::Lenient);
+        assert_eq!(summary.per_file.len(), 2);
+    }
+}
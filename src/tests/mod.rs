@@ -1,4 +1,8 @@
 //! Test module for zenpatch.
 
+#[macro_use]
+pub mod test_macros;
+
 mod ported_apply_tests;
 mod large_file_tests;
+mod property_test_apply_reverse_roundtrip;
@@ -13,26 +13,16 @@ fn vfs_from_str(path: &str, content: &str) -> Vfs {
 #[test]
 fn test_update_file_multiple_chunks() {
     let patch = "*** Begin Patch\n*** Update File: multi.txt\n@@\n foo\n-bar\n+BAR\n@@\n baz\n-qux\n+QUX\n*** End Patch";
-    let vfs = vfs_from_str("multi.txt", "foo\nbar\nbaz\nqux");
-    let result_vfs = apply(patch, &vfs).unwrap();
-    assert_eq!(result_vfs.get("multi.txt").unwrap(), "foo\nBAR\nbaz\nQUX");
+    assert_patch_applies!(patch, "foo\nbar\nbaz\nqux", "foo\nBAR\nbaz\nQUX");
 }
 
 #[test]
 fn test_patch_fails_on_unicode_near_miss() {
-    // Initial content uses GREEK CAPITAL LETTER ALPHA (U+0391), not LATIN 'A'
-    let vfs = vfs_from_str("test.txt", "Line Alpha: Α\nLine Next");
-    // Patch context mistakenly uses LATIN 'A' (U+0041) instead of Greek Alpha
+    // Patch context mistakenly uses LATIN 'A' (U+0041) instead of the
+    // GREEK CAPITAL LETTER ALPHA (U+0391) in the file. Strict and lenient
+    // matching should both fail.
     let patch = "*** Begin Patch\n*** Update File: test.txt\n@@\n Line Alpha: A\n-Line Next\n+Modified Line Next\n*** End Patch";
-
-    let result = apply(patch, &vfs);
-
-    // Patch should fail due to exact context mismatch. It will retry with lenient, which should also fail.
-    assert!(result.is_err(), "Patch should have failed due to Unicode character mismatch");
-    match result.unwrap_err() {
-        ZenpatchError::PatchConflict(_) => (), // This is expected
-        e => panic!("Expected PatchConflict, got {:?}", e),
-    }
+    assert_patch_conflicts!(patch, "Line Alpha: Α\nLine Next");
 }
 
 #[test]
@@ -47,16 +37,17 @@ fn test_patch_idempotent_deletion_fails_on_second_apply() {
     // Second application should fail because the context "-LineToDelete" is gone
     let result2 = apply(patch, &result_vfs1);
     assert!(result2.is_err(), "Second patch application should have failed (context not found)");
-    assert!(matches!(result2.unwrap_err(), ZenpatchError::PatchConflict(_)));
+    assert!(matches!(result2.unwrap_err(), ZenpatchError::PatchConflict { .. }));
 }
 
 #[test]
 fn test_patch_repeated_context_close_proximity() {
-    let vfs = vfs_from_str("test.txt", "Marker\nTarget\nMarker\nOther Target\nMarker");
     let patch = "*** Begin Patch\n*** Update File: test.txt\n@@\n Marker\n-Target\n+Modified Target\n Marker\n*** End Patch";
-
-    let result_vfs = apply(patch, &vfs).unwrap();
-    assert_eq!(result_vfs.get("test.txt").unwrap(), "Marker\nModified Target\nMarker\nOther Target\nMarker");
+    assert_patch_applies!(
+        patch,
+        "Marker\nTarget\nMarker\nOther Target\nMarker",
+        "Marker\nModified Target\nMarker\nOther Target\nMarker"
+    );
 }
 
 #[test]
@@ -0,0 +1,72 @@
+//! Property test: for a patch `P` that turns a VFS `V` into `V'`,
+//! `reverse_patch(P)` turns `V'` back into `V`.
+//!
+//! Runs with `cargo test`; set `PROPTEST_CASES` to try more cases.
+
+use proptest::prelude::*;
+
+/// A generated VFS and an `Update` patch that applies to it.
+#[derive(Debug, Clone)]
+struct Case {
+    vfs: crate::vfs::Vfs,
+    patch_text: String,
+}
+
+/// One chunk before it is fitted to a file: where in its segment the
+/// deletion starts, how many lines it deletes, and the words it inserts.
+type ChunkSeed = (usize, usize, Vec<String>);
+
+/// 2–5 files of 5–20 lines, each updated by 1–3 chunks deleting and
+/// inserting 1–3 lines. Every line is unique, so each chunk (and its
+/// reverse) matches exactly one place.
+fn case_strategy() -> impl Strategy<Value = Case> {
+    let word = "[a-z]{1,6}";
+    let chunk = (any::<usize>(), 1usize..=3, prop::collection::vec(word, 1..=3));
+    let file = (prop::collection::vec(word, 5..=20), prop::collection::vec(chunk, 1..=3));
+    prop::collection::vec(file, 2..=5).prop_map(|files| build_case(&files))
+}
+
+fn build_case(files: &[(Vec<String>, Vec<ChunkSeed>)]) -> Case {
+    let mut vfs = crate::vfs::Vfs::new();
+    let mut patch_text = String::from("*** Begin Patch\n");
+    for (file_index, (words, chunks)) in files.iter().enumerate() {
+        let path = format!("file{file_index}.txt");
+        let lines: Vec<String> =
+            words.iter().enumerate().map(|(i, w)| format!("f{file_index} l{i} {w}")).collect();
+        vfs.insert(path.clone(), lines.join("\n") + "\n");
+
+        // Each chunk stays inside its own segment so chunks never overlap.
+        patch_text.push_str(&format!("*** Update File: {path}\n"));
+        let segment = lines.len() / chunks.len();
+        for (chunk_index, (offset, del_len, inserted)) in chunks.iter().enumerate() {
+            let segment_start = chunk_index * segment;
+            let start = segment_start + offset % segment;
+            let end = (start + del_len).min(segment_start + segment);
+            patch_text.push_str("@@\n");
+            if start > segment_start {
+                patch_text.push_str(&format!(" {}\n", lines[start - 1]));
+            }
+            for line in &lines[start..end] {
+                patch_text.push_str(&format!("-{line}\n"));
+            }
+            for (i, w) in inserted.iter().enumerate() {
+                patch_text.push_str(&format!("+ins f{file_index} c{chunk_index} {i} {w}\n"));
+            }
+        }
+    }
+    patch_text.push_str("*** End Patch");
+    Case { vfs, patch_text }
+}
+
+proptest! {
+    #[test]
+    fn property_test_apply_reverse_roundtrip(case in case_strategy()) {
+        let actions = crate::parser::text_to_patch::text_to_patch(&case.patch_text).unwrap();
+        let patched = crate::data::patch::Patch(actions.clone()).apply(&case.vfs).unwrap();
+        prop_assert_ne!(&patched, &case.vfs);
+
+        let reversed = crate::patch_ops::reverse_patch(&actions).unwrap();
+        let restored = crate::data::patch::Patch(reversed).apply(&patched).unwrap();
+        prop_assert_eq!(restored, case.vfs);
+    }
+}
@@ -0,0 +1,99 @@
+//! Assertion macros and fixtures for patch tests.
+//!
+//! Each macro builds a one-file VFS holding `initial_content` under the path
+//! of the patch's first action, runs [`crate::apply`], and panics with the
+//! actual error or content when the expectation does not hold. [`lines`] and
+//! [`chunks`] build the inputs of the chunk-level appliers directly.
+
+/// Path of the first action in `patch`, panicking if it does not parse.
+pub fn first_path(patch: &str) -> std::string::String {
+    match crate::parser::text_to_patch::text_to_patch(patch) {
+        std::result::Result::Ok(actions) if !actions.is_empty() => actions[0].path.clone(),
+        other => std::panic!("test patch does not parse to any action: {:?}", other),
+    }
+}
+
+/// Applies `patch` to a VFS with `initial_content` at the patch's first path.
+pub fn apply_to_content(
+    patch: &str,
+    initial_content: &str,
+) -> (std::string::String, std::result::Result<crate::vfs::Vfs, crate::error::ZenpatchError>) {
+    let path = first_path(patch);
+    let mut vfs = crate::vfs::Vfs::new();
+    vfs.insert(path.clone(), initial_content.to_string());
+    let result = crate::apply(patch, &vfs);
+    (path, result)
+}
+
+/// `text` split into lines, as the appliers receive a file.
+pub fn lines(text: &str) -> std::vec::Vec<std::string::String> {
+    text.lines().map(std::string::String::from).collect()
+}
+
+/// The chunks of a one-file update whose body (everything after the
+/// `*** Update File:` header) is `patch_body`.
+pub fn chunks(patch_body: &str) -> std::vec::Vec<crate::data::chunk::Chunk> {
+    let text = std::format!("*** Begin Patch\n*** Update File: f\n{}\n*** End Patch", patch_body);
+    crate::parser::text_to_patch::text_to_patch(&text).unwrap().remove(0).chunks
+}
+
+/// Asserts that `patch` applies to `initial_content` and yields `expected`.
+macro_rules! assert_patch_applies {
+    ($patch:expr, $initial:expr, $expected:expr $(,)?) => {{
+        let (path, result) = $crate::tests::test_macros::apply_to_content($patch, $initial);
+        match result {
+            ::std::result::Result::Ok(vfs) => ::std::assert_eq!(
+                vfs.get(&path).map(::std::string::String::as_str),
+                ::std::option::Option::Some($expected),
+                "patch applied but `{}` has unexpected content",
+                path
+            ),
+            ::std::result::Result::Err(e) => ::std::panic!("expected the patch to apply to `{}`, got error: {}", path, e),
+        }
+    }};
+}
+
+/// Asserts that applying `patch` to `initial_content` is a `PatchConflict`.
+macro_rules! assert_patch_conflicts {
+    ($patch:expr, $initial:expr $(,)?) => {{
+        let (path, result) = $crate::tests::test_macros::apply_to_content($patch, $initial);
+        match result {
+            ::std::result::Result::Err($crate::error::ZenpatchError::PatchConflict { .. }) => {}
+            ::std::result::Result::Err(e) => ::std::panic!("expected a PatchConflict, got error: {:?}", e),
+            ::std::result::Result::Ok(vfs) => ::std::panic!(
+                "expected a PatchConflict, but the patch applied; `{}` is now: {:?}",
+                path,
+                vfs.get(&path)
+            ),
+        }
+    }};
+}
+
+/// Asserts that applying `patch` to `initial_content` is an `AmbiguousPatch`.
+macro_rules! assert_patch_ambiguous {
+    ($patch:expr, $initial:expr $(,)?) => {{
+        let (path, result) = $crate::tests::test_macros::apply_to_content($patch, $initial);
+        match result {
+            ::std::result::Result::Err($crate::error::ZenpatchError::AmbiguousPatch { .. }) => {}
+            ::std::result::Result::Err(e) => ::std::panic!("expected an AmbiguousPatch, got error: {:?}", e),
+            ::std::result::Result::Ok(vfs) => ::std::panic!(
+                "expected an AmbiguousPatch, but the patch applied; `{}` is now: {:?}",
+                path,
+                vfs.get(&path)
+            ),
+        }
+    }};
+}
+
+#[test]
+fn test_macros_cover_each_outcome() {
+    assert_patch_applies!("*** Begin Patch\n*** Update File: a.txt\n@@\n a\n-b\n+B\n*** End Patch", "a\nb\n", "a\nB\n");
+    assert_patch_conflicts!("*** Begin Patch\n*** Update File: a.txt\n@@\n zzz\n-b\n*** End Patch", "a\nb\n");
+    assert_patch_ambiguous!("*** Begin Patch\n*** Update File: a.txt\n@@\n a\n-b\n*** End Patch", "a\nb\na\nb\n");
+}
+
+#[test]
+#[should_panic(expected = "expected a PatchConflict, but the patch applied")]
+fn test_conflict_macro_reports_applied_content() {
+    assert_patch_conflicts!("*** Begin Patch\n*** Update File: a.txt\n@@\n a\n-b\n*** End Patch", "a\nb\n");
+}
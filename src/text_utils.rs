@@ -0,0 +1,117 @@
+//! Public line-normalization helpers shared with the backtracking patcher.
+//!
+//! These are the exact transformations the patcher applies when comparing a
+//! patch line to a file line in its non-strict whitespace modes, exposed so
+//! tools built on zenpatch can compare lines the same way.
+
+/// Collapses whitespace: trims both ends and replaces every internal run of
+/// whitespace (spaces, tabs, newlines, any Unicode `White_Space`) with a
+/// single ASCII space. This is the comparison key of `WhitespaceMode::Lenient`.
+pub fn normalize(s: &str) -> std::string::String {
+    s.split_whitespace().collect::<std::vec::Vec<_>>().join(" ")
+}
+
+/// Trims both ends and maps typographic look-alikes to their ASCII forms:
+///
+/// * dashes and hyphens U+2010–U+2015 and the minus sign U+2212 → `-`
+/// * single quotes U+2018–U+201B → `'`
+/// * double quotes U+201C–U+201F → `"`
+/// * no-break space U+00A0, spaces U+2002–U+200A, narrow no-break space
+///   U+202F, medium mathematical space U+205F and ideographic space U+3000 → ` `
+///
+/// Combined with [`normalize`], this is the comparison key of
+/// `WhitespaceMode::SuperLenient`.
+pub fn super_normalise(s: &str) -> std::string::String {
+    s.trim()
+        .chars()
+        .map(|c| match c {
+            // Various dash / hyphen code-points → ASCII '-'
+            '\u{2010}' | '\u{2011}' | '\u{2012}' | '\u{2013}' | '\u{2014}' | '\u{2015}'
+            | '\u{2212}' => '-',
+            // Fancy single quotes → '\''
+            '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' => '\'',
+            // Fancy double quotes → '"'
+            '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' => '"',
+            // Non-breaking space and other odd spaces → normal space
+            '\u{00A0}' | '\u{2002}' | '\u{2003}' | '\u{2004}' | '\u{2005}' | '\u{2006}'
+            | '\u{2007}' | '\u{2008}' | '\u{2009}' | '\u{200A}' | '\u{202F}' | '\u{205F}'
+            | '\u{3000}' => ' ',
+            other => other,
+        })
+        .collect::<std::string::String>()
+}
+
+/// Levenshtein distance between `a` and `b`: the fewest single-character
+/// insertions, deletions and substitutions turning one into the other,
+/// counted in `char`s. Backs `WhitespaceMode::Fuzzy`.
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: std::vec::Vec<char> = b.chars().collect();
+    let mut previous: std::vec::Vec<usize> = (0..=b.len()).collect();
+    let mut current = std::vec![0; b.len() + 1];
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// Compares two lines exactly as the patcher does under `mode`:
+///
+/// * `Strict` — byte equality.
+/// * `Lenient` — equality after [`normalize`].
+/// * `SuperLenient` — equality after [`normalize`] then [`super_normalise`].
+/// * `Fuzzy` — as `SuperLenient`, or a [`levenshtein`] distance of at most
+///   `max_edit_distance` between the normalized lines.
+pub fn match_lines(a: &str, b: &str, mode: crate::applier::whitespace_mode::WhitespaceMode) -> bool {
+    crate::applier::backtracking_patcher::match_line(a, b, mode)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{levenshtein, match_lines, normalize, super_normalise};
+    use crate::applier::whitespace_mode::WhitespaceMode;
+
+    #[test]
+    fn test_normalize_collapses_whitespace() {
+        assert_eq!(normalize("  hello   world  "), "hello world");
+        assert_eq!(normalize("a"), "a");
+        assert_eq!(normalize(""), "");
+        assert_eq!(normalize("  \t\n  "), "");
+    }
+
+    #[test]
+    fn test_super_normalise_fancy_characters() {
+        assert_eq!(super_normalise("\u{201C}hi\u{201D}"), "\"hi\"");
+        assert_eq!(super_normalise("\u{2018}hi\u{2019}"), "'hi'");
+        assert_eq!(super_normalise("a\u{2014}b"), "a-b");
+        assert_eq!(super_normalise("\u{00A0}hi\u{00A0}"), "hi");
+    }
+
+    #[test]
+    fn test_super_normalise_trims() {
+        assert_eq!(super_normalise("  hello  "), "hello");
+    }
+
+    #[test]
+    fn test_match_lines_follows_mode() {
+        assert!(!match_lines("a  b", "a b", WhitespaceMode::Strict));
+        assert!(match_lines("a  b", "a b", WhitespaceMode::Lenient));
+        assert!(!match_lines("\u{201C}a\u{201D}", "\"a\"", WhitespaceMode::Lenient));
+        assert!(match_lines("\u{201C}a\u{201D}", "\"a\"", WhitespaceMode::SuperLenient));
+        assert!(!match_lines("abc", "abd", WhitespaceMode::SuperLenient));
+        assert!(match_lines("abc", "abd", WhitespaceMode::FUZZY));
+    }
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("", ""), 0);
+        assert_eq!(levenshtein("abc", ""), 3);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("value", "vaule"), 2);
+        assert_eq!(levenshtein("caf\u{e9}", "cafe"), 1);
+    }
+}
@@ -0,0 +1,54 @@
+//! Defines the `VfsExt` extension trait for `Vfs`.
+//!
+//! `Vfs` is a plain `HashMap` alias, so methods on it live in this trait.
+//! Conforms to rust coding guidelines (one item per file).
+
+/// Extension methods for [`crate::vfs::Vfs`].
+pub trait VfsExt {
+    /// The actions that turn `self` into `other`; applying them to `self`
+    /// yields `other`. See [`crate::diff::diff_vfs`].
+    fn diff(&self, other: &crate::vfs::Vfs) -> std::vec::Vec<crate::data::patch_action::PatchAction>;
+
+    /// A frozen copy of `self` to return to later, e.g. for undo. This is a
+    /// full `clone()` of every path and file content, not a shared view.
+    fn snapshot(&self) -> crate::vfs::Vfs;
+}
+
+impl VfsExt for crate::vfs::Vfs {
+    fn diff(&self, other: &crate::vfs::Vfs) -> std::vec::Vec<crate::data::patch_action::PatchAction> {
+        crate::diff::diff_vfs(self, other)
+    }
+
+    fn snapshot(&self) -> crate::vfs::Vfs {
+        self.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VfsExt;
+
+    #[test]
+    fn test_vfs_diff_round_trips_through_apply() {
+        let mut a = crate::vfs::Vfs::new();
+        a.insert("src/main.rs".to_string(), "fn main() {\n    old();\n}\n".to_string());
+        let mut b = a.clone();
+        b.insert("src/main.rs".to_string(), "fn main() {\n    new();\n}\n".to_string());
+        b.insert("README.md".to_string(), "# Title\n".to_string());
+
+        let patch = crate::data::patch::Patch(a.diff(&b));
+        assert_eq!(patch.apply(&a).unwrap(), b);
+    }
+
+    #[test]
+    fn test_snapshot_is_unaffected_by_speculative_apply() {
+        let mut vfs = crate::vfs::Vfs::new();
+        vfs.insert("a.txt".to_string(), "old\n".to_string());
+        let snapshot = vfs.snapshot();
+
+        let patch = "*** Begin Patch\n*** Update File: a.txt\n@@\n-old\n+new\n*** End Patch";
+        let speculative = crate::apply::apply_to_snapshot(patch, &snapshot).unwrap();
+        assert_eq!(speculative["a.txt"], "new\n");
+        assert_eq!(snapshot, vfs);
+    }
+}
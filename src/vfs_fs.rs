@@ -0,0 +1,141 @@
+//! Converts between a `Vfs` and a directory tree on disk.
+//!
+//! `Vfs` stays a plain type alias; these are free functions taking `&Vfs`.
+//! Keys are `/`-separated paths relative to the directory root.
+
+/// Recursively reads every text file under `root` into a `Vfs`.
+///
+/// Files that are not text (containing a NUL byte or not valid UTF-8) are
+/// skipped and returned as one warning per file alongside the `Vfs`, leaving
+/// it to the caller to report them. Symlinked directories are not followed.
+pub fn vfs_from_directory(
+    root: &std::path::Path,
+) -> std::result::Result<(crate::vfs::Vfs, std::vec::Vec<std::string::String>), crate::error::ZenpatchError> {
+    let mut vfs = crate::vfs::Vfs::new();
+    let mut warnings = std::vec::Vec::new();
+    read_dir_into(root, "", &mut vfs, &mut warnings)?;
+    std::result::Result::Ok((vfs, warnings))
+}
+
+/// Writes every `vfs` entry under `root`, creating intermediate directories.
+///
+/// Keys that are absolute or contain `..` are rejected with `IoError` so an
+/// entry cannot be written outside `root`.
+pub fn vfs_to_directory(vfs: &crate::vfs::Vfs, root: &std::path::Path) -> std::result::Result<(), crate::error::ZenpatchError> {
+    for (key, content) in vfs {
+        let relative = std::path::Path::new(key);
+        let escapes = relative
+            .components()
+            .any(|c| !matches!(c, std::path::Component::Normal(_) | std::path::Component::CurDir));
+        if escapes {
+            return std::result::Result::Err(crate::error::ZenpatchError::IoError {
+                message: format!("{}: path must be relative and stay inside {}", key, root.display()),
+                source: std::option::Option::None,
+            });
+        }
+        let path = root.join(relative);
+        if let std::option::Option::Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| crate::fs_apply::io_error(parent, e))?;
+        }
+        std::fs::write(&path, content).map_err(|e| crate::fs_apply::io_error(&path, e))?;
+    }
+    std::result::Result::Ok(())
+}
+
+fn read_dir_into(
+    dir: &std::path::Path,
+    prefix: &str,
+    vfs: &mut crate::vfs::Vfs,
+    warnings: &mut std::vec::Vec<std::string::String>,
+) -> std::result::Result<(), crate::error::ZenpatchError> {
+    let mut entries = std::fs::read_dir(dir)
+        .map_err(|e| crate::fs_apply::io_error(dir, e))?
+        .collect::<std::result::Result<std::vec::Vec<_>, _>>()
+        .map_err(|e| crate::fs_apply::io_error(dir, e))?;
+    entries.sort_by_key(|e| e.file_name());
+
+    for entry in entries {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let key = if prefix.is_empty() { name } else { format!("{}/{}", prefix, name) };
+        let file_type = entry.file_type().map_err(|e| crate::fs_apply::io_error(&path, e))?;
+        if file_type.is_dir() {
+            read_dir_into(&path, &key, vfs, warnings)?;
+        } else if path.is_file() {
+            let bytes = std::fs::read(&path).map_err(|e| crate::fs_apply::io_error(&path, e))?;
+            if bytes.contains(&0) {
+                warnings.push(format!("{}: skipped binary file (contains NUL bytes)", key));
+                continue;
+            }
+            match std::string::String::from_utf8(bytes) {
+                std::result::Result::Ok(content) => {
+                    vfs.insert(key, content);
+                }
+                std::result::Result::Err(_) => {
+                    warnings.push(format!("{}: skipped non-UTF-8 file", key));
+                }
+            }
+        }
+    }
+    std::result::Result::Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{vfs_from_directory, vfs_to_directory};
+    use crate::vfs::Vfs;
+
+    #[test]
+    fn test_vfs_from_directory_reads_nested_text_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src/nested")).unwrap();
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+        std::fs::write(dir.path().join("src/nested/b.rs"), "fn b() {}").unwrap();
+
+        let (vfs, warnings) = vfs_from_directory(dir.path()).unwrap();
+
+        assert!(warnings.is_empty());
+        assert_eq!(vfs.len(), 2);
+        assert_eq!(vfs.get("a.txt").unwrap(), "a");
+        assert_eq!(vfs.get("src/nested/b.rs").unwrap(), "fn b() {}");
+    }
+
+    #[test]
+    fn test_vfs_from_directory_skips_binary_files_with_warning() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("text.txt"), "ok").unwrap();
+        std::fs::write(dir.path().join("nul.bin"), b"a\0b").unwrap();
+        std::fs::write(dir.path().join("latin1.txt"), [0xE9u8, 0x74, 0xE9]).unwrap();
+
+        let (vfs, warnings) = vfs_from_directory(dir.path()).unwrap();
+
+        assert_eq!(vfs.len(), 1);
+        assert!(vfs.contains_key("text.txt"));
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings.iter().any(|w| w.starts_with("nul.bin")));
+        assert!(warnings.iter().any(|w| w.starts_with("latin1.txt")));
+    }
+
+    #[test]
+    fn test_vfs_to_directory_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut vfs = Vfs::new();
+        vfs.insert("a.txt".to_string(), "a\n".to_string());
+        vfs.insert("deep/dir/b.txt".to_string(), "b".to_string());
+
+        vfs_to_directory(&vfs, dir.path()).unwrap();
+
+        assert_eq!(vfs_from_directory(dir.path()).unwrap().0, vfs);
+    }
+
+    #[test]
+    fn test_vfs_to_directory_rejects_escaping_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut vfs = Vfs::new();
+        vfs.insert("../outside.txt".to_string(), "x".to_string());
+        assert!(matches!(
+            vfs_to_directory(&vfs, dir.path()),
+            Err(crate::error::ZenpatchError::IoError { .. })
+        ));
+    }
+}
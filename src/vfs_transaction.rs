@@ -0,0 +1,86 @@
+//! Defines `VfsTransaction`, which applies patches tentatively on top of a
+//! snapshot that can be restored.
+//!
+//! Conforms to rust coding guidelines (one item per file).
+
+/// A working [`crate::vfs::Vfs`] plus the state it started from.
+///
+/// Each [`VfsTransaction::apply_patch`] is atomic on its own (a failing patch
+/// leaves the working VFS as it was); [`VfsTransaction::commit`] keeps every
+/// change made since construction and [`VfsTransaction::rollback`] discards
+/// them all.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VfsTransaction {
+    vfs: crate::vfs::Vfs,
+    snapshot: crate::vfs::Vfs,
+}
+
+impl VfsTransaction {
+    /// Starts a transaction on `vfs`, snapshotting it for rollback.
+    pub fn new(vfs: crate::vfs::Vfs) -> Self {
+        Self { snapshot: vfs.clone(), vfs }
+    }
+
+    /// Applies `patch_text` to the working VFS with [`crate::apply::apply`].
+    pub fn apply_patch(&mut self, patch_text: &str) -> std::result::Result<(), crate::error::ZenpatchError> {
+        self.vfs = crate::apply::apply(patch_text, &self.vfs)?;
+        std::result::Result::Ok(())
+    }
+
+    /// The working VFS, including every patch applied so far.
+    pub fn vfs(&self) -> &crate::vfs::Vfs {
+        &self.vfs
+    }
+
+    /// Ends the transaction, keeping the changes.
+    pub fn commit(self) -> crate::vfs::Vfs {
+        self.vfs
+    }
+
+    /// Ends the transaction, discarding the changes.
+    pub fn rollback(self) -> crate::vfs::Vfs {
+        self.snapshot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VfsTransaction;
+
+    fn start() -> VfsTransaction {
+        let mut vfs = crate::vfs::Vfs::new();
+        vfs.insert("a.txt".to_string(), "one\n".to_string());
+        VfsTransaction::new(vfs)
+    }
+
+    const PATCH_1: &str = "*** Begin Patch\n*** Update File: a.txt\n@@\n-one\n+two\n*** End Patch";
+    const PATCH_2: &str = "*** Begin Patch\n*** Add File: b.txt\n+b\n*** End Patch";
+
+    #[test]
+    fn test_commit_keeps_all_patches() {
+        let mut tx = start();
+        tx.apply_patch(PATCH_1).unwrap();
+        tx.apply_patch(PATCH_2).unwrap();
+        let vfs = tx.commit();
+        assert_eq!(vfs.get("a.txt").unwrap(), "two\n");
+        assert_eq!(vfs.get("b.txt").unwrap(), "b");
+    }
+
+    #[test]
+    fn test_rollback_restores_snapshot() {
+        let mut tx = start();
+        tx.apply_patch(PATCH_1).unwrap();
+        assert_eq!(tx.vfs().get("a.txt").unwrap(), "two\n");
+        let vfs = tx.rollback();
+        assert_eq!(vfs.get("a.txt").unwrap(), "one\n");
+        assert!(!vfs.contains_key("b.txt"));
+    }
+
+    #[test]
+    fn test_failed_patch_leaves_working_vfs_unchanged() {
+        let mut tx = start();
+        tx.apply_patch(PATCH_1).unwrap();
+        assert!(tx.apply_patch(PATCH_1).is_err());
+        assert_eq!(tx.vfs().get("a.txt").unwrap(), "two\n");
+    }
+}
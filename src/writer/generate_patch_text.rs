@@ -0,0 +1,31 @@
+//! Provides `generate_patch_text`, the inverse of `text_to_patch`.
+//!
+//! Adheres to the one-item-per-file rule and uses fully qualified paths.
+
+/// Renders `actions` as patch text that [`crate::parser::text_to_patch::text_to_patch`]
+/// parses back to equal actions; the name mirrors the parser entry point.
+///
+/// Same output as [`crate::writer::patch_to_text::patch_to_text`]. An empty
+/// slice gives `*** Begin Patch\n*** End Patch`.
+pub fn generate_patch_text(actions: &[crate::data::patch_action::PatchAction]) -> std::string::String {
+    crate::writer::patch_to_text::patch_to_text(actions)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_empty_slice_gives_minimal_patch() {
+        assert_eq!(super::generate_patch_text(&[]), "*** Begin Patch\n*** End Patch");
+    }
+
+    #[test]
+    fn test_generated_text_applies_like_the_original() {
+        let text = "*** Begin Patch\n*** Update File: a.txt\n*** Move to: b.txt\n@@ fn main\n a\n-b\n+B\n*** Add File: c.txt\n+c\n*** End Patch";
+        let mut vfs = crate::vfs::Vfs::new();
+        vfs.insert("a.txt".to_string(), "fn main\na\nb\n".to_string());
+
+        let actions = crate::parser::text_to_patch::text_to_patch(text).unwrap();
+        let generated = super::generate_patch_text(&actions);
+        assert_eq!(crate::apply::apply(&generated, &vfs).unwrap(), crate::apply::apply(text, &vfs).unwrap());
+    }
+}
@@ -0,0 +1,5 @@
+//! Turns parsed patches back into zenpatch text; the inverse of `parser`.
+
+pub mod generate_patch_text;
+pub mod patch_to_text;
+pub mod patch_to_text_with_metadata;
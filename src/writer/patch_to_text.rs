@@ -0,0 +1,23 @@
+//! Provides the `patch_to_text` function for rendering actions as patch text.
+//!
+//! Adheres to the one-item-per-file rule and uses fully qualified paths.
+
+/// Renders `actions` as a complete patch: `*** Begin Patch`, each action in
+/// its `Display` form, then `*** End Patch`. The output parses back with
+/// [`crate::parser::text_to_patch::text_to_patch`] to equal actions.
+pub fn patch_to_text(actions: &[crate::data::patch_action::PatchAction]) -> std::string::String {
+    crate::writer::patch_to_text_with_metadata::patch_to_text_with_metadata(
+        actions,
+        &crate::data::patch_metadata::PatchMetadata::default(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_patch_to_text_round_trips() {
+        let text = "*** Begin Patch\n*** Update File: a.txt\n@@\n ctx\n-old\n+new\n*** Delete File: b.txt\n-b\n*** End Patch";
+        let actions = crate::parser::text_to_patch::text_to_patch(text).unwrap();
+        assert_eq!(super::patch_to_text(&actions), text);
+    }
+}
@@ -0,0 +1,57 @@
+//! Provides `patch_to_text_with_metadata`, the inverse of
+//! `text_to_patch_with_metadata`.
+//!
+//! Adheres to the one-item-per-file rule and uses fully qualified paths.
+
+/// Renders `actions` as a complete patch with `metadata` written after
+/// `*** Begin Patch`: the `*** Author:`, `*** Date:`, `*** Subject:` and
+/// `*** Description:` headers that are set, then one `*** Comment:` line per
+/// comment. Comments therefore come back in order but no longer interleaved
+/// with the actions they were next to.
+pub fn patch_to_text_with_metadata(
+    actions: &[crate::data::patch_action::PatchAction],
+    metadata: &crate::data::patch_metadata::PatchMetadata,
+) -> std::string::String {
+    let mut lines = std::vec![std::string::String::from("*** Begin Patch")];
+    let headers = [
+        ("Author", &metadata.author),
+        ("Date", &metadata.date),
+        ("Subject", &metadata.subject),
+        ("Description", &metadata.description),
+    ];
+    for (name, value) in headers {
+        if let std::option::Option::Some(value) = value {
+            lines.push(std::format!("*** {}: {}", name, value));
+        }
+    }
+    lines.extend(metadata.comments.iter().map(|comment| std::format!("*** Comment: {}", comment)));
+    lines.extend(actions.iter().map(std::string::ToString::to_string));
+    lines.push(std::string::String::from("*** End Patch"));
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_metadata_round_trips() {
+        let metadata = crate::data::patch_metadata::PatchMetadata {
+            comments: vec!["first".to_string(), "second".to_string()],
+            author: Some("Ada <ada@example.com>".to_string()),
+            date: Some("2024-03-01".to_string()),
+            subject: Some("Tidy up".to_string()),
+            description: None,
+        };
+        let actions = crate::parser::text_to_patch::text_to_patch(
+            "*** Begin Patch\n*** Add File: a.txt\n+a\n*** End Patch",
+        )
+        .unwrap();
+
+        let text = super::patch_to_text_with_metadata(&actions, &metadata);
+        let (parsed_actions, parsed_metadata) =
+            crate::parser::text_to_patch_with_metadata::text_to_patch_with_metadata(&text).unwrap();
+
+        assert_eq!(parsed_actions, actions);
+        assert_eq!(parsed_metadata, metadata);
+        assert!(text.starts_with("*** Begin Patch\n*** Author: Ada <ada@example.com>\n*** Date: 2024-03-01\n"));
+    }
+}